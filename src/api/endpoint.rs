@@ -0,0 +1,201 @@
+use super::http;
+use crate::libs::{config::ConfigModule, error::KaslError, secret::Secret};
+use chrono::{Duration, NaiveDate};
+use dialoguer::{theme::ColorfulTheme, Input, Select};
+use reqwest::{Client, Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::BTreeMap, error::Error};
+
+pub const SECRET_FILE: &str = ".endpoint_secret";
+pub const SECRET_PROMPT: &str = "Enter the token or password of the reporting endpoint";
+/// Sent when no template is configured: the date, the daily total in minutes and the intervals as kasl builds them.
+const DEFAULT_BODY_TEMPLATE: &str = r#"{"date": "{{date}}", "total_minutes": {{total_minutes}}, "intervals": {{intervals}}}"#;
+
+/// How requests to the endpoint are authenticated; the token or password is kept as a secret, not in the config.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EndpointAuth {
+    None,
+    Bearer,
+    Basic,
+    Header,
+}
+
+/// Submits daily reports to a company endpoint that is not SiServer.
+pub struct Endpoint {
+    client: Client,
+    config: EndpointConfig,
+}
+
+impl Endpoint {
+    pub fn new(config: &EndpointConfig) -> Self {
+        Self {
+            client: Client::new(),
+            config: config.clone(),
+        }
+    }
+
+    /// Renders the body template for `date` and sends it; `intervals` is the JSON array also stored in the report history.
+    pub async fn send(&self, intervals: &str, date: &NaiveDate, total: Duration) -> Result<StatusCode, Box<dyn Error>> {
+        let body = self.config.render(intervals, date, total)?;
+        let method = Method::from_bytes(self.config.method.to_uppercase().as_bytes())?;
+        let secret = match self.config.auth {
+            EndpointAuth::None => None,
+            _ => Some(Secret::new(SECRET_FILE, SECRET_PROMPT).get_or_prompt()?),
+        };
+        let res = http::send(|| {
+            let mut request = self.client.request(method.clone(), &self.config.url).json(&body);
+            for (name, value) in self.config.headers.iter() {
+                request = request.header(name, value);
+            }
+            match (self.config.auth, &secret) {
+                (EndpointAuth::Bearer, Some(token)) => request.bearer_auth(token),
+                (EndpointAuth::Basic, Some(password)) => request.basic_auth(self.config.username.as_deref().unwrap_or_default(), Some(password)),
+                (EndpointAuth::Header, Some(value)) => request.header(self.config.auth_header.as_deref().unwrap_or("Authorization"), value),
+                _ => request,
+            }
+        })
+        .await?;
+
+        Ok(res.status())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EndpointConfig {
+    pub url: String,
+    #[serde(default = "EndpointConfig::default_method")]
+    pub method: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    pub auth: EndpointAuth,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_header: Option<String>,
+    #[serde(default = "EndpointConfig::default_body_template")]
+    pub body_template: String,
+}
+
+impl EndpointConfig {
+    pub fn module() -> ConfigModule {
+        ConfigModule {
+            key: "endpoint".to_string(),
+            name: "Custom reporting endpoint".to_string(),
+        }
+    }
+
+    fn default_method() -> String {
+        "POST".to_string()
+    }
+
+    fn default_body_template() -> String {
+        DEFAULT_BODY_TEMPLATE.to_string()
+    }
+
+    pub fn init(config: &Option<Self>) -> Result<Self, Box<dyn Error>> {
+        let config = config.clone().unwrap_or(Self {
+            url: "".to_string(),
+            method: Self::default_method(),
+            headers: BTreeMap::new(),
+            auth: EndpointAuth::Bearer,
+            username: None,
+            auth_header: None,
+            body_template: Self::default_body_template(),
+        });
+        let schemes = [EndpointAuth::None, EndpointAuth::Bearer, EndpointAuth::Basic, EndpointAuth::Header];
+        println!("Custom reporting endpoint settings");
+        let url = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Enter the URL reports are sent to")
+            .default(config.url)
+            .interact_text()?;
+        let method = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Enter the HTTP method")
+            .default(config.method)
+            .interact_text()?;
+        let auth = schemes[Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select the authentication scheme")
+            .items(&["None", "Bearer token", "Basic (username and password)", "Token in a custom header"])
+            .default(schemes.iter().position(|scheme| *scheme == config.auth).unwrap_or(1))
+            .interact()?];
+        let username = match auth {
+            EndpointAuth::Basic => Some(
+                Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Enter the username")
+                    .default(config.username.unwrap_or_default())
+                    .interact_text()?,
+            ),
+            _ => None,
+        };
+        let auth_header = match auth {
+            EndpointAuth::Header => Some(
+                Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Enter the name of the header carrying the token")
+                    .default(config.auth_header.unwrap_or("X-Api-Key".to_string()))
+                    .interact_text()?,
+            ),
+            _ => None,
+        };
+        let headers: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Enter extra headers as `Name: value`, separated by `;` (optional)")
+            .default(
+                config
+                    .headers
+                    .iter()
+                    .map(|(name, value)| format!("{}: {}", name, value))
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            )
+            .allow_empty(true)
+            .interact_text()?;
+        let body_template: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Enter the JSON body template ({{date}}, {{total}}, {{total_minutes}}, {{intervals}})")
+            .default(config.body_template)
+            .validate_with(|template: &String| Self::validate_template(template).map_err(|e| e.to_string()))
+            .interact_text()?;
+
+        Ok(Self {
+            url,
+            method,
+            headers: Self::parse_headers(&headers)?,
+            auth,
+            username,
+            auth_header,
+            body_template,
+        })
+    }
+
+    fn parse_headers(headers: &str) -> Result<BTreeMap<String, String>, Box<dyn Error>> {
+        let mut parsed = BTreeMap::new();
+        for header in headers.split(';').map(str::trim).filter(|header| !header.is_empty()) {
+            let (name, value) = header
+                .split_once(':')
+                .ok_or_else(|| KaslError::validation(format!("Invalid header `{}`, expected `Name: value`", header)))?;
+            parsed.insert(name.trim().to_string(), value.trim().to_string());
+        }
+
+        Ok(parsed)
+    }
+
+    fn validate_template(template: &str) -> Result<(), Box<dyn Error>> {
+        Self::fill(template, "[]", &NaiveDate::default(), Duration::zero())?;
+
+        Ok(())
+    }
+
+    /// Replaces the placeholders and parses the result, so a broken template fails before anything is sent.
+    fn fill(template: &str, intervals: &str, date: &NaiveDate, total: Duration) -> Result<Value, Box<dyn Error>> {
+        let body = template
+            .replace("{{date}}", &date.format("%Y-%m-%d").to_string())
+            .replace("{{total}}", &format!("{:02}:{:02}", total.num_hours(), total.num_minutes() % 60))
+            .replace("{{total_minutes}}", &total.num_minutes().to_string())
+            .replace("{{intervals}}", intervals);
+
+        Ok(serde_json::from_str(&body).map_err(|e| KaslError::config(format!("The body template is not valid JSON: {}", e)))?)
+    }
+
+    pub fn render(&self, intervals: &str, date: &NaiveDate, total: Duration) -> Result<Value, Box<dyn Error>> {
+        Self::fill(&self.body_template, intervals, date, total)
+    }
+}
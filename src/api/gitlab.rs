@@ -1,8 +1,9 @@
-use crate::libs::config::ConfigModule;
+use super::http::SendWithRetry;
+use crate::libs::{config::ConfigModule, error::KaslError};
 use chrono::{Duration, Local};
-use dialoguer::{theme::ColorfulTheme, Input};
+use dialoguer::{theme::ColorfulTheme, Confirm, Input};
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::error::Error;
 
 #[derive(Debug)]
@@ -27,17 +28,21 @@ struct PushData {
 pub struct CommitInfo {
     pub sha: String,
     pub message: String,
+    pub url: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 struct Commit {
     id: String,
     message: String,
+    web_url: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 struct User {
     id: u32,
+    #[serde(default)]
+    username: String,
 }
 
 impl GitLab {
@@ -50,11 +55,32 @@ impl GitLab {
 
     pub async fn get_user_id(&self) -> Result<u32, reqwest::Error> {
         let url = format!("{}/api/v4/user", self.config.api_url);
-        let response = self.client.get(&url).header("PRIVATE-TOKEN", &self.config.access_token).send().await?;
+        let response = self
+            .client
+            .get(&url)
+            .header("PRIVATE-TOKEN", &self.config.access_token)
+            .send_with_retry()
+            .await?;
 
         Ok(response.json::<User>().await?.id)
     }
 
+    /// Authenticated read of the current user for `kasl api test`.
+    pub async fn check(&self) -> Result<String, Box<dyn Error>> {
+        let url = format!("{}/api/v4/user", self.config.api_url);
+        let response = self
+            .client
+            .get(&url)
+            .header("PRIVATE-TOKEN", &self.config.access_token)
+            .send_with_retry()
+            .await?;
+        if !response.status().is_success() {
+            return Err(KaslError::network(format!("GitLab user request failed with status {}", response.status())));
+        }
+
+        Ok(format!("signed in as {}", response.json::<User>().await?.username))
+    }
+
     pub async fn get_today_commits(&self) -> Result<Vec<CommitInfo>, reqwest::Error> {
         let today = Local::now();
         let yesterday = (today - Duration::days(1)).format("%Y-%m-%d").to_string();
@@ -64,7 +90,12 @@ impl GitLab {
             "{}/api/v4/users/{}/events?after={}&before={}",
             self.config.api_url, user_id, yesterday, tomorrow
         );
-        let response = self.client.get(&url).header("PRIVATE-TOKEN", &self.config.access_token).send().await?;
+        let response = self
+            .client
+            .get(&url)
+            .header("PRIVATE-TOKEN", &self.config.access_token)
+            .send_with_retry()
+            .await?;
         let mut commits_info = Vec::new();
 
         for event in response.json::<Vec<Event>>().await? {
@@ -79,6 +110,7 @@ impl GitLab {
                             .map(|(part, _)| part)
                             .unwrap_or(&commit_detail.message)
                             .to_string(),
+                        url: commit_detail.web_url,
                     });
                 }
             }
@@ -89,7 +121,12 @@ impl GitLab {
 
     async fn get_commit_detail(&self, project_id: u32, commit_sha: &str) -> Result<Commit, reqwest::Error> {
         let url = format!("{}/api/v4/projects/{}/repository/commits/{}", self.config.api_url, project_id, commit_sha);
-        let response = self.client.get(&url).header("PRIVATE-TOKEN", &self.config.access_token).send().await?;
+        let response = self
+            .client
+            .get(&url)
+            .header("PRIVATE-TOKEN", &self.config.access_token)
+            .send_with_retry()
+            .await?;
 
         Ok(response.json::<Commit>().await?)
     }
@@ -97,10 +134,19 @@ impl GitLab {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct GitLabConfig {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
     pub access_token: String,
     pub api_url: String,
 }
 
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum GitLabConfigs {
+    One(GitLabConfig),
+    Many(Vec<GitLabConfig>),
+}
+
 impl GitLabConfig {
     pub fn module() -> ConfigModule {
         ConfigModule {
@@ -108,16 +154,61 @@ impl GitLabConfig {
             name: "GitLab".to_string(),
         }
     }
+
+    /// Accepts both a single GitLab object (older configs) and an array of instances.
+    pub fn deserialize_list<'de, D>(deserializer: D) -> Result<Vec<Self>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(match Option::<GitLabConfigs>::deserialize(deserializer)? {
+            Some(GitLabConfigs::One(config)) => vec![config],
+            Some(GitLabConfigs::Many(configs)) => configs,
+            None => vec![],
+        })
+    }
+
+    pub fn label(&self) -> &str {
+        match self.name.is_empty() {
+            true => &self.api_url,
+            false => &self.name,
+        }
+    }
+
+    pub fn init_list(configs: &[GitLabConfig]) -> Result<Vec<Self>, Box<dyn Error>> {
+        let mut instances = vec![];
+        for config in configs {
+            instances.push(Self::init(&Some(config.clone()))?);
+        }
+        if instances.is_empty() {
+            instances.push(Self::init(&None)?);
+        }
+        while Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt("Add another GitLab instance?")
+            .default(false)
+            .interact()?
+        {
+            instances.push(Self::init(&None)?);
+        }
+
+        Ok(instances)
+    }
+
     pub fn init(config: &Option<GitLabConfig>) -> Result<Self, Box<dyn Error>> {
         let config = config
             .clone()
             .or(Some(Self {
+                name: "".to_string(),
                 access_token: "".to_string(),
                 api_url: "".to_string(),
             }))
             .unwrap();
         println!("GitLab settings");
         Ok(Self {
+            name: Input::with_theme(&ColorfulTheme::default())
+                .allow_empty(true)
+                .with_prompt("Enter a name for this GitLab instance")
+                .default(config.name)
+                .interact_text()?,
             access_token: Input::with_theme(&ColorfulTheme::default())
                 .with_prompt("Enter your GitLab private token")
                 .default(config.access_token)
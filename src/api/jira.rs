@@ -1,19 +1,22 @@
-use super::Session;
-use crate::libs::{config::ConfigModule, secret::Secret};
+use super::{http::SendWithRetry, Session};
+use crate::libs::{config::ConfigModule, error::KaslError, secret::Secret};
 use chrono::NaiveDate;
 use dialoguer::{theme::ColorfulTheme, Input};
 use reqwest::{
-    header::{HeaderMap, HeaderValue, COOKIE},
-    Client, StatusCode,
+    header::{HeaderValue, COOKIE},
+    Client, Response, StatusCode,
 };
 use serde::{Deserialize, Serialize};
-use std::{error::Error, time::Duration};
+use std::error::Error;
 
 const MAX_RETRY_COUNT: i32 = 3;
-const SESSION_ID_FILE: &str = ".jira_session_id";
-const SECRET_FILE: &str = ".jira_secret";
+pub const SESSION_ID_FILE: &str = ".jira_session_id";
+pub const SECRET_FILE: &str = ".jira_secret";
+pub const SECRET_PROMPT: &str = "Enter your Jira password";
 const AUTH_URL: &str = "rest/auth/1/session";
 const SEARCH_URL: &str = "rest/api/2/search";
+const MYSELF_URL: &str = "rest/api/2/myself";
+const DEFAULT_JQL: &str = "status in (Done, Решена) AND resolved >= \"{date}\" AND resolved <= \"{date} 23:59\" AND assignee in (currentUser())";
 
 #[derive(Serialize, Clone, Debug)]
 pub struct LoginCredentials {
@@ -57,6 +60,12 @@ pub struct JiraSearchResults {
     pub issues: Vec<JiraIssue>,
 }
 
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct JiraUser {
+    display_name: String,
+}
+
 #[derive(Debug)]
 pub struct Jira {
     client: Client,
@@ -69,7 +78,7 @@ impl Session for Jira {
     async fn login(&self) -> Result<String, Box<dyn Error>> {
         let credentials = self.credentials.clone().expect("Credentials not set!");
         let auth_url = format!("{}/{}", self.config.api_url, AUTH_URL);
-        let auth_res = self.client.post(auth_url).json(&credentials).send().await?;
+        let auth_res = self.client.post(auth_url).json(&credentials).send_with_retry().await?;
 
         if !auth_res.status().is_success() {
             return Err(Box::new(std::io::Error::new(std::io::ErrorKind::Other, "Jira authenticate failed")));
@@ -93,7 +102,7 @@ impl Session for Jira {
     }
 
     fn secret(&self) -> Secret {
-        Secret::new(SECRET_FILE, "Enter your Jira password")
+        Secret::new(SECRET_FILE, SECRET_PROMPT)
     }
 
     fn retry(&self) -> i32 {
@@ -115,41 +124,62 @@ impl Jira {
         }
     }
 
-    pub async fn get_completed_issues(&mut self, date: &NaiveDate) -> Result<Vec<JiraIssue>, Box<dyn Error>> {
+    pub fn issue_url(&self, key: &str) -> String {
+        format!("{}/browse/{}", self.config.api_url.trim_end_matches('/'), key)
+    }
+
+    /// Sends a GET request with the session cookie, logging in again when the session has expired.
+    async fn get(&mut self, path: &str, query: &[(&str, &str)]) -> Result<Response, Box<dyn Error>> {
+        let url = format!("{}/{}", &self.config.api_url, path);
         loop {
             let session_id = self.get_session_id().await?;
-            let date = date.format("%Y-%m-%d").to_string();
-            let jql = format!(
-                "status in (Done, Решена) AND resolved >= \"{}\" AND resolved <= \"{} 23:59\" AND assignee in (currentUser())",
-                &date, &date
-            );
-
-            let mut headers = HeaderMap::new();
-            headers.insert(COOKIE, HeaderValue::from_str(&session_id)?);
-            let url = format!("{}/{}?jql={}", &self.config.api_url, SEARCH_URL, &jql);
-
-            let res = self.client.get(&url).headers(headers).send().await?;
+            let res = self
+                .client
+                .get(&url)
+                .query(query)
+                .header(COOKIE, HeaderValue::from_str(&session_id)?)
+                .send_with_retry()
+                .await?;
 
             match res.status() {
                 StatusCode::UNAUTHORIZED if self.retries < MAX_RETRY_COUNT => {
                     self.delete_session_id()?;
-                    tokio::time::sleep(Duration::from_secs(1)).await;
                     self.retries += 1;
-                    continue;
-                }
-                _ => {
-                    let search_results = res.json::<JiraSearchResults>().await?;
-                    return Ok(search_results.issues);
                 }
+                _ => return Ok(res),
             }
         }
     }
+
+    /// Logs in and reads the current user for `kasl api test`.
+    pub async fn check(&mut self) -> Result<String, Box<dyn Error>> {
+        let res = self.get(MYSELF_URL, &[]).await?;
+        if !res.status().is_success() {
+            return Err(KaslError::network(format!("Jira user request failed with status {}", res.status())));
+        }
+
+        Ok(format!("signed in as {}", res.json::<JiraUser>().await?.display_name))
+    }
+
+    /// Searches issues with the given JQL, falling back to the configured one and then to
+    /// issues resolved on `date`. A `{date}` placeholder in the JQL is replaced with `date`.
+    pub async fn get_completed_issues(&mut self, date: &NaiveDate, jql: Option<&str>) -> Result<Vec<JiraIssue>, Box<dyn Error>> {
+        let jql = jql
+            .or(self.config.jql.as_deref())
+            .unwrap_or(DEFAULT_JQL)
+            .replace("{date}", &date.format("%Y-%m-%d").to_string());
+        let search_results = self.get(SEARCH_URL, &[("jql", &jql)]).await?.json::<JiraSearchResults>().await?;
+
+        Ok(search_results.issues)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct JiraConfig {
     pub login: String,
     pub api_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jql: Option<String>,
 }
 
 impl JiraConfig {
@@ -165,9 +195,15 @@ impl JiraConfig {
             .or(Some(Self {
                 login: "".to_string(),
                 api_url: "".to_string(),
+                jql: None,
             }))
             .unwrap();
         println!("Jira settings");
+        let jql: String = Input::with_theme(&ColorfulTheme::default())
+            .allow_empty(true)
+            .with_prompt("Enter a custom JQL for task import (empty for default)")
+            .default(config.jql.unwrap_or_default())
+            .interact_text()?;
         Ok(Self {
             login: Input::with_theme(&ColorfulTheme::default())
                 .with_prompt("Enter your Jira login")
@@ -177,6 +213,7 @@ impl JiraConfig {
                 .with_prompt("Enter the Jira API URL")
                 .default(config.api_url)
                 .interact_text()?,
+            jql: Some(jql).filter(|jql| !jql.is_empty()),
         })
     }
 }
@@ -0,0 +1,300 @@
+use super::http::SendWithRetry;
+use crate::libs::{config::ConfigModule, error::KaslError, oauth::OAuth, secret::Secret, task::Task};
+use chrono::NaiveDate;
+use dialoguer::{theme::ColorfulTheme, Input};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+
+const API_URL: &str = "https://api.notion.com/v1";
+const API_VERSION: &str = "2022-06-28";
+const PAGES_URL: &str = "pages";
+const PAGE_URL: &str = "pages/{page_id}";
+const ME_URL: &str = "users/me";
+const QUERY_URL: &str = "databases/{database_id}/query";
+/// `source` of tasks imported from Notion; their `external_id` is the page ID.
+pub const SOURCE: &str = "notion";
+pub const SECRET_FILE: &str = ".notion_secret";
+pub const SECRET_PROMPT: &str = "Enter your Notion integration token";
+const AUTHORIZE_URL: &str = "https://api.notion.com/v1/oauth/authorize";
+const TOKEN_URL: &str = "https://api.notion.com/v1/oauth/token";
+pub const CLIENT_SECRET_FILE: &str = ".notion_client_secret";
+pub const CLIENT_SECRET_PROMPT: &str = "Enter the OAuth client secret of your Notion integration";
+pub const OAUTH_TOKEN_FILE: &str = ".notion_oauth_token";
+
+#[derive(Debug, Deserialize)]
+struct QueryResults {
+    results: Vec<Page>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Page {
+    id: String,
+    #[serde(default)]
+    url: Option<String>,
+    properties: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    #[serde(default)]
+    name: Option<String>,
+    /// For a bot user, who installed or signed in to the integration.
+    #[serde(default)]
+    bot: Value,
+}
+
+/// What `push_task` did with a task.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PushResult {
+    Created,
+    Updated,
+}
+
+#[derive(Debug)]
+pub struct Notion {
+    client: Client,
+    config: NotionConfig,
+}
+
+impl Notion {
+    pub fn new(config: &NotionConfig) -> Self {
+        Self {
+            client: Client::new(),
+            config: config.clone(),
+        }
+    }
+
+    /// Integration token, or an OAuth access token when a public integration's client ID is configured.
+    async fn token(&self) -> Result<String, Box<dyn Error>> {
+        match &self.config.oauth_client_id {
+            Some(client_id) => {
+                OAuth {
+                    authorize_url: AUTHORIZE_URL.to_string(),
+                    token_url: TOKEN_URL.to_string(),
+                    client_id: client_id.clone(),
+                    scopes: vec![],
+                    extra_params: vec![("owner".to_string(), "user".to_string())],
+                    client_secret: Secret::new(CLIENT_SECRET_FILE, CLIENT_SECRET_PROMPT),
+                    token_file: OAUTH_TOKEN_FILE,
+                }
+                .access_token()
+                .await
+            }
+            None => Secret::new(SECRET_FILE, SECRET_PROMPT).get_or_prompt(),
+        }
+    }
+
+    async fn me(&self) -> Result<User, Box<dyn Error>> {
+        let url = format!("{}/{}", API_URL, ME_URL);
+        let res = self
+            .client
+            .get(url)
+            .bearer_auth(self.token().await?)
+            .header("Notion-Version", API_VERSION)
+            .send_with_retry()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(KaslError::network(format!("Notion user request failed with status {}", res.status())));
+        }
+
+        Ok(res.json::<User>().await?)
+    }
+
+    /// Reads the integration's own user for `kasl api test`.
+    pub async fn check(&self) -> Result<String, Box<dyn Error>> {
+        Ok(format!("integration {}", self.me().await?.name.unwrap_or_default()))
+    }
+
+    /// The configured user ID, or the user who signed in to a public integration.
+    async fn user_id(&self) -> Result<String, Box<dyn Error>> {
+        if let Some(user_id) = &self.config.user_id {
+            return Ok(user_id.clone());
+        }
+        let me = self.me().await?;
+
+        me.bot["owner"]["user"]["id"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| KaslError::config("The Notion integration does not belong to a user, enter your Notion user ID in `kasl init`").into())
+    }
+
+    /// Creates a page for `task`, or updates the completeness of the page it was imported from or already pushed to
+    /// on `date`, so pushing twice does not duplicate it.
+    pub async fn push_task(&self, task: &Task, date: &NaiveDate) -> Result<PushResult, Box<dyn Error>> {
+        let page_id = match (task.source.as_deref(), &task.external_id) {
+            (Some(SOURCE), Some(page_id)) => Some(page_id.clone()),
+            _ => self.find_page(&task.name, date).await?,
+        };
+        let completeness = json!({ "number": task.completeness.unwrap_or(100) });
+        let (request, result) = match page_id {
+            Some(page_id) => {
+                let url = format!("{}/{}", API_URL, PAGE_URL.replace("{page_id}", &page_id));
+                let body = json!({ "properties": { self.config.completeness_property.as_str(): completeness } });
+                (self.client.patch(url).json(&body), PushResult::Updated)
+            }
+            None => {
+                let url = format!("{}/{}", API_URL, PAGES_URL);
+                let body = json!({
+                    "parent": { "database_id": self.config.database_id },
+                    "properties": {
+                        self.config.title_property.as_str(): { "title": [{ "text": { "content": task.name } }] },
+                        self.config.completeness_property.as_str(): completeness,
+                        self.config.date_property.as_str(): { "date": { "start": date.format("%Y-%m-%d").to_string() } },
+                    }
+                });
+                (self.client.post(url).json(&body), PushResult::Created)
+            }
+        };
+        let res = request
+            .bearer_auth(self.token().await?)
+            .header("Notion-Version", API_VERSION)
+            .send_with_retry()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(KaslError::network(format!("Notion page update failed with status {}", res.status())));
+        }
+
+        Ok(result)
+    }
+
+    /// The ID of a page titled `name` on `date`.
+    async fn find_page(&self, name: &str, date: &NaiveDate) -> Result<Option<String>, Box<dyn Error>> {
+        let filter = json!({
+            "and": [
+                { "property": self.config.title_property, "title": { "equals": name } },
+                { "property": self.config.date_property, "date": { "equals": date.format("%Y-%m-%d").to_string() } },
+            ]
+        });
+
+        Ok(self.query(filter).await?.into_iter().next().map(|page| page.id))
+    }
+
+    async fn query(&self, filter: Value) -> Result<Vec<Page>, Box<dyn Error>> {
+        let url = format!("{}/{}", API_URL, QUERY_URL.replace("{database_id}", &self.config.database_id));
+        let res = self
+            .client
+            .post(url)
+            .bearer_auth(self.token().await?)
+            .header("Notion-Version", API_VERSION)
+            .json(&json!({ "filter": filter }))
+            .send_with_retry()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(KaslError::network(format!("Notion database query failed with status {}", res.status())));
+        }
+
+        Ok(res.json::<QueryResults>().await?.results)
+    }
+
+    /// Fetches database items whose completeness is below 100 as local incomplete tasks, linked to their pages.
+    /// With an assignee property configured, only items assigned to the current user are returned.
+    pub async fn get_incomplete_tasks(&self) -> Result<Vec<Task>, Box<dyn Error>> {
+        let incomplete = json!({ "property": self.config.completeness_property, "number": { "less_than": 100 } });
+        let filter = match &self.config.assignee_property {
+            Some(assignee_property) => json!({
+                "and": [incomplete, { "property": assignee_property, "people": { "contains": self.user_id().await? } }]
+            }),
+            None => incomplete,
+        };
+
+        Ok(self
+            .query(filter)
+            .await?
+            .iter()
+            .filter_map(|page| {
+                let name = page.properties[&self.config.title_property]["title"]
+                    .as_array()?
+                    .iter()
+                    .filter_map(|text| text["plain_text"].as_str())
+                    .collect::<String>();
+                let completeness = page.properties[&self.config.completeness_property]["number"].as_i64().map(|c| c as i32);
+                Some(Task::new(&name, "", completeness.or(Some(0))).with_external(SOURCE, &page.id, page.url.clone()))
+            })
+            .collect())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NotionConfig {
+    pub database_id: String,
+    pub title_property: String,
+    pub completeness_property: String,
+    pub date_property: String,
+    /// Client ID of a public integration; when set, kasl signs in with OAuth instead of an integration token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oauth_client_id: Option<String>,
+    /// People property naming who an item is assigned to; when set, only your items are offered as incomplete tasks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee_property: Option<String>,
+    /// Your Notion user ID, needed for the assignee filter with an internal integration token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+}
+
+impl NotionConfig {
+    pub fn module() -> ConfigModule {
+        ConfigModule {
+            key: "notion".to_string(),
+            name: "Notion".to_string(),
+        }
+    }
+    pub fn init(config: &Option<Self>) -> Result<Self, Box<dyn Error>> {
+        let config = config.clone().unwrap_or(Self {
+            database_id: "".to_string(),
+            title_property: "Name".to_string(),
+            completeness_property: "Completeness".to_string(),
+            date_property: "Date".to_string(),
+            oauth_client_id: None,
+            assignee_property: None,
+            user_id: None,
+        });
+        println!("Notion settings");
+        Ok(Self {
+            database_id: Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Enter the Notion database ID")
+                .default(config.database_id)
+                .interact_text()?,
+            title_property: Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Enter the title property name")
+                .default(config.title_property)
+                .interact_text()?,
+            completeness_property: Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Enter the completeness (number) property name")
+                .default(config.completeness_property)
+                .interact_text()?,
+            date_property: Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Enter the date property name")
+                .default(config.date_property)
+                .interact_text()?,
+            oauth_client_id: Some(
+                Input::<String>::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Enter the OAuth client ID of a public integration (leave empty to use an integration token)")
+                    .default(config.oauth_client_id.unwrap_or_default())
+                    .allow_empty(true)
+                    .interact_text()?,
+            )
+            .filter(|client_id| !client_id.is_empty()),
+            assignee_property: Some(
+                Input::<String>::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Enter the assignee (people) property name (leave empty to offer all items)")
+                    .default(config.assignee_property.unwrap_or_default())
+                    .allow_empty(true)
+                    .interact_text()?,
+            )
+            .filter(|assignee_property| !assignee_property.is_empty()),
+            user_id: Some(
+                Input::<String>::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Enter your Notion user ID (leave empty to use the user who signed in with OAuth)")
+                    .default(config.user_id.unwrap_or_default())
+                    .allow_empty(true)
+                    .interact_text()?,
+            )
+            .filter(|user_id| !user_id.is_empty()),
+        })
+    }
+}
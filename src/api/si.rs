@@ -1,21 +1,22 @@
 use crate::{
-    api::Session,
+    api::{http, Session},
     libs::{config::ConfigModule, secret::Secret},
 };
 use base64::prelude::*;
-use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
 use dialoguer::{theme::ColorfulTheme, Input};
 use reqwest::{
-    header::{self, HeaderMap, HeaderValue, COOKIE},
-    multipart, Client, StatusCode,
+    header::{self, HeaderValue, COOKIE},
+    multipart, Client, Response, StatusCode,
 };
 use serde::{Deserialize, Serialize};
 use std::{collections::HashSet, error::Error};
 
 const MAX_RETRY_COUNT: i32 = 3;
 const COOKIE_KEY: &str = "PORTALSESSID=";
-const SESSION_ID_FILE: &str = ".si_session_id";
-const SECRET_FILE: &str = ".si_secret";
+pub const SESSION_ID_FILE: &str = ".si_session_id";
+pub const SECRET_FILE: &str = ".si_secret";
+pub const SECRET_PROMPT: &str = "Enter your SiServer password";
 const AUTH_URL: &str = "auth/ldap";
 const LOGIN_URL: &str = "auth/login-by-token";
 const REPORT_URL: &str = "report-card/send-daily-report";
@@ -78,17 +79,17 @@ impl Session for Si {
     async fn login(&self) -> Result<String, Box<dyn Error>> {
         let credentials = self.credentials.clone().expect("Credentials not set!");
         let auth_url = format!("{}/{}", self.config.auth_url, AUTH_URL);
-        let auth_res = self.client.post(auth_url).json(&credentials).send().await?;
+        let auth_res = http::send(|| self.client.post(&auth_url).json(&credentials)).await?;
         let auth_body = auth_res.text().await?;
         let auth_session: AuthSession = serde_json::from_str(&auth_body)?;
 
         let login_url = format!("{}/{}", self.config.api_url, LOGIN_URL);
-        let login_res = self
-            .client
-            .post(login_url)
-            .header(header::AUTHORIZATION, format!("Bearer {}", auth_session.payload.token))
-            .send()
-            .await?;
+        let login_res = http::send(|| {
+            self.client
+                .post(&login_url)
+                .header(header::AUTHORIZATION, format!("Bearer {}", auth_session.payload.token))
+        })
+        .await?;
 
         if let Some(cookie) = login_res.headers().get("Set-Cookie") {
             if let Ok(cookie_val) = cookie.to_str() {
@@ -116,7 +117,7 @@ impl Session for Si {
     }
 
     fn secret(&self) -> Secret {
-        Secret::new(SECRET_FILE, "Enter your SiServer password")
+        Secret::new(SECRET_FILE, SECRET_PROMPT)
     }
 
     fn retry(&self) -> i32 {
@@ -138,83 +139,68 @@ impl Si {
         }
     }
 
-    pub async fn send(&mut self, data: &String, date: &NaiveDate) -> Result<StatusCode, Box<dyn Error>> {
+    /// Posts a form with the session cookie, logging in again when the session has expired.
+    async fn post_form<F>(&mut self, path: &str, form: F) -> Result<Response, Box<dyn Error>>
+    where
+        F: Fn() -> multipart::Form,
+    {
+        let url = format!("{}/{}", self.config.api_url, path);
         loop {
             let session_id = self.get_session_id().await?;
-            let url = format!("{}/{}", self.config.api_url, REPORT_URL);
-            let date = date.format("%Y-%m-%d").to_string();
-            let form = multipart::Form::new()
-                .text("date", date)
-                .text("tasks", data.clone())
-                .text("comment", "")
-                .text("day_type", "1")
-                .text("duty", "0")
-                .text("only_save", "0");
-
-            let mut headers = HeaderMap::new();
-            headers.insert(COOKIE, HeaderValue::from_str(&format!("{}{}", COOKIE_KEY, session_id))?);
-
-            let res = self.client.post(url).headers(headers).multipart(form).send().await?;
+            let cookie = HeaderValue::from_str(&format!("{}{}", COOKIE_KEY, session_id))?;
+            let res = http::send(|| self.client.post(&url).header(COOKIE, cookie.clone()).multipart(form())).await?;
 
             match res.status() {
                 StatusCode::UNAUTHORIZED if self.retries < MAX_RETRY_COUNT => {
                     self.delete_session_id()?;
-                    tokio::time::sleep(Duration::seconds(1).to_std()?).await;
                     self.retries += 1;
-                    continue;
                 }
-                _ => return Ok(res.status()),
+                _ => return Ok(res),
             }
         }
     }
 
-    pub async fn send_monthly(&mut self, date: &NaiveDate) -> Result<StatusCode, Box<dyn Error>> {
-        loop {
-            let session_id = self.get_session_id().await?;
-            let url = format!("{}/{}", self.config.api_url, MONTHLY_REPORT_URL);
-            let (year, month) = (date.year(), date.month());
-            let form = multipart::Form::new().text("month", month.to_string()).text("year", year.to_string());
+    pub async fn send(&mut self, data: &str, date: &NaiveDate) -> Result<StatusCode, Box<dyn Error>> {
+        let date = date.format("%Y-%m-%d").to_string();
+        let res = self
+            .post_form(REPORT_URL, || {
+                multipart::Form::new()
+                    .text("date", date.clone())
+                    .text("tasks", data.to_string())
+                    .text("comment", "")
+                    .text("day_type", "1")
+                    .text("duty", "0")
+                    .text("only_save", "0")
+            })
+            .await?;
 
-            let mut headers = HeaderMap::new();
-            headers.insert(COOKIE, HeaderValue::from_str(&format!("{}{}", COOKIE_KEY, session_id))?);
+        Ok(res.status())
+    }
 
-            let res = self.client.post(url).headers(headers).multipart(form).send().await?;
+    pub async fn send_monthly(&mut self, date: &NaiveDate) -> Result<StatusCode, Box<dyn Error>> {
+        let (year, month) = (date.year(), date.month());
+        let res = self
+            .post_form(MONTHLY_REPORT_URL, || {
+                multipart::Form::new().text("month", month.to_string()).text("year", year.to_string())
+            })
+            .await?;
 
-            match res.status() {
-                StatusCode::UNAUTHORIZED if self.retries < MAX_RETRY_COUNT => {
-                    self.delete_session_id()?;
-                    tokio::time::sleep(Duration::seconds(1).to_std()?).await;
-                    self.retries += 1;
-                    continue;
-                }
-                _ => return Ok(res.status()),
-            }
-        }
+        Ok(res.status())
     }
 
     pub async fn rest_dates(&mut self, year: NaiveDate) -> Result<HashSet<NaiveDate>, Box<dyn Error>> {
-        loop {
-            let session_id = self.get_session_id().await?;
-            let url = format!("{}/{}", self.config.api_url, REST_DATES_URL);
-            let form = multipart::Form::new().text("year", year.format("%Y").to_string());
-            let mut headers = HeaderMap::new();
-            headers.insert(COOKIE, HeaderValue::from_str(&format!("{}{}", COOKIE_KEY, session_id))?);
+        let year = year.format("%Y").to_string();
+        let res = self.post_form(REST_DATES_URL, || multipart::Form::new().text("year", year.clone())).await?;
+        let rest_dates_response = res.json::<RestDatesResponse>().await?;
 
-            let res = self.client.post(url).headers(headers).multipart(form).send().await?;
+        rest_dates_response.unique_dates()
+    }
 
-            match res.status() {
-                StatusCode::UNAUTHORIZED if self.retries < MAX_RETRY_COUNT => {
-                    self.delete_session_id()?;
-                    tokio::time::sleep(Duration::seconds(1).to_std()?).await;
-                    self.retries += 1;
-                    continue;
-                }
-                _ => {
-                    let rest_dates_response = res.json::<RestDatesResponse>().await?;
-                    return Ok(rest_dates_response.unique_dates()?);
-                }
-            }
-        }
+    /// Logs in and reads this year's rest dates for `kasl api test`.
+    pub async fn check(&mut self) -> Result<String, Box<dyn Error>> {
+        let rest_dates = self.rest_dates(Local::now().date_naive()).await?;
+
+        Ok(format!("{} rest dates this year", rest_dates.len()))
     }
 
     pub fn is_last_working_day_of_month(&self, date: &NaiveDate) -> Result<bool, Box<dyn Error>> {
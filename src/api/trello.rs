@@ -0,0 +1,185 @@
+use super::http::SendWithRetry;
+use crate::libs::{config::ConfigModule, error::KaslError, secret::Secret, task::Task};
+use chrono::{Duration, Local, NaiveDate, NaiveTime, TimeZone};
+use dialoguer::{theme::ColorfulTheme, Input};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+const API_URL: &str = "https://api.trello.com/1";
+const ACTIONS_URL: &str = "boards/{board_id}/actions";
+const COMMENTS_URL: &str = "cards/{card_id}/actions/comments";
+const ME_URL: &str = "members/me";
+const CARD_URL: &str = "https://trello.com/c";
+const SOURCE: &str = "trello";
+pub const KEY_FILE: &str = ".trello_key";
+pub const KEY_PROMPT: &str = "Enter your Trello API key";
+pub const SECRET_FILE: &str = ".trello_secret";
+pub const SECRET_PROMPT: &str = "Enter your Trello token";
+
+#[derive(Debug, Deserialize)]
+struct Action {
+    data: ActionData,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ActionData {
+    card: Card,
+    list_after: Option<List>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Card {
+    id: String,
+    name: String,
+    short_link: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct List {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Member {
+    username: String,
+}
+
+#[derive(Debug)]
+pub struct Trello {
+    client: Client,
+    config: TrelloConfig,
+}
+
+impl Trello {
+    pub fn new(config: &TrelloConfig) -> Self {
+        Self {
+            client: Client::new(),
+            config: config.clone(),
+        }
+    }
+
+    fn auth(&self) -> Result<[(&str, String); 2], Box<dyn Error>> {
+        let key = Secret::new(KEY_FILE, KEY_PROMPT).get_or_prompt()?;
+        let token = Secret::new(SECRET_FILE, SECRET_PROMPT).get_or_prompt()?;
+
+        Ok([("key", key), ("token", token)])
+    }
+
+    /// Reads the token's member for `kasl api test`.
+    pub async fn check(&self) -> Result<String, Box<dyn Error>> {
+        let url = format!("{}/{}", API_URL, ME_URL);
+        let res = self.client.get(url).query(&self.auth()?).send_with_retry().await?;
+
+        if !res.status().is_success() {
+            return Err(KaslError::network(format!("Trello member request failed with status {}", res.status())));
+        }
+
+        Ok(format!("signed in as {}", res.json::<Member>().await?.username))
+    }
+
+    /// Returns cards moved to the configured done list on `date`.
+    pub async fn get_done_cards(&self, date: &NaiveDate) -> Result<Vec<Task>, Box<dyn Error>> {
+        let url = format!("{}/{}", API_URL, ACTIONS_URL.replace("{board_id}", &self.config.board_id));
+        let since = local_midnight(date);
+        let before = local_midnight(&date.succ_opt().unwrap_or(*date));
+        let res = self
+            .client
+            .get(url)
+            .query(&self.auth()?)
+            .query(&[("filter", "updateCard:idList"), ("since", &since), ("before", &before)])
+            .send_with_retry()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(KaslError::network(format!("Trello board actions request failed with status {}", res.status())));
+        }
+
+        let mut tasks: Vec<Task> = vec![];
+        for action in res.json::<Vec<Action>>().await? {
+            let moved_to_done = action.data.list_after.is_some_and(|list| list.name == self.config.done_list);
+            let card = action.data.card;
+            if moved_to_done && tasks.iter().all(|task| task.external_id.as_deref() != Some(&card.id)) {
+                let url = card.short_link.map(|short_link| format!("{}/{}", CARD_URL, short_link));
+                tasks.push(Task::new(&card.name, "", Some(100)).with_external(SOURCE, &card.id, url));
+            }
+        }
+
+        Ok(tasks)
+    }
+
+    pub async fn post_summary(&self, text: &str) -> Result<(), Box<dyn Error>> {
+        let card_id = self
+            .config
+            .summary_card_id
+            .as_ref()
+            .ok_or_else(|| KaslError::config("Trello summary card is not configured"))?;
+        let url = format!("{}/{}", API_URL, COMMENTS_URL.replace("{card_id}", card_id));
+        let res = self.client.post(url).query(&self.auth()?).query(&[("text", text)]).send_with_retry().await?;
+
+        if !res.status().is_success() {
+            return Err(KaslError::network(format!("Trello comment request failed with status {}", res.status())));
+        }
+
+        Ok(())
+    }
+}
+
+/// Start of `date` in the local time zone as an RFC 3339 timestamp, since Trello reads bare dates as UTC midnight.
+fn local_midnight(date: &NaiveDate) -> String {
+    let midnight = date.and_time(NaiveTime::MIN);
+    // Where the clocks skip midnight, the day starts an hour later.
+    match Local.from_local_datetime(&midnight).earliest() {
+        Some(start) => start.to_rfc3339(),
+        None => Local
+            .from_local_datetime(&(midnight + Duration::hours(1)))
+            .earliest()
+            .unwrap_or_default()
+            .to_rfc3339(),
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TrelloConfig {
+    pub board_id: String,
+    pub done_list: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary_card_id: Option<String>,
+}
+
+impl TrelloConfig {
+    pub fn module() -> ConfigModule {
+        ConfigModule {
+            key: "trello".to_string(),
+            name: "Trello".to_string(),
+        }
+    }
+    pub fn init(config: &Option<Self>) -> Result<Self, Box<dyn Error>> {
+        let config = config.clone().unwrap_or(Self {
+            board_id: "".to_string(),
+            done_list: "Done".to_string(),
+            summary_card_id: None,
+        });
+        println!("Trello settings");
+        let board_id = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Enter the Trello board ID")
+            .default(config.board_id)
+            .interact_text()?;
+        let done_list = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Enter the name of the done list")
+            .default(config.done_list)
+            .interact_text()?;
+        let summary_card_id: String = Input::with_theme(&ColorfulTheme::default())
+            .allow_empty(true)
+            .with_prompt("Enter the card ID for daily summaries (empty to skip)")
+            .default(config.summary_card_id.unwrap_or_default())
+            .interact_text()?;
+        Ok(Self {
+            board_id,
+            done_list,
+            summary_card_id: Some(summary_card_id).filter(|id| !id.is_empty()),
+        })
+    }
+}
@@ -0,0 +1,94 @@
+use super::http::SendWithRetry;
+use crate::libs::{config::ConfigModule, error::KaslError, secret::Secret};
+use dialoguer::{theme::ColorfulTheme, Input};
+use reqwest::{Client, Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+pub const SECRET_FILE: &str = ".webdav_secret";
+pub const SECRET_PROMPT: &str = "Enter your WebDAV password (a Nextcloud app password works)";
+
+/// Uploads files into one WebDAV folder, e.g. a Nextcloud directory.
+#[derive(Debug)]
+pub struct WebDav {
+    client: Client,
+    config: WebDavConfig,
+}
+
+impl WebDav {
+    pub fn new(config: &WebDavConfig) -> Self {
+        Self {
+            client: Client::new(),
+            config: config.clone(),
+        }
+    }
+
+    fn password(&self) -> Result<String, Box<dyn Error>> {
+        Secret::new(SECRET_FILE, SECRET_PROMPT).get_or_prompt()
+    }
+
+    fn file_url(&self, file_name: &str) -> String {
+        format!("{}/{}", self.config.url.trim_end_matches('/'), file_name)
+    }
+
+    /// Writes `body` to `file_name` in the configured folder, creating the folder when the server reports it missing.
+    pub async fn upload(&self, file_name: &str, body: Vec<u8>) -> Result<String, Box<dyn Error>> {
+        let url = self.file_url(file_name);
+        let password = self.password()?;
+        let put = || {
+            self.client
+                .put(&url)
+                .basic_auth(&self.config.username, Some(&password))
+                .body(body.clone())
+                .send_with_retry()
+        };
+        let mut res = put().await?;
+        if matches!(res.status(), StatusCode::NOT_FOUND | StatusCode::CONFLICT) {
+            self.client
+                .request(Method::from_bytes(b"MKCOL")?, &self.config.url)
+                .basic_auth(&self.config.username, Some(&password))
+                .send_with_retry()
+                .await?;
+            res = put().await?;
+        }
+        if !res.status().is_success() {
+            return Err(KaslError::network(format!("WebDAV upload failed with status {}", res.status())));
+        }
+
+        Ok(url)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WebDavConfig {
+    /// Folder the files are written to, e.g. `https://cloud.example.com/remote.php/dav/files/me/kasl`.
+    pub url: String,
+    pub username: String,
+}
+
+impl WebDavConfig {
+    pub fn module() -> ConfigModule {
+        ConfigModule {
+            key: "webdav".to_string(),
+            name: "WebDAV / Nextcloud".to_string(),
+        }
+    }
+
+    pub fn init(config: &Option<Self>) -> Result<Self, Box<dyn Error>> {
+        let config = config.clone().unwrap_or(Self {
+            url: "".to_string(),
+            username: "".to_string(),
+        });
+        println!("WebDAV settings");
+        Ok(Self {
+            url: Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Enter the URL of the WebDAV folder for exports")
+                .default(config.url)
+                .interact_text()?,
+            username: Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Enter your WebDAV username")
+                .default(config.username)
+                .interact_text()?,
+        })
+    }
+}
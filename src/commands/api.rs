@@ -0,0 +1,103 @@
+use crate::{
+    api::{gitlab::GitLab, jira::Jira, notion::Notion, si::Si, trello::Trello, ApiCheck},
+    libs::{config::Config, error::KaslError, view::View},
+};
+use clap::{Args, Subcommand, ValueEnum};
+use std::{error::Error, future::Future, time::Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Service {
+    All,
+    Gitlab,
+    Jira,
+    Notion,
+    Si,
+    Trello,
+}
+
+#[derive(Debug, Subcommand)]
+enum ApiCommand {
+    #[command(about = "Log in to the configured integrations and make a lightweight read call")]
+    Test {
+        #[arg(value_enum, default_value = "all")]
+        service: Service,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct ApiArgs {
+    #[command(subcommand)]
+    command: ApiCommand,
+}
+
+pub async fn cmd(api_args: ApiArgs) -> Result<(), Box<dyn Error>> {
+    let ApiCommand::Test { service } = api_args.command;
+    let config = Config::read()?;
+    let tested = |candidate: Service| service == Service::All || service == candidate;
+    let mut checks: Vec<ApiCheck> = vec![];
+
+    if tested(Service::Gitlab) {
+        for gitlab_config in config.gitlab.iter() {
+            let name = format!("gitlab ({})", gitlab_config.label());
+            checks.push(check(name, GitLab::new(gitlab_config).check()).await);
+        }
+    }
+    if tested(Service::Jira) {
+        if let Some(jira_config) = &config.jira {
+            checks.push(check("jira".to_string(), Jira::new(jira_config).check()).await);
+        }
+    }
+    if tested(Service::Notion) {
+        if let Some(notion_config) = &config.notion {
+            checks.push(check("notion".to_string(), Notion::new(notion_config).check()).await);
+        }
+    }
+    if tested(Service::Si) {
+        if let Some(si_config) = &config.si {
+            checks.push(check("si".to_string(), Si::new(si_config).check()).await);
+        }
+    }
+    if tested(Service::Trello) {
+        if let Some(trello_config) = &config.trello {
+            checks.push(check("trello".to_string(), Trello::new(trello_config).check()).await);
+        }
+    }
+
+    if checks.is_empty() {
+        return Err(KaslError::config("No matching integration is configured, run `kasl init` first"));
+    }
+    View::api_checks(&checks)?;
+    let failed = checks.iter().filter(|check| check.result.is_err()).count();
+    if failed > 0 {
+        return Err(KaslError::network(format!("{} of {} checks failed", failed, checks.len())));
+    }
+
+    Ok(())
+}
+
+async fn check<E: Into<Box<dyn Error>>>(service: String, request: impl Future<Output = Result<String, E>>) -> ApiCheck {
+    let started = Instant::now();
+    let result = request.await.map_err(|error| reason(error.into().as_ref()));
+
+    ApiCheck {
+        service,
+        latency_ms: started.elapsed().as_millis(),
+        result,
+    }
+}
+
+/// The error with all of its sources, e.g. `error sending request: client error (Connect): dns error: ...`,
+/// which tells a proxy or certificate problem apart from a wrong password.
+fn reason(error: &dyn Error) -> String {
+    let mut reason = error.to_string();
+    let mut source = error.source();
+    while let Some(error) = source {
+        let message = error.to_string();
+        if !reason.contains(&message) {
+            reason = format!("{}: {}", reason, message);
+        }
+        source = error.source();
+    }
+
+    reason
+}
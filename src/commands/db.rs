@@ -0,0 +1,66 @@
+use crate::{
+    db::{db::Db, events::Events},
+    libs::{config::Config, dry_run, retention},
+};
+use chrono::Local;
+use clap::{Args, Subcommand};
+use std::error::Error;
+
+#[derive(Debug, Subcommand)]
+enum DbCommand {
+    #[command(about = "Apply the retention policy and shrink the database")]
+    Maintain,
+}
+
+#[derive(Debug, Args)]
+pub struct DbArgs {
+    #[command(subcommand)]
+    command: DbCommand,
+}
+
+pub fn cmd(db_args: DbArgs) -> Result<(), Box<dyn Error>> {
+    match db_args.command {
+        DbCommand::Maintain => maintain(),
+    }
+}
+
+fn maintain() -> Result<(), Box<dyn Error>> {
+    let retention_config = Config::read().ok().and_then(|config| config.retention).unwrap_or_default();
+    let cutoff = retention_config.cutoff(Local::now().date_naive());
+    let mut events = Events::new()?;
+    let all_events = events.fetch_all()?;
+    let days = retention::compact(&all_events, cutoff);
+    if days.is_empty() {
+        println!("No days before {} need compacting", cutoff.format("%B %-d, %Y"));
+        return Ok(());
+    }
+    let removed_count = all_events.iter().filter(|event| days.contains_key(&event.start.date())).count() - days.len();
+    if dry_run::is_enabled() {
+        dry_run::print(&format!(
+            "Would compact {} days before {}, removing {} work intervals",
+            days.len(),
+            cutoff.format("%B %-d, %Y"),
+            removed_count
+        ));
+        return Ok(());
+    }
+
+    let backup_path = Db::backup(&events.conn)?;
+    println!("Saved a backup of the database to {}", backup_path.display());
+    Db::transaction(&events.conn.clone(), || {
+        for (date, day_events) in days.iter() {
+            events.replace_day(*date, day_events)?;
+        }
+        Ok(())
+    })?;
+    Db::new()?.conn.execute_batch("VACUUM")?;
+
+    println!(
+        "Compacted {} days before {} into daily totals, removing {} work intervals",
+        days.len(),
+        cutoff.format("%B %-d, %Y"),
+        removed_count
+    );
+
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use crate::{
+    db::{
+        db::Db,
+        events::{Events, SelectRequest},
+        heartbeat::Heartbeats,
+        migrations,
+    },
+    libs::{
+        config::{Config, CONFIG_FILE_NAME},
+        data_storage::DataStorage,
+        diag::{self, ActivityLog},
+        dry_run,
+        event::FormatEvent,
+        health, permissions,
+        pid::PidFile,
+    },
+};
+use chrono::Local;
+use clap::{Args, Subcommand};
+use flate2::{write::GzEncoder, Compression};
+use std::{env::consts::OS, error::Error, fs::File, path::PathBuf};
+use tar::{Builder, Header};
+
+#[derive(Debug, Subcommand)]
+enum DiagCommand {
+    #[command(about = "Bundle the redacted configuration and recorded activity for a bug report")]
+    Export {
+        #[arg(short, long, help = "Output file (defaults to kasl-diag-<date>.tar.gz)")]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct DiagArgs {
+    #[command(subcommand)]
+    command: DiagCommand,
+}
+
+pub fn cmd(diag_args: DiagArgs) -> Result<(), Box<dyn Error>> {
+    match diag_args.command {
+        DiagCommand::Export { output } => export(output),
+    }
+}
+
+/// Prints one line per check and how many of them found a problem.
+pub fn doctor() -> Result<(), Box<dyn Error>> {
+    let now = Local::now().naive_local();
+    let mut checks: Vec<(&str, Result<String, String>)> = vec![];
+    let version = migrations::version(&Db::new()?.conn)?;
+    checks.push((
+        "Database",
+        match version == migrations::latest() {
+            true => Ok(format!("schema version {}", version)),
+            false => Err(format!("schema version {}, expected {}", version, migrations::latest())),
+        },
+    ));
+    checks.push((
+        "Configuration",
+        match DataStorage::new().get_path(CONFIG_FILE_NAME)?.exists() {
+            true => Config::read().map(|_| "readable".to_string()).map_err(|e| e.to_string()),
+            false => Ok("not created yet, run `kasl init`".to_string()),
+        },
+    ));
+    let heartbeat = Heartbeats::new()?.fetch()?;
+    checks.push((
+        "Watcher",
+        match (PidFile::running(), &heartbeat) {
+            (Some((pid, started_at)), _) => Ok(format!(
+                "running (PID {}, uptime {})",
+                pid,
+                FormatEvent::format_duration(Some(now - started_at))
+            )),
+            (None, Some(_)) => Err("not running, but it did not shut down cleanly".to_string()),
+            (None, None) => Ok("not running".to_string()),
+        },
+    ));
+    checks.push((
+        "Input access",
+        match permissions::input_monitoring_warning() {
+            Some(warning) => Err(warning),
+            None => Ok("keyboard and mouse can be read".to_string()),
+        },
+    ));
+    let workday_open = Events::new()?
+        .fetch(SelectRequest::Daily, now.date())?
+        .last()
+        .is_some_and(|event| event.end.is_none());
+    checks.push((
+        "Heartbeat",
+        match (health::check(heartbeat.as_ref(), now, workday_open), &heartbeat) {
+            (Some(alert), _) => Err(alert.to_string()),
+            (None, Some(heartbeat)) => Ok(format!(
+                "last beat at {}, last input at {}",
+                heartbeat.beat_at.format("%H:%M:%S"),
+                heartbeat.last_active_at.format("%H:%M:%S")
+            )),
+            (None, None) => Ok("none recorded".to_string()),
+        },
+    ));
+
+    let problems = checks.iter().filter(|(_, result)| result.is_err()).count();
+    for (name, result) in checks {
+        match result {
+            Ok(message) => println!("[ok] {}: {}", name, message),
+            Err(message) => println!("[!!] {}: {}", name, message),
+        }
+    }
+    match problems {
+        0 => println!("\nNo problems found"),
+        problems => println!("\n{} problems found", problems),
+    }
+
+    Ok(())
+}
+
+fn export(output: Option<PathBuf>) -> Result<(), Box<dyn Error>> {
+    let path = output.unwrap_or_else(|| PathBuf::from(format!("kasl-diag-{}.tar.gz", Local::now().format("%Y-%m-%d"))));
+    if dry_run::is_enabled() {
+        dry_run::print(&format!("Would save the diagnostics bundle to {}", path.display()));
+        return Ok(());
+    }
+    let info = format!(
+        "kasl {}\nos: {}\nschema version: {}\ndata dir: {}\ncreated at: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        OS,
+        migrations::version(&Db::new()?.conn)?,
+        DataStorage::new().get_path("")?.display(),
+        Local::now().format("%Y-%m-%d %H:%M:%S")
+    );
+    let config = match Config::read() {
+        Ok(config) => {
+            let mut config = serde_json::to_value(config)?;
+            diag::redact(&mut config);
+            serde_json::to_string_pretty(&config)?
+        }
+        Err(e) => format!("\"{}\"", e),
+    };
+    let activity = ActivityLog::read()?;
+    let activity_json = serde_json::to_string_pretty(&activity)?;
+
+    let mut builder = Builder::new(GzEncoder::new(File::create(&path)?, Compression::default()));
+    for (name, contents) in [("info.txt", info), (CONFIG_FILE_NAME, config), ("activity.json", activity_json)] {
+        let mut header = Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(Local::now().timestamp() as u64);
+        header.set_cksum();
+        builder.append_data(&mut header, name, contents.as_bytes())?;
+    }
+    builder.into_inner()?.finish()?;
+
+    println!("Diagnostics with {} activity records written to {}", activity.len(), path.display());
+
+    Ok(())
+}
@@ -1,11 +1,18 @@
 use crate::{
-    db::events::{Events, SelectRequest},
+    db::{
+        db::Db,
+        event_changes::{self, EventChanges},
+        events::{Events, SelectRequest},
+    },
     libs::{
+        date, dry_run,
+        error::KaslError,
         event::{EventGroup, EventType, FormatEvents},
-        view::View,
+        validation::{self, EventFix},
+        view::{TableOptions, View},
     },
 };
-use chrono::Local;
+use chrono::{Local, NaiveDate, NaiveTime};
 use clap::Args;
 use std::error::Error;
 
@@ -16,35 +23,170 @@ pub struct EventArgs {
         value_enum
     )]
     pub(crate) event_type: EventType,
-    #[arg(short, long)]
+    #[arg(short, long, group = "view")]
     pub(crate) show: bool,
-    #[arg(short, long)]
+    #[arg(short, long, group = "view")]
     pub(crate) raw: bool,
+    #[arg(long, value_name = "DATE", requires = "view", value_parser = date::parse_arg, help = "Show the events of another day, e.g. 2024-08-01 or yesterday")]
+    pub(crate) date: Option<NaiveDate>,
+    #[arg(long, help = "Check all events for overlaps, open ends and other inconsistencies")]
+    pub(crate) validate: bool,
+    #[arg(long, requires = "validate", help = "Normalize the inconsistent events found by --validate")]
+    pub(crate) fix: bool,
+    #[command(flatten)]
+    pub(crate) table: TableOptions,
+}
+
+#[derive(Debug, Args)]
+pub struct StartArgs {
+    #[arg(long, value_parser = date::parse_time_arg, help = "Set the workday start retroactively, e.g. 08:30")]
+    pub(crate) at: Option<NaiveTime>,
+    #[arg(long, requires = "at", value_parser = date::parse_arg, help = "Day whose start is set, defaults to today")]
+    pub(crate) date: Option<NaiveDate>,
+}
+
+/// `kasl start`: records a start now, or with `--at` moves the day's first start to the given time.
+///
+/// A later start may only trim the first interval; it must not swallow a recorded pause or later work.
+pub fn start(args: StartArgs) -> Result<(), Box<dyn Error>> {
+    let Some(at) = args.at else {
+        return cmd(EventArgs {
+            event_type: EventType::Start,
+            show: false,
+            raw: false,
+            date: None,
+            validate: false,
+            fix: false,
+            table: TableOptions::default(),
+        });
+    };
+    let now = Local::now().naive_local();
+    let date = args.date.unwrap_or(now.date());
+    let start = date.and_time(at);
+    if start > now {
+        return Err(KaslError::validation(format!("The start {} is in the future", start.format("%Y-%m-%d %H:%M"))));
+    }
+    let mut events = Events::new()?;
+    let first = events.fetch(SelectRequest::Daily, date)?.into_iter().min_by_key(|event| event.start);
+    let before = match &first {
+        None if date != now.date() => {
+            return Err(KaslError::validation(format!(
+                "No events recorded on {}, a start on a past day would stay open",
+                date.format("%Y-%m-%d")
+            )))
+        }
+        None => None,
+        Some(event) if event.end.is_some_and(|end| start >= end) => {
+            return Err(KaslError::validation(format!(
+                "The start {} is after the first interval ends at {} and would hide the pause that follows it",
+                at.format("%H:%M"),
+                event.end.unwrap().format("%H:%M")
+            )))
+        }
+        Some(event) if event.start == start => {
+            println!("The workday on {} already starts at {}", date.format("%B %-d, %Y"), at.format("%H:%M"));
+            return Ok(());
+        }
+        Some(event) => Some(event.start),
+    };
+    let message = match before {
+        Some(before) => format!(
+            "Workday start on {} moved from {} to {}",
+            date.format("%B %-d, %Y"),
+            before.format("%H:%M"),
+            at.format("%H:%M")
+        ),
+        None => format!("Workday start on {} set to {}", date.format("%B %-d, %Y"), at.format("%H:%M")),
+    };
+    if dry_run::is_enabled() {
+        dry_run::print(&message);
+        return Ok(());
+    }
+
+    Db::transaction(&events.conn.clone(), || {
+        match &first {
+            Some(event) => {
+                let mut event = event.clone();
+                event.start = start;
+                events.update(&event)?;
+            }
+            None => events.insert_interval(start, None)?,
+        }
+        EventChanges::new()?.insert(date, event_changes::ACTION_START, before, start)
+    })?;
+    println!("{}", message);
+
+    Ok(())
 }
 
 pub fn cmd(event_args: EventArgs) -> Result<(), Box<dyn Error>> {
     let now = Local::now();
+    if event_args.validate {
+        let mut events = Events::new()?;
+        let issues = validation::validate(&events.fetch_all()?);
+        if issues.is_empty() {
+            println!("No inconsistent events found");
+            return Ok(());
+        }
+        issues.iter().for_each(|issue| println!("{}", issue));
+        if event_args.fix {
+            let fixes = validation::fix(&issues);
+            if dry_run::is_enabled() {
+                for event_fix in fixes.iter() {
+                    match event_fix {
+                        EventFix::Update(event) => dry_run::print(&format!("UPDATE event {}", event.id)),
+                        EventFix::Delete(id) => dry_run::print(&format!("DELETE event {}", id)),
+                    }
+                }
+                return Ok(());
+            }
+            for event_fix in fixes.iter() {
+                match event_fix {
+                    EventFix::Update(event) => events.update(event)?,
+                    EventFix::Delete(id) => events.delete(*id)?,
+                }
+            }
+            println!("{} inconsistent events have been fixed", fixes.len());
+        }
+
+        return Ok(());
+    }
+    let date = event_args.date.unwrap_or(now.date_naive());
     if event_args.raw {
-        println!("\nRaw events for {}", now.format("%B %-d, %Y"));
+        println!("\nRaw events for {}", date.format("%B %-d, %Y"));
 
-        let events = Events::new()?.fetch(SelectRequest::Daily, now.date_naive())?.format();
-        View::events_raw(&events)?;
+        let events = Events::new()?.fetch(SelectRequest::Daily, date)?.format();
+        View::events_raw(&events, &event_args.table)?;
 
         return Ok(());
     } else if event_args.show {
-        println!("\nWorking hours for {}", now.format("%B %-d, %Y"));
+        println!("\nWorking hours for {}", date.format("%B %-d, %Y"));
 
         let events = Events::new()?
-            .fetch(SelectRequest::Daily, now.date_naive())?
+            .fetch(SelectRequest::Daily, date)?
             .merge()
             .update_duration()
             .total_duration()
             .format();
-        View::events(&events)?;
+        View::events(&events, &event_args.table)?;
+        for change in EventChanges::new()?.fetch(date)? {
+            let before = change.before.map(|before| before.format("%H:%M").to_string()).unwrap_or("none".to_string());
+            println!(
+                "{} corrected at {}: {} -> {}",
+                change.action,
+                change.changed_at.format("%Y-%m-%d %H:%M"),
+                before,
+                change.after.format("%H:%M")
+            );
+        }
 
         return Ok(());
     }
-    let _ = Events::new()?.insert(&event_args.event_type);
+    if dry_run::is_enabled() {
+        dry_run::print(&format!("INSERT event {}", &event_args.event_type));
+        return Ok(());
+    }
+    Events::new()?.insert(&event_args.event_type)?;
 
     println!("Time {}", &event_args.event_type);
 
@@ -0,0 +1,103 @@
+use crate::{
+    db::{events::Events, journal::Journal, projects::Projects, task_notes::TaskNotes, tasks::Tasks},
+    libs::{
+        archive::Archive,
+        compression::{self, Compression},
+        destination::{Destination, DestinationKind},
+        dry_run,
+        error::KaslError,
+        interactive,
+        task::TaskFilter,
+    },
+};
+use chrono::Local;
+use clap::Args;
+use dialoguer::{theme::ColorfulTheme, Password};
+use std::{error::Error, path::PathBuf};
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    #[arg(short, long, help = "Output file (defaults to kasl-<date>.json)")]
+    output: Option<PathBuf>,
+    #[arg(
+        long,
+        value_enum,
+        default_value = "local",
+        help = "Where to write the export; remote destinations use the file name of --output"
+    )]
+    to: DestinationKind,
+    #[arg(long, value_enum, help = "Compress the archive into a .zip or .json.gz file")]
+    compress: Option<Compression>,
+    #[arg(long, help = "Protect the archive with a password (AES-256 zip)")]
+    encrypt: bool,
+    #[arg(long, help = "Replace task and project names with pseudonyms and drop comments, notes and journal texts")]
+    redact: bool,
+}
+
+pub async fn cmd(export_args: ExportArgs) -> Result<(), Box<dyn Error>> {
+    let events = Events::new()?.fetch_all()?;
+    let mut tasks = Tasks::new()?.fetch(TaskFilter::All)?;
+    tasks.extend(Tasks::new()?.fetch(TaskFilter::Deleted)?);
+    let task_notes = TaskNotes::new()?.fetch(None)?;
+    let journal = Journal::new()?.fetch(None)?;
+    let projects = Projects::new()?.fetch()?;
+    let mut archive = Archive::new(events, tasks, task_notes, journal, projects);
+    if export_args.redact {
+        archive.redact();
+    }
+
+    // Encryption is only available for zip archives, so it picks zip when no compression is given.
+    let compress = match (export_args.compress, export_args.encrypt) {
+        (Some(Compression::Gz), true) => return Err(KaslError::validation("gzip files cannot be encrypted, use --compress zip")),
+        (None, true) => Some(Compression::Zip),
+        (compress, _) => compress,
+    };
+    let extension = compress.map_or("json", |compress| compress.extension());
+    let path = export_args
+        .output
+        .unwrap_or_else(|| PathBuf::from(format!("kasl-{}.{}", Local::now().format("%Y-%m-%d"), extension)));
+    let destination = Destination::new(export_args.to, path)?;
+    if dry_run::is_enabled() {
+        dry_run::print(&format!(
+            "Would write {} events, {} tasks, {} task notes, {} journal entries and {} projects to {}",
+            archive.events.len(),
+            archive.tasks.len(),
+            archive.task_notes.len(),
+            archive.journal.len(),
+            archive.projects.len(),
+            destination.describe()
+        ));
+        return Ok(());
+    }
+    let json = archive.to_json()?;
+    let contents = match compress {
+        Some(Compression::Gz) => compression::gzip(&json)?,
+        Some(Compression::Zip) => {
+            let password = match export_args.encrypt {
+                true if interactive::is_disabled() => return Err(interactive::required("The archive password", "export without --encrypt")),
+                true => Some(
+                    Password::with_theme(&ColorfulTheme::default())
+                        .with_prompt("Enter a password for the archive")
+                        .with_confirmation("Repeat the password", "The passwords do not match")
+                        .interact()?,
+                ),
+                false => None,
+            };
+            compression::zip(&format!("kasl-{}.json", Local::now().format("%Y-%m-%d")), &json, password.as_deref())?
+        }
+        None => json,
+    };
+    let location = destination.write(contents).await?;
+
+    println!(
+        "Exported {} events, {} tasks, {} task notes, {} journal entries and {} projects to {}",
+        archive.events.len(),
+        archive.tasks.len(),
+        archive.task_notes.len(),
+        archive.journal.len(),
+        archive.projects.len(),
+        location
+    );
+
+    Ok(())
+}
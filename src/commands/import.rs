@@ -0,0 +1,303 @@
+use crate::{
+    db::{db::Db, events::Events, journal::Journal, projects::Projects, task_notes::TaskNotes, tasks::Tasks},
+    libs::{
+        archive::Archive,
+        dry_run, merge,
+        task::{Task, TaskFilter, TaskNote},
+    },
+};
+use clap::Args;
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    path::PathBuf,
+};
+
+#[derive(Debug, Args)]
+pub struct ImportArgs {
+    #[arg(help = "Archive file created by the export command")]
+    file: PathBuf,
+    #[arg(
+        long,
+        help = "Union the archived work intervals with the local ones and add the other records under new IDs instead of replacing records by ID"
+    )]
+    merge: bool,
+}
+
+pub fn cmd(import_args: ImportArgs) -> Result<(), Box<dyn Error>> {
+    let archive = Archive::read(&import_args.file)?;
+    if dry_run::is_enabled() {
+        return dry_run(&archive, import_args.merge);
+    }
+    // A failure in any table leaves the database as it was before the import.
+    let (events_count, tasks_count, notes_count, journal_count, projects_count) = Db::transaction(&Db::new()?.conn, || {
+        let mut events = Events::new()?;
+        if import_args.merge {
+            let local_events = events.fetch_all()?;
+            let mut events_count = 0;
+            for (date, day_events) in merge::merge_days(&local_events, &archive.events) {
+                events_count += events.replace_day(date, &day_events)?;
+            }
+            let (tasks_count, notes_count, journal_count, projects_count) = merge_records(&archive, true)?;
+            return Ok((events_count, tasks_count, notes_count, journal_count, projects_count));
+        }
+
+        Ok((
+            events.restore(&archive.events)?,
+            Tasks::new()?.restore(&archive.tasks)?,
+            TaskNotes::new()?.restore(&archive.task_notes)?,
+            Journal::new()?.restore(&archive.journal)?,
+            Projects::new()?.restore(&archive.projects)?,
+        ))
+    })?;
+
+    println!(
+        "Imported {} events, {} tasks, {} task notes, {} journal entries and {} projects from {} (exported at {})",
+        events_count,
+        tasks_count,
+        notes_count,
+        journal_count,
+        projects_count,
+        import_args.file.display(),
+        archive.exported_at
+    );
+
+    Ok(())
+}
+
+/// Adds the archived tasks, notes, journal entries and projects that are not in the database yet under new IDs,
+/// so records of another device never replace unrelated local ones that happen to share an ID.
+/// Projects are matched by name, tasks by timestamp and name, notes and journal entries by timestamp and text.
+/// Task chains, project links and the tasks of notes are pointed at the new or matching local records.
+/// Without `apply` nothing is written and the counts of what would be added are returned.
+fn merge_records(archive: &Archive, apply: bool) -> Result<(usize, usize, usize, usize), Box<dyn Error>> {
+    let mut next_placeholder = 0;
+    let mut new_id = |insert: &mut dyn FnMut() -> Result<i32, Box<dyn Error>>| -> Result<i32, Box<dyn Error>> {
+        match apply {
+            true => insert(),
+            false => {
+                next_placeholder -= 1;
+                Ok(next_placeholder)
+            }
+        }
+    };
+
+    let mut projects = Projects::new()?;
+    let local_projects: HashMap<String, i32> = projects.fetch()?.into_iter().filter_map(|project| Some((project.name, project.id?))).collect();
+    let mut project_ids: HashMap<i32, i32> = HashMap::new();
+    let mut projects_count = 0;
+    for project in archive.projects.iter() {
+        let id = match local_projects.get(&project.name) {
+            Some(id) => *id,
+            None => {
+                projects_count += 1;
+                new_id(&mut || projects.insert(project))?
+            }
+        };
+        if let Some(archived_id) = project.id {
+            project_ids.insert(archived_id, id);
+        }
+    }
+
+    let mut tasks = Tasks::new()?;
+    let mut local_tasks = tasks.fetch(TaskFilter::All)?;
+    local_tasks.extend(tasks.fetch(TaskFilter::Deleted)?);
+    let local_task_ids: HashMap<(Option<String>, String), i32> = local_tasks
+        .into_iter()
+        .filter_map(|task| Some(((task.timestamp, task.name), task.id?)))
+        .collect();
+    let mut archived_tasks: Vec<_> = archive.tasks.iter().collect();
+    // Chains start with their oldest record, so the chain ID is known before the later days are added.
+    archived_tasks.sort_by_key(|task| task.id);
+    let mut task_ids: HashMap<i32, i32> = HashMap::new();
+    let mut tasks_count = 0;
+    for task in archived_tasks {
+        let id = match local_task_ids.get(&(task.timestamp.clone(), task.name.clone())) {
+            Some(id) => *id,
+            None => {
+                tasks_count += 1;
+                let chain_id = task
+                    .task_id
+                    .filter(|task_id| Some(*task_id) != task.id)
+                    .and_then(|task_id| task_ids.get(&task_id).copied());
+                let task = Task {
+                    project_id: task.project_id.and_then(|project_id| project_ids.get(&project_id).copied()),
+                    ..task.clone()
+                };
+                new_id(&mut || tasks.append(&task, chain_id))?
+            }
+        };
+        if let Some(archived_id) = task.id {
+            task_ids.insert(archived_id, id);
+        }
+    }
+
+    let mut notes = TaskNotes::new()?;
+    let local_notes: HashSet<(i32, String, String)> = notes.fetch(None)?.into_iter().map(|note| (note.task_id, note.timestamp, note.text)).collect();
+    let mut notes_count = 0;
+    for note in archive.task_notes.iter() {
+        let Some(task_id) = task_ids.get(&note.task_id).copied() else {
+            continue;
+        };
+        if local_notes.contains(&(task_id, note.timestamp.clone(), note.text.clone())) {
+            continue;
+        }
+        notes_count += 1;
+        if apply {
+            notes.append(&TaskNote { task_id, ..note.clone() })?;
+        }
+    }
+
+    let mut journal = Journal::new()?;
+    let local_entries: HashSet<(String, String)> = journal.fetch(None)?.into_iter().map(|entry| (entry.timestamp, entry.text)).collect();
+    let mut journal_count = 0;
+    for entry in archive.journal.iter() {
+        if local_entries.contains(&(entry.timestamp.clone(), entry.text.clone())) {
+            continue;
+        }
+        journal_count += 1;
+        if apply {
+            journal.append(entry)?;
+        }
+    }
+
+    Ok((tasks_count, notes_count, journal_count, projects_count))
+}
+
+fn dry_run(archive: &Archive, merge: bool) -> Result<(), Box<dyn Error>> {
+    let local_events = Events::new()?.fetch_all()?;
+    match merge {
+        true => {
+            let merged_days = merge::merge_days(&local_events, &archive.events);
+            let deleted_count = local_events.iter().filter(|event| merged_days.contains_key(&event.start.date())).count();
+            let inserted_count: usize = merged_days.values().map(|day_events| day_events.len()).sum();
+            dry_run::print(&format!(
+                "DELETE {} and INSERT {} events in {} days",
+                deleted_count,
+                inserted_count,
+                merged_days.len()
+            ));
+            let (tasks_count, notes_count, journal_count, projects_count) = merge_records(archive, false)?;
+            dry_run::print(&format!(
+                "INSERT {} tasks, {} task notes, {} journal entries and {} projects under new IDs",
+                tasks_count, notes_count, journal_count, projects_count
+            ));
+
+            return Ok(());
+        }
+        false => {
+            let local_ids: HashSet<i32> = local_events.iter().map(|event| event.id).collect();
+            let replaced_count = archive.events.iter().filter(|event| local_ids.contains(&event.id)).count();
+            dry_run::print(&format!(
+                "INSERT OR REPLACE {} events, {} of them replace existing ones",
+                archive.events.len(),
+                replaced_count
+            ));
+        }
+    }
+
+    let mut tasks = Tasks::new()?;
+    let mut local_tasks = tasks.fetch(TaskFilter::All)?;
+    local_tasks.extend(tasks.fetch(TaskFilter::Deleted)?);
+    let local_ids: HashSet<Option<i32>> = local_tasks.iter().map(|task| task.id).collect();
+    let replaced_count = archive.tasks.iter().filter(|task| local_ids.contains(&task.id)).count();
+    dry_run::print(&format!(
+        "INSERT OR REPLACE {} tasks, {} of them replace existing ones",
+        archive.tasks.len(),
+        replaced_count
+    ));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        libs::{journal::JournalEntry, project::Project},
+        testing::{memory_db, TaskFixture},
+    };
+    use chrono::NaiveDate;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    /// Another device's archive whose IDs collide with the local records.
+    fn archive() -> Archive {
+        let task = |id: i32, task_id: i32, name: &str, day: u32| Task {
+            id: Some(id),
+            task_id: Some(task_id),
+            timestamp: Some(format!("2024-03-{:02} 12:00:00", day)),
+            project_id: Some(1),
+            ..Task::new(name, "", Some(50))
+        };
+        Archive::new(
+            vec![],
+            vec![task(1, 1, "Remote task", 4), task(2, 1, "Remote task", 5)],
+            vec![TaskNote {
+                id: 1,
+                task_id: 1,
+                timestamp: "2024-03-04 13:00:00".to_string(),
+                text: "Remote note".to_string(),
+            }],
+            vec![JournalEntry {
+                id: 1,
+                timestamp: "2024-03-04 18:00:00".to_string(),
+                text: "Remote entry".to_string(),
+            }],
+            vec![Project {
+                id: Some(1),
+                ..Project::new("Remote project")
+            }],
+        )
+    }
+
+    #[test]
+    fn merge_adds_foreign_records_under_new_ids() {
+        let conn = memory_db().unwrap();
+        Projects::new().unwrap().insert(&Project::new("Local project")).unwrap();
+        TaskFixture::new("Local task", date(4)).project(1).insert(&conn).unwrap();
+
+        assert_eq!(merge_records(&archive(), true).unwrap(), (2, 1, 1, 1));
+
+        let local = Tasks::new().unwrap().fetch(TaskFilter::ByRowIds(vec![1])).unwrap();
+        assert_eq!(local[0].name, "Local task");
+        assert_eq!(local[0].project_id, Some(1));
+        let remote_project = Projects::new().unwrap().find("Remote project").unwrap().unwrap();
+        let remote: Vec<Task> = Tasks::new()
+            .unwrap()
+            .fetch(TaskFilter::All)
+            .unwrap()
+            .into_iter()
+            .filter(|task| task.name == "Remote task")
+            .collect();
+        assert_eq!(remote.len(), 2);
+        assert!(remote.iter().all(|task| task.id != Some(1)));
+        assert!(remote.iter().all(|task| task.project_id == remote_project.id));
+        assert_eq!(remote[0].chain_id(), remote[1].chain_id());
+        let notes = TaskNotes::new().unwrap().fetch(remote[0].chain_id()).unwrap();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].text, "Remote note");
+        assert_eq!(Journal::new().unwrap().fetch(None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn merging_the_same_archive_twice_adds_nothing() {
+        memory_db().unwrap();
+        merge_records(&archive(), true).unwrap();
+
+        assert_eq!(merge_records(&archive(), false).unwrap(), (0, 0, 0, 0));
+        assert_eq!(merge_records(&archive(), true).unwrap(), (0, 0, 0, 0));
+        assert_eq!(Tasks::new().unwrap().fetch(TaskFilter::All).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn dry_run_merge_writes_nothing() {
+        memory_db().unwrap();
+
+        assert_eq!(merge_records(&archive(), false).unwrap(), (2, 1, 1, 1));
+        assert!(Tasks::new().unwrap().fetch(TaskFilter::All).unwrap().is_empty());
+        assert!(Projects::new().unwrap().fetch().unwrap().is_empty());
+    }
+}
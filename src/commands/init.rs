@@ -1,22 +1,92 @@
-use crate::libs::{config::Config, scheduler::Scheduler};
+use crate::{
+    api::{gitlab::GitLab, jira::Jira, si::Si, Session},
+    libs::{config::Config, interactive, scheduler::Scheduler},
+};
 use clap::Args;
+use dialoguer::{theme::ColorfulTheme, Confirm};
 use std::error::Error;
 
 #[derive(Debug, Args)]
 pub struct InitArgs {
     #[arg(short, long)]
     delete: bool,
+    #[arg(
+        long,
+        conflicts_with = "delete",
+        help = "Check the autostart tasks and re-register them if they are missing or stale"
+    )]
+    verify: bool,
 }
 
-pub fn cmd(init_args: InitArgs) -> Result<(), Box<dyn Error>> {
+pub async fn cmd(init_args: InitArgs) -> Result<(), Box<dyn Error>> {
+    if interactive::is_disabled() && !init_args.delete && !init_args.verify {
+        return Err(interactive::required("The configuration", "run `kasl init` without --yes or edit config.json"));
+    }
     let _ = Config::set_app_global();
     if init_args.delete {
         Scheduler::delete()?;
 
         return Ok(());
     }
-    Scheduler::new()?;
-    Config::init()?.save()?;
+    if init_args.verify {
+        match Scheduler::verify()? {
+            true => println!("Autostart tasks are up to date"),
+            false => {
+                Scheduler::new()?;
+                println!("Autostart tasks were missing or pointed to another location and have been re-registered");
+            }
+        }
+
+        return Ok(());
+    }
+    autostart()?;
+    let config = Config::init()?;
+    config.save()?;
+    if Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Test the configured API connections now?")
+        .default(true)
+        .interact()?
+    {
+        check_connections(&config).await;
+    }
 
     Ok(())
 }
+
+/// Autostart relies on the Windows Task Scheduler; other platforms have to start kasl themselves.
+fn autostart() -> Result<(), Box<dyn Error>> {
+    if !cfg!(windows) {
+        println!("Autostart is only available on Windows, run `kasl start` and `kasl end` from your session scripts instead");
+        return Ok(());
+    }
+    if Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Register autostart tasks for logon, lock and unlock?")
+        .default(true)
+        .interact()?
+    {
+        Scheduler::new()?;
+    }
+
+    Ok(())
+}
+
+async fn check_connections(config: &Config) {
+    let print_result = |name: &str, result: Result<(), Box<dyn Error>>| match result {
+        Ok(_) => println!("{}: connected", name),
+        Err(e) => println!("{}: failed ({})", name, e),
+    };
+    if let Some(si_config) = &config.si {
+        let mut si = Si::new(si_config);
+        let _ = si.delete_session_id();
+        print_result("SiServer", si.get_session_id().await.map(|_| ()));
+    }
+    if let Some(jira_config) = &config.jira {
+        let mut jira = Jira::new(jira_config);
+        let _ = jira.delete_session_id();
+        print_result("Jira", jira.get_session_id().await.map(|_| ()));
+    }
+    for gitlab_config in config.gitlab.iter() {
+        let result = GitLab::new(gitlab_config).get_user_id().await;
+        print_result(&format!("GitLab {}", gitlab_config.label()), result.map(|_| ()).map_err(|e| e.into()));
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{
+    db::{events::Events, projects::Projects, tasks::Tasks},
+    libs::{
+        config::Config,
+        dry_run,
+        error::KaslError,
+        event::{EventGroup, EventGroupDuration},
+        invoice::Invoice,
+        task::TaskFilter,
+        view::View,
+    },
+};
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use clap::Args;
+use std::{collections::HashMap, error::Error, path::PathBuf};
+
+#[derive(Debug, Args)]
+pub struct InvoiceArgs {
+    #[arg(short, long, help = "Month in YYYY-MM format (defaults to the current month)")]
+    month: Option<String>,
+    #[arg(short, long, help = "Write the invoice with its project breakdown to a CSV file")]
+    output: Option<PathBuf>,
+}
+
+pub fn cmd(invoice_args: InvoiceArgs) -> Result<(), Box<dyn Error>> {
+    let rate = Config::read()?
+        .rate
+        .ok_or_else(|| KaslError::config("Hourly rate is not configured, run `kasl init` first"))?;
+    let month = match invoice_args.month {
+        Some(month) => NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d").map_err(|_| format!("Invalid month: {}", month))?,
+        None => Local::now().date_naive().with_day(1).unwrap(),
+    };
+    let month_end = month.checked_add_months(chrono::Months::new(1)).unwrap() - Duration::days(1);
+
+    let durations: HashMap<NaiveDate, Duration> = Events::new()?
+        .fetch_range(month, month_end)?
+        .group_events()
+        .calc()
+        .0
+        .into_iter()
+        .map(|(date, (_, duration))| (date, duration))
+        .collect();
+    let tasks = Tasks::new()?.fetch(TaskFilter::Range(month, month_end))?;
+    let invoice = Invoice::new(&rate, durations, &tasks, &Projects::new()?.fetch()?);
+
+    println!("\nInvoice for {}", month.format("%B, %Y"));
+    View::invoice(&invoice)?;
+
+    if let Some(path) = invoice_args.output {
+        if dry_run::is_enabled() {
+            dry_run::print(&format!("Would save the invoice to {}", path.display()));
+            return Ok(());
+        }
+        invoice.save_csv(&path)?;
+        println!("Invoice has been saved to {}", path.display());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::{
+    db::journal::Journal,
+    libs::{date, dry_run, view::View},
+};
+use chrono::{Local, NaiveDate};
+use clap::Args;
+use std::error::Error;
+
+#[derive(Debug, Args)]
+pub struct JournalArgs {
+    #[arg(help = "Journal entry text; without it the entries of the day are shown")]
+    text: Option<String>,
+    #[arg(long, value_name = "DATE", value_parser = date::parse_arg, conflicts_with = "text", help = "Show the entries of another day, e.g. 2024-08-01, yesterday or last friday")]
+    date: Option<NaiveDate>,
+}
+
+pub fn cmd(journal_args: JournalArgs) -> Result<(), Box<dyn Error>> {
+    let mut journal = Journal::new()?;
+    if let Some(text) = journal_args.text {
+        if dry_run::is_enabled() {
+            dry_run::print(&format!("INSERT journal entry {}", text));
+            return Ok(());
+        }
+        journal.insert(&text)?;
+        println!("Journal entry has been added");
+        return Ok(());
+    }
+
+    let date = journal_args.date.unwrap_or_else(|| Local::now().date_naive());
+    let entries = journal.fetch(Some(date))?;
+    if entries.is_empty() {
+        println!("No journal entries for {}", date.format("%B %-d, %Y"));
+        return Ok(());
+    }
+    println!("\nJournal for {}", date.format("%B %-d, %Y"));
+    View::journal(&entries)?;
+
+    Ok(())
+}
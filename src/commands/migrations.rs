@@ -0,0 +1,62 @@
+use crate::{
+    db::{db::Db, migrations},
+    libs::{dry_run, error::KaslError},
+};
+use clap::{Args, Subcommand};
+use std::error::Error;
+
+#[derive(Debug, Subcommand)]
+enum MigrationsCommand {
+    #[command(about = "Show the current and the latest schema version")]
+    Status,
+    #[command(about = "Revert schema migrations, dropping the data they added")]
+    Rollback {
+        #[arg(long, help = "Target schema version (defaults to one version back)")]
+        to: Option<usize>,
+        #[arg(long, help = "Confirm that data added by the reverted migrations is dropped")]
+        yes_i_know: bool,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct MigrationsArgs {
+    #[command(subcommand)]
+    command: MigrationsCommand,
+}
+
+pub fn cmd(migrations_args: MigrationsArgs) -> Result<(), Box<dyn Error>> {
+    let db = Db::new()?;
+    let version = migrations::version(&db.conn)?;
+    match migrations_args.command {
+        MigrationsCommand::Status => {
+            println!("Schema version {} of {}", version, migrations::latest());
+        }
+        MigrationsCommand::Rollback { to, yes_i_know } => {
+            let to = to.unwrap_or(version.saturating_sub(1));
+            if to >= version {
+                println!("Schema version is already {}", version);
+                return Ok(());
+            }
+            if to == 0 {
+                return Err(KaslError::validation(
+                    "Version 0 has no tables, rolling back to it would drop all events and tasks; save them with `kasl export` and remove kasl.db instead",
+                ));
+            }
+            if dry_run::is_enabled() {
+                dry_run::print(&format!("Would roll the schema back from version {} to {}", version, to));
+                return Ok(());
+            }
+            if !yes_i_know && !cfg!(debug_assertions) {
+                return Err(KaslError::validation(
+                    "Rolling back drops the tables and columns added by the reverted migrations, pass --yes-i-know to proceed",
+                ));
+            }
+            let backup_path = Db::backup(&db.conn)?;
+            println!("Backup saved to {}", backup_path.display());
+            migrations::rollback(&db.conn, to)?;
+            println!("Schema rolled back from version {} to {}", version, to);
+        }
+    }
+
+    Ok(())
+}
@@ -1,15 +1,33 @@
+pub mod api;
+pub mod db;
+pub mod diag;
 pub mod event;
+pub mod export;
+pub mod import;
 pub mod init;
+pub mod invoice;
+pub mod journal;
+pub mod migrations;
+pub mod project;
+pub mod remind;
 pub mod report;
+pub mod secret;
+pub mod stats;
+pub mod status;
 pub mod sum;
 pub mod task;
 pub mod update;
 pub mod watch;
 
-use crate::libs::event::EventType;
+use crate::libs::{data_storage::DATA_DIR_ENV, dry_run, event::EventType, interactive, summary::MorningSummary, view::TableOptions};
 use clap::{Parser, Subcommand};
 use event::EventArgs;
-use std::error::Error;
+use std::{
+    env,
+    error::Error,
+    io::{self, IsTerminal},
+    path::PathBuf,
+};
 
 #[derive(Debug, Subcommand)]
 enum Commands {
@@ -17,10 +35,22 @@ enum Commands {
     Init(init::InitArgs),
     #[command(about = "Create task")]
     Task(task::TaskArgs),
+    #[command(about = "Manage projects that tasks belong to")]
+    Project(project::ProjectArgs),
+    #[command(about = "Mark tasks as complete")]
+    Done {
+        #[arg(required = true, help = "Task IDs")]
+        ids: Vec<i32>,
+    },
+    #[command(name = "t", about = "Quick-capture a task, e.g. kasl t \"fix login bug #urgent @backend 50%\"")]
+    QuickTask {
+        #[arg(required = true, trailing_var_arg = true, help = "Task name with optional #tags, @project and N% completeness")]
+        text: Vec<String>,
+    },
     #[command(about = "Write timestamp and event type to database", arg_required_else_help = true)]
     Event(event::EventArgs),
-    #[command(about = "Write start timestamp to database")]
-    Start,
+    #[command(about = "Write start timestamp to database, or set the workday start retroactively with --at")]
+    Start(event::StartArgs),
     #[command(about = "Write end timestamp to database")]
     End,
     #[command(about = "Get summary")]
@@ -30,13 +60,81 @@ enum Commands {
     #[command(about = "Prepare a report")]
     Report(report::ReportArgs),
     #[command(about = "Watch")]
-    Watch,
+    Watch(watch::WatchArgs),
+    #[command(about = "Export the entire history to a JSON archive")]
+    Export(export::ExportArgs),
+    #[command(about = "Import a JSON archive created by the export command")]
+    Import(import::ImportArgs),
+    #[command(about = "Prepare a monthly invoice")]
+    Invoice(invoice::InvoiceArgs),
+    #[command(about = "Show long-term statistics")]
+    Stats(stats::StatsArgs),
+    #[command(about = "Add a timestamped journal entry or show the entries of a day")]
+    Journal(journal::JournalArgs),
+    #[command(about = "Show a compact snapshot of the current workday")]
+    Status(status::StatusArgs),
+    #[command(about = "Collect diagnostics for bug reports")]
+    Diag(diag::DiagArgs),
+    #[command(about = "Check the database, configuration and the health of `kasl watch`")]
+    Doctor,
+    #[command(about = "Inspect or roll back the database schema")]
+    Migrations(migrations::MigrationsArgs),
+    #[command(about = "Maintain the database")]
+    Db(db::DbArgs),
+    #[command(about = "Inspect, refresh or remove stored credentials")]
+    Secret(secret::SecretArgs),
+    #[command(about = "Check the connection to the configured integrations")]
+    Api(api::ApiArgs),
+    #[command(about = "Manage recurring reminders shown while `kasl watch` runs")]
+    Remind(remind::RemindArgs),
+}
+
+impl Commands {
+    /// Commands that run other programs or wait for input instead of writing records, so a dry run cannot preview them.
+    fn unsupported_in_dry_run(&self) -> Option<&'static str> {
+        match self {
+            Self::Init(_) => Some("kasl init"),
+            Self::Update => Some("kasl update"),
+            Self::Watch(_) => Some("kasl watch"),
+            _ => None,
+        }
+    }
+
+    /// Commands people type themselves; the banner would get lost in scheduled `event` runs, status bars and scripts.
+    fn shows_morning_summary(&self) -> bool {
+        matches!(
+            self,
+            Self::Task(_)
+                | Self::Project(_)
+                | Self::Done { .. }
+                | Self::QuickTask { .. }
+                | Self::Start(_)
+                | Self::Sum(_)
+                | Self::Report(_)
+                | Self::Invoice(_)
+                | Self::Stats(_)
+                | Self::Journal(_)
+                | Self::Remind(_)
+        )
+    }
 }
 
 #[derive(Debug, Parser)]
 #[command(author, version, about, long_about = None)]
 #[command(arg_required_else_help(true))]
 pub struct Cli {
+    #[arg(long, global = true, help = "Directory for the database, configuration and other data files")]
+    data_dir: Option<PathBuf>,
+    #[arg(long, global = true, help = "Show what would change without writing anything")]
+    dry_run: bool,
+    #[arg(
+        long,
+        short,
+        visible_alias = "non-interactive",
+        global = true,
+        help = "Accept confirmations and use defaults instead of prompting"
+    )]
+    yes: bool,
     #[command(subcommand)]
     command: Commands,
 }
@@ -44,24 +142,73 @@ pub struct Cli {
 impl Cli {
     pub async fn menu() -> Result<(), Box<dyn Error>> {
         let cli = Self::parse();
+        if let Some(data_dir) = cli.data_dir {
+            env::set_var(DATA_DIR_ENV, data_dir);
+        }
+        if cli.dry_run {
+            if let Some(command) = cli.command.unsupported_in_dry_run() {
+                return Err(dry_run::unsupported(command));
+            }
+            dry_run::enable();
+        }
+        if cli.yes {
+            interactive::assume_yes();
+        }
+        if cli.command.shows_morning_summary() && io::stdout().is_terminal() {
+            MorningSummary::show();
+        }
         match cli.command {
-            Commands::Init(args) => init::cmd(args),
+            Commands::Init(args) => init::cmd(args).await,
             Commands::Task(args) => task::cmd(args).await,
+            Commands::Project(args) => project::cmd(args),
+            Commands::QuickTask { text } => task::quick(text),
+            Commands::Done { ids } => task::done(ids),
             Commands::Event(args) => event::cmd(args),
-            Commands::Start => event::cmd(EventArgs {
-                event_type: EventType::Start,
-                show: false,
-                raw: false,
-            }),
+            Commands::Start(args) => event::start(args),
             Commands::End => event::cmd(EventArgs {
                 event_type: EventType::End,
                 show: false,
                 raw: false,
+                date: None,
+                validate: false,
+                fix: false,
+                table: TableOptions::default(),
             }),
             Commands::Sum(args) => sum::cmd(args).await,
             Commands::Report(args) => report::cmd(args).await,
             Commands::Update => update::cmd().await,
-            Commands::Watch => Ok(watch::cmd()),
+            Commands::Watch(args) => watch::cmd(args).await,
+            Commands::Export(args) => export::cmd(args).await,
+            Commands::Import(args) => import::cmd(args),
+            Commands::Invoice(args) => invoice::cmd(args),
+            Commands::Stats(args) => stats::cmd(args),
+            Commands::Journal(args) => journal::cmd(args),
+            Commands::Status(args) => status::cmd(args),
+            Commands::Diag(args) => diag::cmd(args),
+            Commands::Doctor => diag::doctor(),
+            Commands::Migrations(args) => migrations::cmd(args),
+            Commands::Db(args) => db::cmd(args),
+            Commands::Secret(args) => secret::cmd(args),
+            Commands::Api(args) => api::cmd(args).await,
+            Commands::Remind(args) => remind::cmd(args),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_definition_is_consistent() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn event_date_only_applies_to_shown_events() {
+        assert!(Cli::try_parse_from(["kasl", "event", "--date", "yesterday"]).is_err());
+        assert!(Cli::try_parse_from(["kasl", "event", "--show", "--date", "yesterday"]).is_ok());
+        assert!(Cli::try_parse_from(["kasl", "event", "--raw", "--date", "2024-08-01"]).is_ok());
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{
+    db::{projects::Projects, tasks::Tasks},
+    libs::{dry_run, error::KaslError, project::Project, task::TaskFilter, view::View},
+};
+use clap::{Args, Subcommand};
+use std::error::Error;
+
+#[derive(Debug, Subcommand)]
+enum ProjectCommand {
+    #[command(about = "Create a project")]
+    Add {
+        #[arg(help = "Project name")]
+        name: String,
+        #[arg(long, help = "Client the project is done for")]
+        client: Option<String>,
+        #[arg(long, help = "Comma-separated default tags")]
+        tags: Option<String>,
+        #[arg(long, help = "Hourly rate, overrides the configured one for this project")]
+        rate: Option<f64>,
+    },
+    #[command(about = "List projects")]
+    List,
+    #[command(about = "Change a project")]
+    Edit {
+        #[arg(help = "Project ID or name")]
+        project: String,
+        #[arg(long, help = "New project name")]
+        name: Option<String>,
+        #[arg(long, help = "Client the project is done for")]
+        client: Option<String>,
+        #[arg(long, help = "Comma-separated default tags")]
+        tags: Option<String>,
+        #[arg(long, help = "Hourly rate, overrides the configured one for this project")]
+        rate: Option<f64>,
+    },
+    #[command(about = "Delete a project, its tasks are kept without a project")]
+    Delete {
+        #[arg(help = "Project ID or name")]
+        project: String,
+    },
+    #[command(about = "Assign tasks to a project")]
+    Assign {
+        #[arg(help = "Project ID or name")]
+        project: String,
+        #[arg(required = true, help = "Task IDs")]
+        ids: Vec<i32>,
+    },
+    #[command(about = "Remove tasks from their project")]
+    Unassign {
+        #[arg(required = true, help = "Task IDs")]
+        ids: Vec<i32>,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct ProjectArgs {
+    #[command(subcommand)]
+    command: ProjectCommand,
+}
+
+pub fn cmd(project_args: ProjectArgs) -> Result<(), Box<dyn Error>> {
+    let mut projects = Projects::new()?;
+    match project_args.command {
+        ProjectCommand::Add { name, client, tags, rate } => {
+            if projects.find(&name)?.is_some() {
+                return Err(KaslError::validation(format!("Project {} already exists", name)));
+            }
+            let project = Project {
+                client,
+                tags: tags.as_deref().map(Project::parse_tags).unwrap_or_default(),
+                hourly_rate: rate,
+                ..Project::new(&name)
+            };
+            if dry_run::is_enabled() {
+                dry_run::print(&format!("INSERT project {}", name));
+                return Ok(());
+            }
+            let id = projects.insert(&project)?;
+            println!("Project {} has been created with ID {}", name, id);
+        }
+        ProjectCommand::List => {
+            let projects = projects.fetch()?;
+            if projects.is_empty() {
+                println!("No projects yet, create one with `kasl project add`");
+                return Ok(());
+            }
+            View::projects(&projects)?;
+        }
+        ProjectCommand::Edit {
+            project,
+            name,
+            client,
+            tags,
+            rate,
+        } => {
+            let mut project = find(&mut projects, &project)?;
+            if let Some(name) = name {
+                project.name = name;
+            }
+            if client.is_some() {
+                project.client = client;
+            }
+            if let Some(tags) = tags {
+                project.tags = Project::parse_tags(&tags);
+            }
+            if rate.is_some() {
+                project.hourly_rate = rate;
+            }
+            if dry_run::is_enabled() {
+                dry_run::print(&format!("UPDATE project {}", project.id.unwrap_or_default()));
+                return View::projects(&[project]);
+            }
+            projects.update(&project)?;
+            View::projects(&[project])?;
+        }
+        ProjectCommand::Delete { project } => {
+            let project = find(&mut projects, &project)?;
+            if dry_run::is_enabled() {
+                dry_run::print(&format!("DELETE project {} and clear it from its tasks", project.name));
+                return Ok(());
+            }
+            let count = projects.delete(project.id.unwrap_or_default())?;
+            println!("Project {} has been deleted, {} task records no longer belong to it", project.name, count);
+        }
+        ProjectCommand::Assign { project, ids } => {
+            let project = find(&mut projects, &project)?;
+            let chain_ids = chain_ids(&ids)?;
+            if dry_run::is_enabled() {
+                dry_run::print(&format!("UPDATE the records of {} tasks SET project {}", chain_ids.len(), project.name));
+                return Ok(());
+            }
+            let count = Tasks::new()?.assign_project(&chain_ids, project.id)?;
+            println!("{} task records have been assigned to {}", count, project.name);
+        }
+        ProjectCommand::Unassign { ids } => {
+            let chain_ids = chain_ids(&ids)?;
+            if dry_run::is_enabled() {
+                dry_run::print(&format!("UPDATE the records of {} tasks SET project = NULL", chain_ids.len()));
+                return Ok(());
+            }
+            let count = Tasks::new()?.assign_project(&chain_ids, None)?;
+            println!("{} task records have been removed from their project", count);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn find(projects: &mut Projects, project: &str) -> Result<Project, Box<dyn Error>> {
+    Ok(projects
+        .find(project)?
+        .ok_or_else(|| KaslError::validation(format!("Project {} not found, see `kasl project list`", project)))?)
+}
+
+/// Chain IDs of the given tasks, so a project applies to every day a task was worked on.
+fn chain_ids(ids: &[i32]) -> Result<Vec<i32>, Box<dyn Error>> {
+    let tasks = Tasks::new()?.fetch(TaskFilter::ByRowIds(ids.to_vec()))?;
+    if tasks.is_empty() {
+        return Err(KaslError::validation("Tasks not found"));
+    }
+
+    Ok(tasks.iter().filter_map(|task| task.chain_id()).collect())
+}
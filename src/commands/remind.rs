@@ -0,0 +1,72 @@
+use crate::{
+    db::reminders::Reminders,
+    libs::{dry_run, error::KaslError, reminder::Reminder, view::View},
+};
+use clap::{Args, Subcommand};
+use std::error::Error;
+
+#[derive(Debug, Subcommand)]
+enum RemindCommand {
+    #[command(about = "Add a reminder shown by `kasl watch` after every N active minutes")]
+    Add {
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..), help = "Active minutes between reminders")]
+        every: u32,
+        #[arg(help = "Message to show, e.g. \"Stand up\"")]
+        message: String,
+        #[arg(long, help = "Shell command to run with the message, e.g. a desktop notification")]
+        command: Option<String>,
+    },
+    #[command(about = "List reminders")]
+    List,
+    #[command(about = "Remove a reminder")]
+    Remove {
+        #[arg(help = "Reminder ID")]
+        id: i32,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct RemindArgs {
+    #[command(subcommand)]
+    command: RemindCommand,
+}
+
+pub fn cmd(remind_args: RemindArgs) -> Result<(), Box<dyn Error>> {
+    let mut reminders = Reminders::new()?;
+    match remind_args.command {
+        RemindCommand::Add { every, message, command } => {
+            let reminder = Reminder {
+                id: None,
+                minutes: every,
+                message,
+                command,
+            };
+            if dry_run::is_enabled() {
+                dry_run::print(&format!("INSERT reminder every {} minutes: {}", reminder.minutes, reminder.message));
+                return Ok(());
+            }
+            let id = reminders.insert(&reminder)?;
+            println!("Reminder {} has been added, restart `kasl watch` to pick it up", id);
+        }
+        RemindCommand::List => {
+            let reminders = reminders.fetch()?;
+            if reminders.is_empty() {
+                println!("No reminders yet, add one with `kasl remind add`");
+                return Ok(());
+            }
+            View::reminders(&reminders)?;
+        }
+        RemindCommand::Remove { id } => {
+            if dry_run::is_enabled() {
+                dry_run::print(&format!("DELETE reminder {}", id));
+                return Ok(());
+            }
+            if reminders.delete(id)? == 0 {
+                return Err(KaslError::validation(format!("Reminder {} not found, see `kasl remind list`", id)));
+            }
+            println!("Reminder {} has been removed", id);
+        }
+    }
+
+    Ok(())
+}
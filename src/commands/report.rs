@@ -1,18 +1,24 @@
 use crate::{
-    api::si::Si,
+    api::{endpoint::Endpoint, si::Si},
     db::{
         events::{Events, SelectRequest},
+        journal::Journal,
+        reports::{Reports, SubmissionFilter},
         tasks::Tasks,
     },
     libs::{
+        clipboard,
         config::Config,
-        event::{EventGroup, EventType, FormatEvents},
+        date, dry_run,
+        event::{Event, EventGroup, EventType, FormatEvent, FormatEvents},
+        interactive, report,
         task::{FormatTasks, Task, TaskFilter},
-        view::View,
+        view::{TableOptions, View},
     },
 };
-use chrono::{Duration, Local};
+use chrono::{Duration, Local, NaiveDate};
 use clap::Args;
+use reqwest::StatusCode;
 use std::error::Error;
 
 #[derive(Debug, Args)]
@@ -21,91 +27,249 @@ pub struct ReportArgs {
     send: bool,
     #[arg(long, short, help = "Last day report")]
     last: bool,
+    #[arg(long, requires = "send", help = "Send the report even if working hours are below the configured minimum")]
+    force: bool,
+    #[arg(long, conflicts_with_all = ["send", "send_all"], help = "List past workdays without a submitted report")]
+    pending: bool,
+    #[arg(long, conflicts_with = "send", help = "Send reports for all past workdays without a submitted report")]
+    send_all: bool,
+    #[arg(long, value_name = "DATE", conflicts_with_all = ["last", "pending", "send_all"], value_parser = date::parse_arg, help = "Report for another day, e.g. 2024-08-01, yesterday or 2 days ago")]
+    date: Option<NaiveDate>,
+    #[arg(long, value_name = "DATE", value_parser = date::parse_arg, conflicts_with_all = ["send", "send_all", "pending"], help = "Show all submission attempts for a date, e.g. 2024-08-01 or last friday")]
+    history: Option<NaiveDate>,
+    #[arg(long, conflicts_with_all = ["send", "send_all", "pending", "history"], help = "Copy the rendered report to the clipboard")]
+    copy: bool,
+    #[arg(long, conflicts_with_all = ["send", "send_all", "pending", "history"], help = "Replace task names with pseudonyms and leave out comments, links and notes")]
+    redact: bool,
 }
 
 pub async fn cmd(report_args: ReportArgs) -> Result<(), Box<dyn Error>> {
-    let mut date = Local::now();
+    let today = Local::now().date_naive();
+    if let Some(date) = report_args.history {
+        let submissions = Reports::new()?.fetch(&date, SubmissionFilter::All)?;
+        if submissions.is_empty() {
+            println!("No reports have been submitted for {}", date.format("%B %-d, %Y"));
+            return Ok(());
+        }
+        println!("\nSubmissions for {}", date.format("%B %-d, %Y"));
+        View::report_history(&submissions)?;
+
+        return Ok(());
+    }
+    if report_args.pending || report_args.send_all {
+        let pending_dates = report::pending_dates(today)?;
+        if pending_dates.is_empty() {
+            println!("All reports for the last {} days have been submitted", report::PENDING_DAYS);
+            return Ok(());
+        }
+        if report_args.pending {
+            println!("\nWorkdays without a submitted report:");
+            for date in pending_dates.iter() {
+                println!("  {}", date.format("%B %-d, %Y"));
+            }
+            return Ok(());
+        }
+        if pending_dates.len() > 1
+            && !dry_run::is_enabled()
+            && !interactive::confirm(
+                &format!("Send {} reports, starting with {}?", pending_dates.len(), pending_dates[0].format("%B %-d, %Y")),
+                false,
+            )?
+        {
+            return Ok(());
+        }
+        for date in pending_dates {
+            send(date, false, false).await?;
+        }
+
+        return Ok(());
+    }
+
+    let mut date = report_args.date.unwrap_or(today);
     if report_args.last {
         date = date - Duration::days(1);
     }
 
-    let events = Events::new()?
-        .fetch(SelectRequest::Daily, date.date_naive())?
-        .merge()
-        .update_duration()
-        .total_duration()
-        .format();
-    let mut tasks = Tasks::new()?.fetch(TaskFilter::Date(date.date_naive()))?;
-
     if report_args.send {
-        if tasks.is_empty() {
-            println!("Tasks not found((");
+        return send(date, report_args.force, date == today).await;
+    }
+
+    let mut daily_events = daily_events(date)?;
+    let events = daily_events.format();
+    let mut tasks = Tasks::new()?.fetch(TaskFilter::Date(date))?;
+    if report_args.redact {
+        tasks = tasks.iter().map(Task::redacted).collect();
+    }
+    let mut output = format!(
+        "\nReport for {}\n{}",
+        date.format("%B %-d, %Y"),
+        View::events_table(&events, &TableOptions::default())?
+    );
+    if let Some(rounding) = Config::read().ok().and_then(|config| config.rounding) {
+        output.push_str(&format!("{}\n", rounding.describe()));
+    }
+    if !tasks.is_empty() {
+        output.push_str(&format!("\nTasks:\n{}", View::tasks_table(&tasks, &TableOptions::default())?));
+        let links: Vec<&Task> = tasks.iter().filter(|task| task.external_url.is_some()).collect();
+        if !links.is_empty() {
+            output.push_str("\nLinks:\n");
+            for task in links {
+                output.push_str(&format!("  {}: {}\n", task.name, task.external_url.as_deref().unwrap_or_default()));
+            }
+        }
+    }
+    let journal = Journal::new()?.fetch(Some(date))?;
+    if !journal.is_empty() && !report_args.redact {
+        output.push_str(&format!("\nNotes:\n{}", View::journal_table(&journal)));
+    }
+    print!("{}", output);
+    if report_args.copy {
+        clipboard::copy(output.trim())?;
+        println!("\nThe report has been copied to the clipboard");
+    }
+
+    Ok(())
+}
+
+/// Merged work intervals of `date` and their total, rounded when a rounding policy is configured.
+fn daily_events(date: NaiveDate) -> Result<(Vec<Event>, Duration), Box<dyn Error>> {
+    let daily_events = Events::new()?.fetch(SelectRequest::Daily, date)?.merge().update_duration().total_duration();
+
+    Ok(match Config::read().ok().and_then(|config| config.rounding) {
+        Some(rounding) => rounding.apply(daily_events),
+        None => daily_events,
+    })
+}
+
+/// Submits the report for `date`; `finish_day` also records an end event after a successful submission.
+async fn send(date: NaiveDate, force: bool, finish_day: bool) -> Result<(), Box<dyn Error>> {
+    let mut daily_events = daily_events(date)?;
+    let total_duration = daily_events.1;
+    let events = daily_events.format();
+    let mut tasks = Tasks::new()?.fetch(TaskFilter::Date(date))?;
+
+    if tasks.is_empty() {
+        println!("Tasks for {} not found((", date.format("%B %-d, %Y"));
+        return Ok(());
+    }
+
+    if let Some(report_config) = Config::read().ok().and_then(|config| config.report) {
+        if total_duration < report_config.min_duration() && !force {
+            println!(
+                "Working hours {} on {} are below the required minimum of {}\nReview the day with `kasl event --show` or use --force to send the report anyway",
+                FormatEvent::format_duration(Some(total_duration)),
+                date.format("%B %-d, %Y"),
+                FormatEvent::format_duration(Some(report_config.min_duration()))
+            );
             return Ok(());
         }
+    }
+
+    let task_chunks: Vec<Vec<Task>> = tasks.divide(events.0.len());
 
-        let task_chunks: Vec<Vec<Task>> = tasks.divide(events.0.len());
-
-        let events_json = events
-            .0
-            .iter()
-            .enumerate()
-            .map(|(index, event)| {
-                serde_json::json!({
-                    "index": event.id,
-                    "from": event.start,
-                    "to": event.end,
-                    "total_ts": event.duration,
-                    "task": task_chunks.get(index).unwrap().to_owned().format(),
-                    "data": [],
-                    "time": "",
-                    "result": ""
-                })
+    let events_json = events
+        .0
+        .iter()
+        .enumerate()
+        .map(|(index, event)| {
+            serde_json::json!({
+                "index": event.id,
+                "from": event.start,
+                "to": event.end,
+                "total_ts": event.duration,
+                "task": task_chunks.get(index).unwrap().to_owned().format(),
+                "data": [],
+                "time": "",
+                "result": ""
             })
-            .collect::<Vec<_>>();
-        let events_json = serde_json::to_string(&events_json)?;
-
-        match Config::read() {
-            Ok(config) => match config.si {
-                Some(si_config) => {
-                    let mut si = Si::new(&si_config);
-                    match si.send(&events_json, &date.date_naive()).await {
-                        Ok(status) => {
-                            if status.is_success() {
-                                let _ = Events::new()?.insert(&EventType::End);
-                                println!(
-                                    "Your report dated {} has been successfully submitted\nWait for a message to your email address",
-                                    date.format("%B %-d, %Y")
-                                );
-                                if si.is_last_working_day_of_month(&date.date_naive())? {
-                                    let monthly_status = si.send_monthly(&date.date_naive()).await?;
-                                    if monthly_status.is_success() {
-                                        println!(
-                                            "Your monthly report dated {} has been successfully submitted\nWait for a message to your email address",
-                                            date.format("%B %-d, %Y")
-                                        );
-                                    }
-                                }
-                            } else {
-                                println!("Status: {}", status);
-                            }
-                        }
-                        Err(e) => eprintln!("Error sending events: {}", e),
-                    }
+        })
+        .collect::<Vec<_>>();
+    let events_json = serde_json::to_string(&events_json)?;
+
+    if let Some(submission) = Reports::new()?.fetch_last(&date)? {
+        let diff = submission.diff(&events_json)?;
+        println!(
+            "\nThe report dated {} was already submitted at {}",
+            submission.date.format("%B %-d, %Y"),
+            submission.submitted_at.format("%Y-%m-%d %H:%M")
+        );
+        match diff.is_empty() {
+            true => println!("Nothing has changed since then"),
+            false => {
+                println!("Changes since then:");
+                for line in diff.iter() {
+                    println!("  {}", line);
                 }
-                None => eprintln!("Failed to read SiServer config"),
-            },
-            Err(e) => eprintln!("Failed to read config: {}", e),
+            }
+        }
+        if !dry_run::is_enabled() && !interactive::confirm("Send the report again?", !diff.is_empty())? {
+            return Ok(());
         }
+    }
 
+    if dry_run::is_enabled() {
+        dry_run::print(&format!(
+            "Would submit the report dated {} with {} events and {} tasks",
+            date.format("%B %-d, %Y"),
+            events.0.len(),
+            tasks.len()
+        ));
+        println!("{}", events_json);
         return Ok(());
-    } else {
-        println!("\nReport for {}", date.format("%B %-d, %Y"));
-        View::events(&events)?;
-        if !tasks.is_empty() {
-            println!("\nTasks:");
-            View::tasks(&tasks)?;
+    }
+
+    let config = match Config::read() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Failed to read config: {}", e);
+            return Ok(());
+        }
+    };
+    match (config.si, config.endpoint) {
+        (Some(si_config), _) => {
+            let mut si = Si::new(&si_config);
+            match si.send(&events_json, &date).await {
+                Ok(status) => {
+                    let accepted = submitted(&date, &events_json, status, finish_day)?;
+                    if accepted {
+                        println!("Wait for a message to your email address");
+                    }
+                    if accepted && si.is_last_working_day_of_month(&date)? {
+                        let monthly_status = si.send_monthly(&date).await?;
+                        if monthly_status.is_success() {
+                            println!(
+                                "Your monthly report dated {} has been successfully submitted\nWait for a message to your email address",
+                                date.format("%B %-d, %Y")
+                            );
+                        }
+                    }
+                }
+                Err(e) => eprintln!("Error sending events: {}", e),
+            }
         }
+        (None, Some(endpoint_config)) => match Endpoint::new(&endpoint_config).send(&events_json, &date, total_duration).await {
+            Ok(status) => {
+                submitted(&date, &events_json, status, finish_day)?;
+            }
+            Err(e) => eprintln!("Error sending events: {}", e),
+        },
+        (None, None) => eprintln!("Failed to read SiServer config, configure SiServer or a custom reporting endpoint with `kasl init`"),
     }
 
     Ok(())
 }
+
+/// Records a submission attempt and reports its outcome; returns whether it was accepted.
+fn submitted(date: &NaiveDate, events_json: &str, status: StatusCode, finish_day: bool) -> Result<bool, Box<dyn Error>> {
+    Reports::new()?.insert(date, events_json, status.as_u16())?;
+    if !status.is_success() {
+        println!("Status: {}", status);
+        return Ok(false);
+    }
+    if finish_day {
+        Events::new()?.insert(&EventType::End)?;
+    }
+    println!("Your report dated {} has been successfully submitted", date.format("%B %-d, %Y"));
+
+    Ok(true)
+}
@@ -0,0 +1,213 @@
+use crate::{
+    api::{endpoint, jira, notion, si, trello, webdav},
+    libs::{
+        config::Config,
+        data_storage::DataStorage,
+        dry_run,
+        error::KaslError,
+        secret::{self, Secret, SecretStatus},
+        view::View,
+    },
+};
+use clap::{Args, Subcommand, ValueEnum};
+use std::{error::Error, fs};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Service {
+    Endpoint,
+    Gitlab,
+    Jira,
+    Notion,
+    Si,
+    Trello,
+    Webdav,
+}
+
+/// A credential file in the data directory; files without a prompt are session IDs cached after a login.
+struct StoredSecret {
+    service: Service,
+    name: &'static str,
+    file: &'static str,
+    prompt: Option<&'static str>,
+}
+
+const STORED_SECRETS: &[StoredSecret] = &[
+    StoredSecret {
+        service: Service::Endpoint,
+        name: "token",
+        file: endpoint::SECRET_FILE,
+        prompt: Some(endpoint::SECRET_PROMPT),
+    },
+    StoredSecret {
+        service: Service::Jira,
+        name: "password",
+        file: jira::SECRET_FILE,
+        prompt: Some(jira::SECRET_PROMPT),
+    },
+    StoredSecret {
+        service: Service::Jira,
+        name: "session",
+        file: jira::SESSION_ID_FILE,
+        prompt: None,
+    },
+    StoredSecret {
+        service: Service::Notion,
+        name: "token",
+        file: notion::SECRET_FILE,
+        prompt: Some(notion::SECRET_PROMPT),
+    },
+    StoredSecret {
+        service: Service::Notion,
+        name: "OAuth client secret",
+        file: notion::CLIENT_SECRET_FILE,
+        prompt: Some(notion::CLIENT_SECRET_PROMPT),
+    },
+    StoredSecret {
+        service: Service::Notion,
+        name: "OAuth token",
+        file: notion::OAUTH_TOKEN_FILE,
+        prompt: None,
+    },
+    StoredSecret {
+        service: Service::Si,
+        name: "password",
+        file: si::SECRET_FILE,
+        prompt: Some(si::SECRET_PROMPT),
+    },
+    StoredSecret {
+        service: Service::Si,
+        name: "session",
+        file: si::SESSION_ID_FILE,
+        prompt: None,
+    },
+    StoredSecret {
+        service: Service::Trello,
+        name: "API key",
+        file: trello::KEY_FILE,
+        prompt: Some(trello::KEY_PROMPT),
+    },
+    StoredSecret {
+        service: Service::Trello,
+        name: "token",
+        file: trello::SECRET_FILE,
+        prompt: Some(trello::SECRET_PROMPT),
+    },
+    StoredSecret {
+        service: Service::Webdav,
+        name: "password",
+        file: webdav::SECRET_FILE,
+        prompt: Some(webdav::SECRET_PROMPT),
+    },
+];
+
+#[derive(Debug, Subcommand)]
+enum SecretCommand {
+    #[command(about = "Show stored credentials and sessions with masked values")]
+    List {
+        #[arg(value_enum, help = "Show only this service")]
+        service: Option<Service>,
+    },
+    #[command(about = "Enter new credentials for a service and drop its cached session")]
+    Set {
+        #[arg(value_enum)]
+        service: Service,
+    },
+    #[command(about = "Remove stored credentials and sessions of a service")]
+    Delete {
+        #[arg(value_enum)]
+        service: Service,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct SecretArgs {
+    #[command(subcommand)]
+    command: SecretCommand,
+}
+
+pub fn cmd(secret_args: SecretArgs) -> Result<(), Box<dyn Error>> {
+    match secret_args.command {
+        SecretCommand::List { service } => list(service),
+        SecretCommand::Set { service: Service::Gitlab } | SecretCommand::Delete { service: Service::Gitlab } => Err(KaslError::validation(
+            "GitLab tokens are stored in the configuration file, change them with `kasl init`",
+        )),
+        SecretCommand::Set { .. } if dry_run::is_enabled() => Err(dry_run::unsupported("kasl secret set")),
+        SecretCommand::Set { service } => {
+            for stored_secret in secrets_of(service) {
+                if let Some(prompt) = stored_secret.prompt {
+                    Secret::new(stored_secret.file, prompt).prompt()?;
+                }
+            }
+            for stored_secret in secrets_of(service).filter(|stored_secret| stored_secret.prompt.is_none()) {
+                let path = DataStorage::new().get_path(stored_secret.file)?;
+                if path.exists() {
+                    fs::remove_file(path)?;
+                }
+            }
+            println!("Credentials have been updated, the next request logs in again");
+
+            Ok(())
+        }
+        SecretCommand::Delete { service } => {
+            let mut count = 0;
+            for stored_secret in secrets_of(service) {
+                let path = DataStorage::new().get_path(stored_secret.file)?;
+                if !path.exists() {
+                    continue;
+                }
+                match dry_run::is_enabled() {
+                    true => dry_run::print(&format!("Would remove {}", path.display())),
+                    false => fs::remove_file(path)?,
+                }
+                count += 1;
+            }
+            if !dry_run::is_enabled() {
+                println!("{} stored credentials and sessions have been removed", count);
+            }
+
+            Ok(())
+        }
+    }
+}
+
+fn secrets_of(service: Service) -> impl Iterator<Item = &'static StoredSecret> {
+    STORED_SECRETS.iter().filter(move |stored_secret| stored_secret.service == service)
+}
+
+fn list(service: Option<Service>) -> Result<(), Box<dyn Error>> {
+    let shown = |candidate: Service| service.is_none_or(|service| service == candidate);
+    let mut secrets: Vec<SecretStatus> = vec![];
+    if shown(Service::Gitlab) {
+        for gitlab_config in Config::read().map(|config| config.gitlab).unwrap_or_default() {
+            secrets.push(SecretStatus {
+                service: format!("gitlab ({})", gitlab_config.label()),
+                name: "token (config.json)".to_string(),
+                value: secret::mask(&gitlab_config.access_token),
+            });
+        }
+    }
+    for stored_secret in STORED_SECRETS.iter().filter(|stored_secret| shown(stored_secret.service)) {
+        let path = DataStorage::new().get_path(stored_secret.file)?;
+        if !path.exists() {
+            continue;
+        }
+        let value = match stored_secret.prompt {
+            Some(prompt) => Secret::new(stored_secret.file, prompt)
+                .get()
+                .map_or("<unreadable>".to_string(), |value| secret::mask(&value)),
+            None => secret::mask(&fs::read_to_string(&path)?),
+        };
+        secrets.push(SecretStatus {
+            service: format!("{:?}", stored_secret.service).to_lowercase(),
+            name: stored_secret.name.to_string(),
+            value,
+        });
+    }
+    if secrets.is_empty() {
+        println!("No stored credentials found");
+        return Ok(());
+    }
+    View::secrets(&secrets)?;
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use crate::{
+    db::events::Events,
+    libs::{
+        dry_run,
+        stats::{HourlyActivity, WorkStats},
+        view::View,
+    },
+};
+use chrono::{Duration, Local};
+use clap::Args;
+use std::{error::Error, path::PathBuf};
+
+#[derive(Debug, Args)]
+pub struct StatsArgs {
+    #[arg(long, help = "Show activity by hour of day")]
+    by_hour: bool,
+    #[arg(long, default_value_t = 30, help = "Number of days to analyze")]
+    days: i64,
+    #[arg(short, long, help = "Write the hourly statistics to a CSV file")]
+    output: Option<PathBuf>,
+}
+
+pub fn cmd(stats_args: StatsArgs) -> Result<(), Box<dyn Error>> {
+    let to = Local::now().date_naive();
+    let from = to - Duration::days(stats_args.days - 1);
+    let events = Events::new()?.fetch_range(from, to)?;
+
+    if stats_args.by_hour {
+        println!("\nAverage activity by hour from {} to {}", from.format("%B %-d, %Y"), to.format("%B %-d, %Y"));
+        let hourly_activity = HourlyActivity::new(&events, stats_args.days);
+        View::hourly_activity(&hourly_activity)?;
+        if let Some(path) = stats_args.output {
+            if dry_run::is_enabled() {
+                dry_run::print(&format!("Would save the statistics to {}", path.display()));
+                return Ok(());
+            }
+            hourly_activity.save_csv(&path)?;
+            println!("Statistics have been saved to {}", path.display());
+        }
+
+        return Ok(());
+    }
+
+    let previous_to = from - Duration::days(1);
+    let previous_from = previous_to - Duration::days(stats_args.days - 1);
+    let previous = WorkStats::new(Events::new()?.fetch_range(previous_from, previous_to)?);
+    println!("\nStatistics from {} to {}", from.format("%B %-d, %Y"), to.format("%B %-d, %Y"));
+    View::stats(&WorkStats::new(events), &previous)?;
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use crate::{
+    db::{
+        events::{Events, SelectRequest},
+        heartbeat::Heartbeats,
+        tasks::Tasks,
+    },
+    libs::{config::Config, contract::ContractConfig, event::FormatEvent, health, permissions, status::Status, task::TaskFilter, view::View},
+};
+use chrono::Local;
+use clap::{Args, ValueEnum};
+use std::error::Error;
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StatusFormat {
+    Waybar,
+    Polybar,
+    Tmux,
+}
+
+#[derive(Debug, Args)]
+pub struct StatusArgs {
+    #[arg(long, help = "Print a one-line status for shell prompts and status bars")]
+    short: bool,
+    #[arg(long, value_enum, conflicts_with = "short", help = "Print the status in a status bar format")]
+    format: Option<StatusFormat>,
+}
+
+pub fn cmd(status_args: StatusArgs) -> Result<(), Box<dyn Error>> {
+    let events = Events::new()?.fetch(SelectRequest::Daily, Local::now().date_naive())?;
+    let incomplete_tasks = Tasks::new()?.fetch(TaskFilter::Incomplete)?.len();
+    let status = Status::new(events, incomplete_tasks);
+    let productivity = status.productivity().map_or("-".to_string(), |productivity| format!("{:.0}%", productivity));
+    let alert = health::check(Heartbeats::new()?.fetch()?.as_ref(), Local::now().naive_local(), status.active);
+    let config = Config::read().ok();
+    let display = config.as_ref().and_then(|config| config.display.clone()).unwrap_or_default();
+    let target = ContractConfig::daily_target(config.as_ref().and_then(|config| config.contract.as_ref()));
+    let break_debt = config
+        .and_then(|config| config.breaks)
+        .and_then(|breaks| breaks.evaluate(Local::now().date_naive(), status.duration, &status.pauses));
+    let break_due = break_debt
+        .as_ref()
+        .map_or("-".to_string(), |break_debt| FormatEvent::format_duration(Some(break_debt.missing())));
+    let forecast = status.forecast(target);
+    let since_break = status
+        .since_break
+        .map_or("-".to_string(), |since_break| format!("{}m", since_break.num_minutes()));
+
+    if status_args.short {
+        println!(
+            "{} {} | {} pauses | {} | end {} | {} tasks{}{}",
+            status.state(),
+            FormatEvent::format_duration(Some(status.duration)),
+            status.pauses.len(),
+            productivity,
+            forecast,
+            status.incomplete_tasks,
+            break_debt.as_ref().map_or("".to_string(), |_| format!(" | break due {}", break_due)),
+            alert.as_ref().map_or("", |_| " | watch alert")
+        );
+
+        return Ok(());
+    }
+
+    match status_args.format {
+        Some(StatusFormat::Waybar) => {
+            let status_json = serde_json::json!({
+                "text": format!("{} {}", status.icon(&display), FormatEvent::format_duration(Some(status.duration))),
+                "alt": status.state(),
+                "class": match alert {
+                    Some(_) => "warning".to_string(),
+                    None => status.state().replace(' ', "-"),
+                },
+                "tooltip": format!(
+                    "State: {}\nSince last break: {}\nPauses: {}\nProductivity: {}\nProjected end: {}\nBreak due: {}\nIncomplete tasks: {}{}",
+                    status.state(),
+                    since_break,
+                    status.pauses.len(),
+                    productivity,
+                    forecast,
+                    break_due,
+                    status.incomplete_tasks,
+                    alert.as_ref().map_or("".to_string(), |alert| format!("\n{}", alert))
+                ),
+            });
+            println!("{}", status_json);
+        }
+        Some(StatusFormat::Polybar) => {
+            println!(
+                "{} {} ({})",
+                status.icon(&display),
+                FormatEvent::format_duration(Some(status.duration)),
+                since_break
+            );
+        }
+        Some(StatusFormat::Tmux) => {
+            let icon = match display.color(status.active) {
+                Some(color) => format!("#[fg={}]{}#[default]", color, status.icon(&display)),
+                None => status.icon(&display).to_string(),
+            };
+            println!("{} {} ({})", icon, FormatEvent::format_duration(Some(status.duration)), since_break);
+        }
+        None => {
+            View::status(&status, target, break_debt.as_ref())?;
+            if let Some(alert) = alert {
+                println!("Warning: {}", alert);
+            }
+            if let Some(warning) = permissions::input_monitoring_warning() {
+                println!("Warning: {}", warning);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,49 +1,291 @@
 use crate::{
     api::si::Si,
-    db::events::{Events, SelectRequest},
+    db::{events::Events, projects::Projects, tasks::Tasks},
     libs::{
+        clipboard,
         config::Config,
-        event::{EventGroup, EventGroupDuration, EventGroupTotalDuration},
-        view::View,
+        contract::Compliance,
+        error::KaslError,
+        event::{EventGroup, EventGroupDuration, EventGroupTotalDuration, FormatEvent},
+        productivity::BreakDebt,
+        stats::WorkStats,
+        summary::{self, Period, PeriodReport},
+        task::TaskFilter,
+        view::{TableOptions, View},
     },
 };
-use chrono::{Duration, Local, NaiveDate};
+use chrono::{Datelike, Duration, Local, Months, NaiveDate};
 use clap::Args;
-use std::{collections::HashSet, error::Error};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+};
 
 #[derive(Debug, Args)]
 pub struct SumArgs {
     #[arg(long, help = "Send report")]
     send: bool,
+    #[arg(long, help = "Show a calendar heatmap of working hours")]
+    heatmap: bool,
+    #[arg(long, default_value_t = 3, help = "Number of months covered by the heatmap")]
+    months: u32,
+    #[arg(long, value_name = "N", num_args = 0..=1, conflicts_with = "heatmap", help = "Show ISO week N of the current year, or the current week without N")]
+    week: Option<Option<u32>>,
+    #[arg(long, value_name = "N", num_args = 0..=1, conflicts_with_all = ["heatmap", "week"], help = "Show quarter N (1-4) of the current year month by month, or the current quarter without N")]
+    quarter: Option<Option<u32>>,
+    #[arg(long, value_name = "YEAR", num_args = 0..=1, conflicts_with_all = ["heatmap", "week"], help = "Show a year month by month, the current year without YEAR")]
+    year: Option<Option<i32>>,
+    #[arg(
+        long,
+        value_name = "PERIOD",
+        num_args = 1..=2,
+        conflicts_with_all = ["heatmap", "week", "quarter", "year", "send"],
+        help = "Compare with last-week, last-month, last-quarter or last-year, or compare two FROM..TO date ranges"
+    )]
+    compare: Vec<String>,
+    #[arg(long, conflicts_with = "heatmap", help = "Copy the rendered summary to the clipboard")]
+    copy: bool,
+    #[command(flatten)]
+    table: TableOptions,
 }
 
-pub async fn cmd(_sum_args: SumArgs) -> Result<(), Box<dyn Error>> {
+pub async fn cmd(sum_args: SumArgs) -> Result<(), Box<dyn Error>> {
     let now = Local::now();
-    println!("\nWorking hours for {}", now.format("%B, %Y"));
+    if sum_args.heatmap {
+        let to = now.date_naive();
+        let from = (to - Months::new(sum_args.months)).succ_opt().unwrap();
+        println!("\nWorking hours from {} to {}\n", from.format("%B %-d, %Y"), to.format("%B %-d, %Y"));
+        let durations: HashMap<NaiveDate, Duration> = Events::new()?
+            .fetch_range(from, to)?
+            .group_events()
+            .calc()
+            .0
+            .into_iter()
+            .map(|(date, (_, duration))| (date, duration))
+            .collect();
+        View::heatmap(&durations, from, to)?;
+
+        return Ok(());
+    }
+    let today = now.date_naive();
+    if !sum_args.compare.is_empty() {
+        return compare(&sum_args, today);
+    }
+    let period = match sum_args.week {
+        Some(Some(week)) => Period::iso_week(today.iso_week().year(), week)
+            .ok_or_else(|| KaslError::validation(format!("Week {} does not exist in {}", week, today.iso_week().year())))?,
+        Some(None) => Period::week(today),
+        None if sum_args.quarter.is_some() || sum_args.year.is_some() => return months(&sum_args, today),
+        None => Period::month(today),
+    };
+    println!("\nWorking hours for {}", period.title());
     let mut rest_dates: HashSet<NaiveDate> = HashSet::new();
     let duration: Duration = Duration::hours(8);
-    match Config::read() {
-        Ok(config) => match config.si {
-            Some(si_config) => match Si::new(&si_config).rest_dates(now.date_naive()).await {
-                Ok(dates) => {
-                    rest_dates = dates;
-                }
-                Err(e) => eprintln!("Error requesting rest dates: {}", e),
+    // Rest days come from SiServer per month, so weekly summaries count worked days only.
+    if let Period::Month(_) = period {
+        match Config::read() {
+            Ok(config) => match config.si {
+                Some(si_config) => match Si::new(&si_config).rest_dates(today).await {
+                    Ok(dates) => {
+                        rest_dates = dates;
+                    }
+                    Err(e) => eprintln!("Error requesting rest dates: {}", e),
+                },
+                None => eprintln!("Failed to read SiServer config"),
             },
-            None => eprintln!("Failed to read SiServer config"),
-        },
-        Err(e) => eprintln!("Failed to read config: {}", e),
+            Err(e) => eprintln!("Failed to read config: {}", e),
+        }
     }
 
-    let event_summary = Events::new()?
-        .fetch(SelectRequest::Monthly, now.date_naive())?
-        .group_events()
-        .calc()
-        .add_rest_dates(rest_dates, duration)
-        .total_duration()
-        .format();
+    let mut event_summary = period.durations(today)?.add_rest_dates(rest_dates.clone(), duration).total_duration();
+
+    let earnings = match Config::read().ok().and_then(|config| config.rate) {
+        Some(rate) => {
+            let tasks = Tasks::new()?.fetch(TaskFilter::Range(period.first_day(), period.last_day()))?;
+            let projects = Projects::new()?.fetch()?;
+            let daily_amounts: HashMap<NaiveDate, f64> = event_summary
+                .0
+                .iter()
+                .map(|(date, (_, duration))| (*date, rate.blended(&summary::task_rates(*date, &tasks, &projects)).amount(*duration)))
+                .collect();
+            let total_amount: f64 = daily_amounts.values().sum();
+            Some((
+                daily_amounts.into_iter().map(|(date, amount)| (date, rate.format_amount(amount))).collect(),
+                rate.format_amount(total_amount),
+            ))
+        }
+        None => None,
+    };
+
+    let table = View::sum_table(&event_summary.format(), &earnings, &sum_args.table)?;
+    table.printstd();
+    let mut footer = String::new();
+    if let Period::Week(_) = period {
+        let previous_total = period.previous().durations(today)?.total_duration().1;
+        let delta = event_summary.1 - previous_total;
+        footer = format!(
+            "Previous week: {} ({})",
+            FormatEvent::format_duration(Some(previous_total)),
+            FormatEvent::format_delta(delta)
+        );
+        println!("{}", footer);
+    }
+    if let (Period::Month(_), Ok(config)) = (period, Config::read()) {
+        let mut sections = vec![];
+        if let Some(contract) = config.contract {
+            let compliance = Compliance::new(&contract, &period, today, event_summary.1, &event_summary.0, &rest_dates);
+            sections.push(compliance_section(&compliance));
+        }
+        if let Some(breaks) = config.breaks {
+            let work_stats = WorkStats::new(event_summary.0.values().flat_map(|(events, _)| events.clone()).collect());
+            sections.push(breaks_section(&breaks.debts(&work_stats.days), work_stats.days.len()));
+        }
+        footer = sections.join("\n");
+        if !footer.is_empty() {
+            println!("{}", footer);
+        }
+    }
+    let project_section = projects(&period, today)?;
+    if sum_args.copy {
+        clipboard::copy(&format!("Working hours for {}\n{}{}{}", period.title(), table, footer, project_section))?;
+        println!("\nThe summary has been copied to the clipboard");
+    }
+
+    Ok(())
+}
+
+/// Contract fulfillment of the month and what is left to reach it.
+fn compliance_section(compliance: &Compliance) -> String {
+    let mut section = format!(
+        "Contract: {} of {} ({:.1}%), {} remaining",
+        FormatEvent::format_duration(Some(compliance.worked)),
+        FormatEvent::format_duration(Some(compliance.target)),
+        compliance.fulfillment(),
+        FormatEvent::format_duration(Some(compliance.remaining()))
+    );
+    match compliance.required_average() {
+        Some(average) => {
+            section += &format!(
+                "\nRequired average: {} per day over {} remaining workdays",
+                FormatEvent::format_duration(Some(average)),
+                compliance.remaining_workdays
+            )
+        }
+        None if compliance.remaining() > Duration::zero() => section += "\nNo workdays left this month",
+        None => {}
+    }
+    if !compliance.core_misses.is_empty() {
+        let dates: Vec<String> = compliance.core_misses.iter().map(|date| date.format("%B %-d").to_string()).collect();
+        section += &format!("\nCore hours missed on: {}", dates.join(", "));
+    }
+
+    section
+}
 
-    View::sum(&event_summary)?;
+/// How many worked days of the month took the required breaks, and which fell short.
+fn breaks_section(debts: &[BreakDebt], days: usize) -> String {
+    let missing = debts.iter().fold(Duration::zero(), |total, debt| total + debt.missing());
+    let mut section = format!("Breaks: {} of {} days compliant", days - debts.len(), days);
+    if !debts.is_empty() {
+        let dates: Vec<String> = debts
+            .iter()
+            .map(|debt| format!("{} ({})", debt.date.format("%B %-d"), FormatEvent::format_duration(Some(debt.missing()))))
+            .collect();
+        section += &format!(
+            ", {} missing\nShort breaks on: {}",
+            FormatEvent::format_duration(Some(missing)),
+            dates.join(", ")
+        );
+    }
+
+    section
+}
+
+/// Month-by-month summary of a quarter or a year.
+fn months(sum_args: &SumArgs, today: NaiveDate) -> Result<(), Box<dyn Error>> {
+    let year = sum_args.year.flatten().unwrap_or(today.year());
+    let period = match sum_args.quarter {
+        Some(quarter) => {
+            let quarter = quarter.unwrap_or(today.month0() / 3 + 1);
+            Period::quarter_of(year, quarter).ok_or_else(|| KaslError::validation(format!("Quarter {} does not exist, use 1-4", quarter)))?
+        }
+        None => Period::year_of(year).ok_or_else(|| KaslError::validation(format!("Year {} is out of range", year)))?,
+    };
+    println!("\nWorking hours for {}", period.title());
+    let month_totals = summary::month_totals(&period.durations(today)?);
+    if month_totals.is_empty() {
+        println!("No working hours recorded");
+        return Ok(());
+    }
+    let table = View::months_table(&month_totals);
+    table.printstd();
+    let mut highlights = String::new();
+    if month_totals.len() > 1 {
+        let best = month_totals.iter().max_by_key(|month| month.duration);
+        let worst = month_totals.iter().min_by_key(|month| month.duration);
+        if let (Some(best), Some(worst)) = (best, worst) {
+            highlights = format!(
+                "Best month: {} ({})\nWorst month: {} ({})",
+                best.month.format("%B"),
+                FormatEvent::format_duration(Some(best.duration)),
+                worst.month.format("%B"),
+                FormatEvent::format_duration(Some(worst.duration))
+            );
+            println!("{}", highlights);
+        }
+    }
+    let project_section = projects(&period, today)?;
+    if sum_args.copy {
+        clipboard::copy(&format!("Working hours for {}\n{}{}{}", period.title(), table, highlights, project_section))?;
+        println!("\nThe summary has been copied to the clipboard");
+    }
+
+    Ok(())
+}
+
+/// Prints the working time per project when any task of the period belongs to one; returns the printed section.
+fn projects(period: &Period, today: NaiveDate) -> Result<String, Box<dyn Error>> {
+    let Some((from, to)) = period.finished_days(today) else {
+        return Ok(String::new());
+    };
+    let tasks = Tasks::new()?.fetch(TaskFilter::Range(from, to))?;
+    if tasks.iter().all(|task| task.project_id.is_none()) {
+        return Ok(String::new());
+    }
+    let rate = Config::read().ok().and_then(|config| config.rate);
+    let project_totals = summary::project_totals(&period.durations(today)?, &tasks, &Projects::new()?.fetch()?, rate.as_ref());
+    let section = format!("\nProjects:\n{}", View::project_totals_table(&project_totals));
+    print!("{}", section);
+
+    Ok(section)
+}
+
+/// Side-by-side comparison of two periods, e.g. this month against the last one.
+fn compare(sum_args: &SumArgs, today: NaiveDate) -> Result<(), Box<dyn Error>> {
+    let parse = |spec: &str| Period::range(spec, today).ok_or_else(|| KaslError::validation(format!("Invalid range '{}', use YYYY-MM-DD..YYYY-MM-DD", spec)));
+    let (before, after) = match sum_args.compare.as_slice() {
+        [spec] => {
+            let period = match spec.as_str() {
+                "last-week" => Period::week(today),
+                "last-month" => Period::month(today),
+                "last-quarter" => Period::quarter_of(today.year(), today.month0() / 3 + 1).unwrap_or(Period::month(today)),
+                "last-year" => Period::year_of(today.year()).unwrap_or(Period::month(today)),
+                range => parse(range)?,
+            };
+            (period.previous(), period)
+        }
+        [before, after] => (parse(before)?, parse(after)?),
+        _ => return Err(KaslError::validation("Pass one period or two date ranges to --compare")),
+    };
+    let before = PeriodReport::new(before, today)?;
+    let after = PeriodReport::new(after, today)?;
+    println!("\nComparison of {} and {}", before.period.title(), after.period.title());
+    let table = View::comparison_table(&before, &after);
+    table.printstd();
+    if sum_args.copy {
+        clipboard::copy(&format!("Comparison of {} and {}\n{}", before.period.title(), after.period.title(), table))?;
+        println!("\nThe comparison has been copied to the clipboard");
+    }
 
     Ok(())
 }
@@ -1,92 +1,428 @@
 use crate::{
-    api::{gitlab::GitLab, jira::Jira},
-    db::tasks::Tasks,
+    api::{
+        gitlab::GitLab,
+        jira::Jira,
+        notion::{Notion, PushResult},
+        trello::Trello,
+    },
+    commands::project,
+    db::{db::Db, projects::Projects, task_notes::TaskNotes, tasks::Tasks},
     libs::{
         config::Config,
-        task::{Task, TaskFilter},
-        view::View,
+        date, dry_run,
+        error::KaslError,
+        interactive,
+        task::{FormatTasks, QuickTask, Task, TaskFilter},
+        view::{TableOptions, View},
     },
 };
-use chrono::Local;
-use clap::Args;
+use chrono::{Local, NaiveDate};
+use clap::{Args, Subcommand};
 use dialoguer::{theme::ColorfulTheme, Input, MultiSelect};
 use std::error::Error;
 
+const SOURCE_GITLAB: &str = "gitlab";
+const SOURCE_JIRA: &str = "jira";
+
 #[derive(Debug, PartialEq, Eq, Hash)]
 enum TaskSource {
     Incomplete,
-    Gitlab,
+    Gitlab(String),
     Jira,
+    Notion,
+    Trello,
+}
+
+#[derive(Debug, Subcommand)]
+enum TaskCommand {
+    #[command(about = "Move tasks to the trash")]
+    Delete {
+        #[arg(required = true, help = "Task IDs")]
+        ids: Vec<i32>,
+    },
+    #[command(about = "Change task completeness by the given amount, e.g. +25 or -10")]
+    Bump {
+        #[arg(help = "Task ID")]
+        id: i32,
+        #[arg(allow_negative_numbers = true, help = "Completeness change")]
+        delta: i32,
+    },
+    #[command(about = "Append a timestamped note to a task")]
+    Note {
+        #[arg(help = "Task ID")]
+        id: i32,
+        #[arg(help = "Note text")]
+        text: String,
+    },
+    #[command(about = "Show a task with its notes")]
+    Show {
+        #[arg(help = "Task ID")]
+        id: i32,
+    },
+    #[command(about = "Manage deleted tasks", subcommand)]
+    Trash(TrashCommand),
+}
+
+#[derive(Debug, Subcommand)]
+enum TrashCommand {
+    #[command(about = "List deleted tasks")]
+    List,
+    #[command(about = "Restore deleted tasks")]
+    Restore {
+        #[arg(required = true, help = "Task IDs")]
+        ids: Vec<i32>,
+    },
+    #[command(about = "Permanently remove deleted tasks, all of them when no IDs are given")]
+    Purge {
+        #[arg(help = "Task IDs")]
+        ids: Vec<i32>,
+    },
 }
 
 #[derive(Debug, Args)]
 pub struct TaskArgs {
+    #[command(subcommand)]
+    command: Option<TaskCommand>,
     #[arg(short, long)]
     name: Option<String>,
     #[arg(long)]
     comment: Option<String>,
     #[arg(short, long)]
     completeness: Option<i32>,
+    #[arg(long, help = "Project ID or name the new task belongs to")]
+    project: Option<String>,
     #[arg(short, long)]
     show: bool,
     #[arg(short, long)]
     all: bool,
     #[arg(short, long)]
     id: Option<Vec<i32>>,
+    #[arg(long, value_name = "DATE", requires = "show", value_parser = date::parse_arg, help = "Show the tasks of another day, e.g. 2024-08-01, yesterday or monday")]
+    date: Option<NaiveDate>,
+    #[arg(long, requires = "show", help = "Show only tasks imported from the given source, e.g. gitlab or jira")]
+    source: Option<String>,
     #[arg(short, long, help = "Find incomplete tasks")]
     find: bool,
+    #[arg(long, help = "Custom JQL for Jira issues, overrides the configured one")]
+    jql: Option<String>,
+    #[arg(long, help = "Push today's completed tasks to Notion")]
+    notion: bool,
+    #[arg(long, help = "Post today's tasks as a comment to the Trello summary card")]
+    trello: bool,
+    #[command(flatten)]
+    table: TableOptions,
+}
+
+fn subcommand(command: TaskCommand) -> Result<(), Box<dyn Error>> {
+    match command {
+        TaskCommand::Delete { ids } if dry_run::is_enabled() => {
+            let tasks = Tasks::new()?.fetch(TaskFilter::ByRowIds(ids))?;
+            dry_run::print(&format!("UPDATE {} tasks SET deleted_at", tasks.len()));
+            View::tasks(&tasks)?;
+        }
+        TaskCommand::Delete { ids } => {
+            let count = Tasks::new()?.trash(&ids)?;
+            println!("{} tasks have been moved to the trash", count);
+        }
+        TaskCommand::Bump { id, delta } => {
+            let mut tasks = Tasks::new()?;
+            let task = find_task(id)?;
+            let completeness = (task.completeness.unwrap_or(0) + delta).clamp(0, 100);
+            if dry_run::is_enabled() {
+                dry_run::print(&format!("UPDATE task {} SET completeness = {}", id, completeness));
+                return Ok(());
+            }
+            tasks.update_completeness(id, completeness)?;
+            View::tasks(&tasks.fetch(TaskFilter::ByRowIds(vec![id]))?)?;
+        }
+        TaskCommand::Note { id, text } => {
+            let task = find_task(id)?;
+            let chain_id = task.chain_id().ok_or_else(|| KaslError::validation(format!("Task {} not found", id)))?;
+            if dry_run::is_enabled() {
+                dry_run::print(&format!("INSERT note for task {}", task.name));
+                return Ok(());
+            }
+            TaskNotes::new()?.insert(chain_id, &text)?;
+            println!("Note has been added to task {}", task.name);
+        }
+        TaskCommand::Show { id } => {
+            let task = find_task(id)?;
+            let notes = TaskNotes::new()?.fetch(task.chain_id())?;
+            View::tasks(&[task])?;
+            if !notes.is_empty() {
+                println!("\nNotes:");
+                View::task_notes(&notes)?;
+            }
+        }
+        TaskCommand::Trash(TrashCommand::List) => {
+            let tasks = Tasks::new()?.fetch(TaskFilter::Deleted)?;
+            if tasks.is_empty() {
+                println!("Trash is empty");
+                return Ok(());
+            }
+            View::tasks(&tasks)?;
+        }
+        TaskCommand::Trash(TrashCommand::Restore { ids }) if dry_run::is_enabled() => {
+            let tasks = trashed(&ids)?;
+            dry_run::print(&format!("UPDATE {} tasks SET deleted_at = NULL", tasks.len()));
+            View::tasks(&tasks)?;
+        }
+        TaskCommand::Trash(TrashCommand::Purge { ids }) if dry_run::is_enabled() => {
+            let tasks = trashed(&ids)?;
+            dry_run::print(&format!("DELETE {} tasks", tasks.len()));
+            View::tasks(&tasks)?;
+        }
+        TaskCommand::Trash(TrashCommand::Restore { ids }) => {
+            let count = Tasks::new()?.untrash(&ids)?;
+            println!("{} tasks have been restored", count);
+        }
+        TaskCommand::Trash(TrashCommand::Purge { ids }) => {
+            if ids.is_empty() && !interactive::confirm("Permanently remove all deleted tasks?", false)? {
+                return Ok(());
+            }
+            let count = Db::transaction(&Db::new()?.conn, || {
+                let count = Tasks::new()?.purge(&ids)?;
+                TaskNotes::new()?.delete_orphaned()?;
+                Ok(count)
+            })?;
+            println!("{} tasks have been permanently removed", count);
+        }
+    }
+
+    Ok(())
+}
+
+fn find_task(id: i32) -> Result<Task, Box<dyn Error>> {
+    Ok(Tasks::new()?
+        .fetch(TaskFilter::ByRowIds(vec![id]))?
+        .pop()
+        .ok_or_else(|| KaslError::validation(format!("Task {} not found", id)))?)
+}
+
+/// Deleted tasks with the given IDs, or all of them when no IDs are given.
+fn trashed(ids: &[i32]) -> Result<Vec<Task>, Box<dyn Error>> {
+    let tasks = Tasks::new()?.fetch(TaskFilter::Deleted)?;
+
+    Ok(tasks
+        .into_iter()
+        .filter(|task| ids.is_empty() || task.id.is_some_and(|id| ids.contains(&id)))
+        .collect())
+}
+
+/// Links a task from an external source to the record imported on an earlier day,
+/// so it continues that task instead of creating a new one. Returns `None` when it was already imported today.
+fn link_external(mut task: Task, today: &NaiveDate) -> Result<Option<Task>, Box<dyn Error>> {
+    let (Some(source), Some(external_id)) = (task.source.clone(), task.external_id.clone()) else {
+        return Ok(Some(task));
+    };
+    let Some(existing) = Tasks::new()?.fetch(TaskFilter::ByExternalId(source, external_id))?.pop() else {
+        return Ok(Some(task));
+    };
+    if existing
+        .timestamp
+        .as_deref()
+        .is_some_and(|timestamp| timestamp.starts_with(&today.format("%Y-%m-%d").to_string()))
+    {
+        return Ok(None);
+    }
+    task.task_id = existing.chain_id();
+
+    Ok(Some(task))
+}
+
+fn linked_name(task: &Task) -> String {
+    match task.task_id {
+        Some(_) => format!("{} (imported earlier)", task.name),
+        None => task.name.to_owned(),
+    }
+}
+
+/// Creates a task from one line of quick-capture syntax without any prompts.
+pub fn quick(words: Vec<String>) -> Result<(), Box<dyn Error>> {
+    let quick_task = QuickTask::parse(&words.join(" "))?;
+    let mut task = Task::new(&quick_task.name, &quick_task.comment(), Some(quick_task.completeness.unwrap_or(100)));
+    if let Some(project) = &quick_task.project {
+        task.project_id = project::find(&mut Projects::new()?, project)?.id;
+    }
+    insert(&task)
+}
+
+/// Creates the task and shows it, or only shows it in a dry run.
+fn insert(task: &Task) -> Result<(), Box<dyn Error>> {
+    if dry_run::is_enabled() {
+        dry_run::print("INSERT task");
+        return View::tasks(std::slice::from_ref(task));
+    }
+    let new_task = Tasks::new()?.insert(task)?.update_id()?.get()?;
+    View::tasks(&new_task)?;
+
+    Ok(())
+}
+
+/// Marks the tasks as complete; IDs that match no task are reported, and only fail the command when none matches.
+pub fn done(ids: Vec<i32>) -> Result<(), Box<dyn Error>> {
+    let mut tasks = Tasks::new()?;
+    let found_tasks = tasks.fetch(TaskFilter::ByRowIds(ids.clone()))?;
+    let unknown_ids: Vec<String> = ids
+        .iter()
+        .filter(|id| found_tasks.iter().all(|task| task.id != Some(**id)))
+        .map(|id| id.to_string())
+        .collect();
+    if found_tasks.is_empty() {
+        return Err(KaslError::validation(format!("Tasks {} not found", unknown_ids.join(", "))));
+    }
+    if !unknown_ids.is_empty() {
+        println!("Tasks {} not found, skipping them", unknown_ids.join(", "));
+    }
+    if dry_run::is_enabled() {
+        dry_run::print(&format!("UPDATE {} tasks SET completeness = 100", found_tasks.len()));
+        return View::tasks(&found_tasks);
+    }
+    let found_ids: Vec<i32> = found_tasks.iter().filter_map(|task| task.id).collect();
+    for id in found_ids.iter() {
+        tasks.update_completeness(*id, 100)?;
+    }
+    View::tasks(&tasks.fetch(TaskFilter::ByRowIds(found_ids))?)?;
+
+    Ok(())
 }
 
 pub async fn cmd(task_args: TaskArgs) -> Result<(), Box<dyn Error>> {
+    if let Some(command) = task_args.command {
+        return subcommand(command);
+    }
     let date = Local::now();
     if task_args.show {
-        let mut filter: TaskFilter = TaskFilter::Date(date.date_naive());
+        let mut filter: TaskFilter = TaskFilter::Date(task_args.date.unwrap_or(date.date_naive()));
         if task_args.all {
             filter = TaskFilter::All;
         } else if task_args.id.is_some() {
             filter = TaskFilter::ByIds(task_args.id.unwrap());
+        } else if let Some(source) = task_args.source {
+            filter = TaskFilter::BySource(source);
         }
         let tasks = Tasks::new()?.fetch(filter)?;
         if tasks.is_empty() {
             println!("Tasks not found((");
             return Ok(());
         }
-        View::tasks(&tasks)?;
+        View::tasks_with(&tasks, &task_args.table)?;
+
+        return Ok(());
+    } else if task_args.notion {
+        let config = Config::read()?;
+        let notion_config = config
+            .notion
+            .ok_or_else(|| KaslError::config("Notion is not configured, run `kasl init` first"))?;
+        let tasks = Tasks::new()?.fetch(TaskFilter::Date(date.date_naive()))?;
+        let completed_tasks: Vec<&Task> = tasks.iter().filter(|task| task.completeness.unwrap_or(100) >= 100).collect();
+        if completed_tasks.is_empty() {
+            println!("Tasks not found((");
+            return Ok(());
+        }
+        if dry_run::is_enabled() {
+            dry_run::print(&format!("Would push {} tasks to Notion", completed_tasks.len()));
+            return Ok(());
+        }
+        let notion = Notion::new(&notion_config);
+        let mut updated = 0;
+        for task in completed_tasks.iter() {
+            if notion.push_task(task, &date.date_naive()).await? == PushResult::Updated {
+                updated += 1;
+            }
+        }
+        println!(
+            "{} tasks have been pushed to Notion, {} of them updated existing pages",
+            completed_tasks.len(),
+            updated
+        );
+
+        return Ok(());
+    } else if task_args.trello {
+        let config = Config::read()?;
+        let trello_config = config
+            .trello
+            .ok_or_else(|| KaslError::config("Trello is not configured, run `kasl init` first"))?;
+        let mut tasks = Tasks::new()?.fetch(TaskFilter::Date(date.date_naive()))?;
+        if tasks.is_empty() {
+            println!("Tasks not found((");
+            return Ok(());
+        }
+        let summary = format!("{}\n{}", date.format("%B %-d, %Y"), tasks.format());
+        if dry_run::is_enabled() {
+            dry_run::print(&format!("Would post to the Trello summary card:\n{}", summary));
+            return Ok(());
+        }
+        Trello::new(&trello_config).post_summary(&summary).await?;
+        println!("Daily summary has been posted to Trello");
 
         return Ok(());
     } else if task_args.find {
+        if interactive::is_disabled() {
+            return Err(interactive::required("The selection of found tasks", "add them with --name instead"));
+        }
         // Incomplete tasks
-        let mut tasks: Vec<(&TaskSource, Vec<Task>)> = Vec::new();
+        let mut tasks: Vec<(TaskSource, Vec<Task>)> = Vec::new();
         let incomplete_tasks = Tasks::new()?.fetch(TaskFilter::Incomplete)?;
         if !incomplete_tasks.is_empty() {
-            tasks.push((&TaskSource::Incomplete, incomplete_tasks));
+            tasks.push((TaskSource::Incomplete, incomplete_tasks));
         }
 
         let config = Config::read()?;
         // Gitlab commits
-        if config.gitlab.is_some() {
+        if !config.gitlab.is_empty() {
             let today_tasks = Tasks::new()?.fetch(TaskFilter::Date(date.date_naive()))?;
-            let commits = GitLab::new(&config.gitlab.unwrap()).get_today_commits().await?;
-            let mut gitlab_tasks: Vec<Task> = Vec::new();
-            commits.iter().for_each(|commit| {
-                if today_tasks.iter().all(|task| task.name != commit.message) {
-                    gitlab_tasks.push(Task::new(&commit.message, "", Some(100)));
+            for gitlab_config in config.gitlab.iter() {
+                let commits = GitLab::new(gitlab_config).get_today_commits().await?;
+                let mut gitlab_tasks: Vec<Task> = Vec::new();
+                for commit in commits.iter() {
+                    if today_tasks.iter().all(|task| task.name != commit.message) {
+                        let task = Task::new(&commit.message, "", Some(100)).with_external(SOURCE_GITLAB, &commit.sha, commit.url.clone());
+                        gitlab_tasks.extend(link_external(task, &date.date_naive())?);
+                    }
+                }
+                if !gitlab_tasks.is_empty() {
+                    tasks.push((TaskSource::Gitlab(gitlab_config.label().to_string()), gitlab_tasks));
                 }
-            });
-            if !gitlab_tasks.is_empty() {
-                tasks.push((&TaskSource::Gitlab, gitlab_tasks));
             }
         }
         // Jira issues
-        if config.jira.is_some() {
-            let jira_issues = Jira::new(&config.jira.unwrap()).get_completed_issues(&date.date_naive()).await?;
+        if let Some(jira_config) = &config.jira {
+            let mut jira = Jira::new(jira_config);
+            let jira_issues = jira.get_completed_issues(&date.date_naive(), task_args.jql.as_deref()).await?;
             let mut jira_tasks: Vec<Task> = Vec::new();
-            jira_issues.iter().for_each(|issue| {
+            for issue in jira_issues.iter() {
                 let name = format!("{} {}", &issue.key, &issue.fields.summary);
-                jira_tasks.push(Task::new(&name, "", Some(100)));
-            });
+                let task = Task::new(&name, "", Some(100)).with_external(SOURCE_JIRA, &issue.key, Some(jira.issue_url(&issue.key)));
+                jira_tasks.extend(link_external(task, &date.date_naive())?);
+            }
             if !jira_tasks.is_empty() {
-                tasks.push((&TaskSource::Jira, jira_tasks));
+                tasks.push((TaskSource::Jira, jira_tasks));
+            }
+        }
+
+        // Notion items
+        if let Some(notion_config) = &config.notion {
+            let mut notion_tasks: Vec<Task> = Vec::new();
+            for task in Notion::new(notion_config).get_incomplete_tasks().await? {
+                notion_tasks.extend(link_external(task, &date.date_naive())?);
+            }
+            if !notion_tasks.is_empty() {
+                tasks.push((TaskSource::Notion, notion_tasks));
+            }
+        }
+
+        // Trello cards
+        if let Some(trello_config) = &config.trello {
+            let today_tasks = Tasks::new()?.fetch(TaskFilter::Date(date.date_naive()))?;
+            let mut trello_tasks: Vec<Task> = Vec::new();
+            for card in Trello::new(trello_config).get_done_cards(&date.date_naive()).await? {
+                if today_tasks.iter().all(|task| task.name != card.name) {
+                    trello_tasks.extend(link_external(card, &date.date_naive())?);
+                }
+            }
+            if !trello_tasks.is_empty() {
+                tasks.push((TaskSource::Trello, trello_tasks));
             }
         }
 
@@ -103,8 +439,19 @@ pub async fn cmd(task_args: TaskArgs) -> Result<(), Box<dyn Error>> {
                     println!("\nIncomplete tasks");
                     name_format = Box::new(|task: &Task| format!("{} - {}%", task.name, task.completeness.unwrap_or(0)));
                 }
-                TaskSource::Gitlab => println!("\nGitlab commits"),
-                TaskSource::Jira => println!("\nJira issues"),
+                TaskSource::Gitlab(name) => {
+                    println!("\nGitlab commits ({})", name);
+                    name_format = Box::new(linked_name);
+                }
+                TaskSource::Jira => {
+                    println!("\nJira issues");
+                    name_format = Box::new(linked_name);
+                }
+                TaskSource::Trello => println!("\nTrello cards"),
+                TaskSource::Notion => {
+                    println!("\nNotion items");
+                    name_format = Box::new(|task: &Task| format!("{} - {}%", task.name, task.completeness.unwrap_or(0)));
+                }
             }
             let task_names: Vec<String> = tasks.iter().map(name_format).collect();
             selected_tasks.push((
@@ -119,7 +466,7 @@ pub async fn cmd(task_args: TaskArgs) -> Result<(), Box<dyn Error>> {
 
         for (task_source, selected_task_indexes) in selected_tasks {
             for index in selected_task_indexes {
-                let mut task = tasks.iter().find(|(ts, _)| ts == &task_source).map_or(&vec![], |(_, tasks)| tasks)[index].clone();
+                let mut task = tasks.iter().find(|(ts, _)| ts == task_source).map_or(&vec![], |(_, tasks)| tasks)[index].clone();
                 match task_source {
                     TaskSource::Incomplete => {
                         println!("Selected task: {}", &task.name);
@@ -136,8 +483,21 @@ pub async fn cmd(task_args: TaskArgs) -> Result<(), Box<dyn Error>> {
                                 .unwrap(),
                         );
                     }
+                    TaskSource::Gitlab(_) | TaskSource::Jira if task.task_id.is_some() => {
+                        println!("Selected task: {}", &task.name);
+                        task.completeness = Some(
+                            Input::with_theme(&ColorfulTheme::default())
+                                .with_prompt("Update completeness")
+                                .default(task.completeness.unwrap_or(100))
+                                .interact_text()?,
+                        );
+                    }
                     _ => {}
                 }
+                if dry_run::is_enabled() {
+                    dry_run::print(&format!("INSERT task {}", task.name));
+                    continue;
+                }
                 let _ = Tasks::new()?.insert(&task);
             }
         }
@@ -145,31 +505,32 @@ pub async fn cmd(task_args: TaskArgs) -> Result<(), Box<dyn Error>> {
         return Ok(());
     }
 
-    let name = task_args.name.unwrap_or_else(|| {
-        Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("Enter task name")
-            .interact_text()
-            .unwrap()
-    });
-    let comment = task_args.comment.unwrap_or_else(|| {
-        Input::with_theme(&ColorfulTheme::default())
+    let name = match task_args.name {
+        Some(name) => name,
+        None if interactive::is_disabled() => return Err(interactive::required("The task name", "pass it with --name")),
+        None => Input::with_theme(&ColorfulTheme::default()).with_prompt("Enter task name").interact_text()?,
+    };
+    let comment = task_args.comment.unwrap_or_else(|| match interactive::is_disabled() {
+        true => String::new(),
+        false => Input::with_theme(&ColorfulTheme::default())
             .allow_empty(true)
             .with_prompt("Enter comment")
             .interact_text()
-            .unwrap()
+            .unwrap(),
     });
-    let completeness = task_args.completeness.unwrap_or_else(|| {
-        Input::with_theme(&ColorfulTheme::default())
+    let completeness = task_args.completeness.unwrap_or_else(|| match interactive::is_disabled() {
+        true => 100,
+        false => Input::with_theme(&ColorfulTheme::default())
             .allow_empty(true)
             .with_prompt("Enter completeness")
             .default(100)
             .interact_text()
-            .unwrap()
+            .unwrap(),
     });
 
-    let task = Task::new(&name, &comment, Some(completeness));
-    let new_task = Tasks::new()?.insert(&task)?.update_id()?.get()?;
-    View::tasks(&new_task)?;
-
-    Ok(())
+    let mut task = Task::new(&name, &comment, Some(completeness));
+    if let Some(project) = task_args.project {
+        task.project_id = project::find(&mut Projects::new()?, &project)?.id;
+    }
+    insert(&task)
 }
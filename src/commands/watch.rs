@@ -1,30 +1,284 @@
-use device_query::{DeviceQuery, DeviceState, Keycode, MouseState};
+use crate::{
+    db::{events::Events, heartbeat::Heartbeats, reminders::Reminders},
+    libs::{
+        activity::{self, DeviceActivity, FallbackActivity, IdleTimeActivity, Monitor, Transition, CHECK_INTERVAL, HOOK_SILENCE, INACTIVITY_THRESHOLD},
+        diag::ActivityLog,
+        event::EventType,
+        health::{Heartbeat, HEARTBEAT_INTERVAL},
+        permissions::{self, ACCESSIBILITY_SETTINGS_URL},
+        pid::PidFile,
+        reminder::ReminderClock,
+    },
+};
+use chrono::{Local, NaiveDateTime, Timelike};
+use clap::Args;
+use std::error::Error;
 use std::sync::{Arc, Mutex};
-use std::{thread, time};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    time,
+};
+use tokio::process::{Child, Command};
 
-pub fn cmd() {
-    let device_state = DeviceState::new();
+/// Delay before the first relaunch of a crashed watcher, doubled after every further crash.
+const RESTART_MIN_DELAY: time::Duration = time::Duration::from_secs(1);
+const RESTART_MAX_DELAY: time::Duration = time::Duration::from_secs(300);
+/// A watcher that ran this long before crashing starts over with the shortest delay.
+const HEALTHY_UPTIME: time::Duration = time::Duration::from_secs(600);
+/// Crashes in a row after which the supervisor warns about a crash loop.
+const CRASH_LOOP_ALERT: u32 = 5;
+/// How long a stopped watcher may take to record the workday end before it is killed.
+const STOP_TIMEOUT: time::Duration = time::Duration::from_secs(10);
+
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    #[arg(long, short, help = "Print a live feed of activity changes")]
+    verbose: bool,
+    #[arg(long, value_name = "FILE", help = "Replay a JSON array of activity timestamps and print the detected pauses")]
+    replay: Option<PathBuf>,
+    #[arg(long, conflicts_with = "replay", help = "Record activity timestamps for `kasl diag export`")]
+    record: bool,
+    #[arg(long, conflicts_with = "replay", help = "Run the watcher as a child process and restart it when it crashes")]
+    supervise: bool,
+}
+
+pub async fn cmd(watch_args: WatchArgs) -> Result<(), Box<dyn Error>> {
+    if let Some(path) = watch_args.replay {
+        return replay(&path);
+    }
+    if watch_args.supervise {
+        return supervise(&watch_args).await;
+    }
+    let _pid_file = PidFile::acquire()?;
+    let verbose = watch_args.verbose;
+    feed(verbose, "watching started");
     let last_active_time = Arc::new(Mutex::new(time::Instant::now()));
 
-    let last_active_clone = last_active_time.clone();
-    thread::spawn(move || loop {
-        let mouse: MouseState = device_state.get_mouse();
-        let keys: Vec<Keycode> = device_state.get_keys();
+    match permissions::input_monitoring_allowed() {
+        // Only macOS keeps a system idle time to fall back to when the input hook stops reporting.
+        true if cfg!(target_os = "macos") => activity::spawn(
+            || FallbackActivity::new(DeviceActivity::new(), IdleTimeActivity::new(), HOOK_SILENCE),
+            last_active_time.clone(),
+        ),
+        true => activity::spawn(DeviceActivity::new, last_active_time.clone()),
+        false => {
+            println!(
+                "kasl has no Accessibility permission, so keyboard and mouse activity cannot be read\nGrant it in System Settings ({}) and restart `kasl watch`\nUntil then pauses are detected from the system idle time, checked once a second",
+                ACCESSIBILITY_SETTINGS_URL
+            );
+            permissions::open_settings();
+            activity::spawn(IdleTimeActivity::new, last_active_time.clone());
+        }
+    }
 
-        if mouse.button_pressed.len() == 0 || !keys.is_empty() {
-            let mut last_active = last_active_clone.lock().unwrap();
-            *last_active = time::Instant::now();
+    let mut activity_log = match watch_args.record {
+        true => Some(ActivityLog::open()?),
+        false => None,
+    };
+    let mut monitor = Monitor::new(INACTIVITY_THRESHOLD);
+    let mut reminders = ReminderClock::new(Reminders::new()?.fetch()?);
+    let mut last_beat: Option<time::Instant> = None;
+    // Listening once for the whole loop keeps a signal that arrives between two checks from being missed.
+    let signal = shutdown_signal();
+    tokio::pin!(signal);
+    loop {
+        tokio::select! {
+            _ = &mut signal => break,
+            _ = tokio::time::sleep(CHECK_INTERVAL) => {
+                let inactive_for = last_active_time.lock().unwrap().elapsed();
+                if last_beat.is_none_or(|last_beat| last_beat.elapsed() >= HEARTBEAT_INTERVAL) {
+                    // A locked or failing database must not stop the watcher; the next beat tries again.
+                    if let Err(e) = beat(inactive_for) {
+                        eprintln!("Failed to record the watcher heartbeat: {}", e);
+                    }
+                    last_beat = Some(time::Instant::now());
+                }
+                if let Some(activity_log) = activity_log.as_mut() {
+                    activity_log.record((Local::now() - chrono::Duration::from_std(inactive_for)?).naive_local().with_nanosecond(0).unwrap_or_default())?;
+                }
+                match monitor.update(inactive_for) {
+                    Some(Transition::PauseStarted) => {
+                        let pause_start = Local::now() - chrono::Duration::from_std(inactive_for)?;
+                        match verbose {
+                            true => feed(verbose, &format!("pause started at {}", pause_start.format("%H:%M:%S"))),
+                            false => println!("The user has been inactive for more than {} seconds!", INACTIVITY_THRESHOLD.as_secs()),
+                        }
+                    }
+                    Some(Transition::ActivityResumed) => feed(verbose, "activity resumed"),
+                    None => {}
+                }
+                if inactive_for < INACTIVITY_THRESHOLD {
+                    reminders.advance(CHECK_INTERVAL).into_iter().for_each(|reminder| reminder.fire());
+                }
+            }
         }
+    }
 
-        thread::sleep(time::Duration::from_millis(100));
-    });
+    shutdown(verbose)
+}
 
+/// Stores that the watcher is alive and when it last saw input, for `kasl status` and `kasl doctor`.
+fn beat(inactive_for: time::Duration) -> Result<(), Box<dyn Error>> {
+    let now = Local::now().naive_local().with_nanosecond(0).unwrap_or_default();
+    Heartbeats::new()?.beat(&Heartbeat {
+        pid: std::process::id(),
+        beat_at: now,
+        last_active_at: now - chrono::Duration::from_std(inactive_for)?,
+    })
+}
+
+/// Relaunches `kasl watch` with the same options whenever it exits with an error, backing off exponentially.
+/// A clean exit, e.g. after Ctrl+C, ends the supervisor as well.
+async fn supervise(watch_args: &WatchArgs) -> Result<(), Box<dyn Error>> {
+    let exe = env::current_exe()?;
+    let mut args = vec!["watch"];
+    if watch_args.verbose {
+        args.push("--verbose");
+    }
+    if watch_args.record {
+        args.push("--record");
+    }
+    let mut delay = RESTART_MIN_DELAY;
+    let mut crashes = 0;
+    let signal = shutdown_signal();
+    tokio::pin!(signal);
     loop {
-        thread::sleep(time::Duration::from_secs(5));
-        let mut last_active = last_active_time.lock().unwrap();
-        if last_active.elapsed() >= time::Duration::from_secs(10) {
-            println!("The user has been inactive for more than 10 seconds!");
-            *last_active = time::Instant::now(); // Сброс таймера
+        let started = time::Instant::now();
+        let mut command = Command::new(&exe);
+        command.args(&args);
+        // Its own process group lets `stop` send Ctrl+Break to the watcher alone.
+        #[cfg(windows)]
+        command.creation_flags(windows::Win32::System::Threading::CREATE_NEW_PROCESS_GROUP.0);
+        let mut child = command.spawn()?;
+        let status = tokio::select! {
+            status = child.wait() => status?,
+            _ = &mut signal => return stop(&mut child).await,
+        };
+        if status.success() {
+            return Ok(());
+        }
+        if started.elapsed() >= HEALTHY_UPTIME {
+            crashes = 0;
+            delay = RESTART_MIN_DELAY;
+        }
+        crashes += 1;
+        eprintln!(
+            "[{}] kasl watch exited unexpectedly ({}), restarting in {} s",
+            Local::now().format("%H:%M:%S"),
+            status,
+            delay.as_secs()
+        );
+        if crashes == CRASH_LOOP_ALERT {
+            eprintln!(
+                "kasl watch has crashed {} times in a row and is not tracking reliably, run `kasl watch --verbose` to see why",
+                crashes
+            );
         }
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = &mut signal => return Ok(()),
+        }
+        delay = (delay * 2).min(RESTART_MAX_DELAY);
+    }
+}
+
+/// Asks the supervised watcher to shut down cleanly so it still records the workday end: SIGTERM on Unix,
+/// Ctrl+Break to its process group on Windows. A watcher that does not exit in time is killed.
+async fn stop(child: &mut Child) -> Result<(), Box<dyn Error>> {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        Command::new("kill").args(["-TERM", &pid.to_string()]).status().await?;
+    }
+    #[cfg(windows)]
+    if let Some(pid) = child.id() {
+        use windows::Win32::System::Console::{GenerateConsoleCtrlEvent, CTRL_BREAK_EVENT};
+        unsafe { GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid)? };
     }
+    if tokio::time::timeout(STOP_TIMEOUT, child.wait()).await.is_err() {
+        child.start_kill()?;
+        child.wait().await?;
+    }
+
+    Ok(())
+}
+
+/// Feeds a recorded timeline through the monitor at full speed without touching the database.
+fn replay(path: &Path) -> Result<(), Box<dyn Error>> {
+    let activity: Vec<NaiveDateTime> = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let (Some(start), Some(end)) = (activity.iter().min(), activity.iter().max()) else {
+        println!("No activity found in {}", path.display());
+        return Ok(());
+    };
+    println!("Workday: {} - {}", start.format("%Y-%m-%d %H:%M:%S"), end.format("%Y-%m-%d %H:%M:%S"));
+    let pauses = activity::replay(&activity)?;
+    if pauses.is_empty() {
+        println!("No pauses of {} seconds or longer", INACTIVITY_THRESHOLD.as_secs());
+        return Ok(());
+    }
+    println!("Pauses:");
+    for (pause_start, pause_end) in pauses {
+        println!(
+            "  {} - {} ({} s)",
+            pause_start.format("%H:%M:%S"),
+            pause_end.format("%H:%M:%S"),
+            (pause_end - pause_start).num_seconds()
+        );
+    }
+
+    Ok(())
+}
+
+/// Prints a timestamped line of the live feed when `--verbose` is set.
+fn feed(verbose: bool, message: &str) {
+    if verbose {
+        println!("[{}] {}", Local::now().format("%H:%M:%S"), message);
+    }
+}
+
+/// Resolves on Ctrl+C, on SIGTERM on Unix and on Ctrl+Break on Windows, which the supervisor sends to stop the watcher.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+            }
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    #[cfg(windows)]
+    {
+        match tokio::signal::windows::ctrl_break() {
+            Ok(mut ctrl_break) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = ctrl_break.recv() => {}
+                }
+            }
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+/// Closes the open working interval so the workday end matches the moment the daemon stopped.
+fn shutdown(verbose: bool) -> Result<(), Box<dyn Error>> {
+    Events::new()?.insert(&EventType::End)?;
+    Heartbeats::new()?.clear()?;
+    feed(verbose, "workday end recorded");
+    println!("kasl watch stopped at {}", Local::now().format("%H:%M:%S"));
+
+    Ok(())
 }
@@ -1,17 +1,112 @@
-use crate::libs::data_storage::DataStorage;
-use rusqlite::{Connection, Result};
+use super::migrations;
+use crate::libs::{data_storage::DataStorage, error::KaslError};
+use chrono::Local;
+use rusqlite::{Connection, ErrorCode, Result};
+use std::cell::RefCell;
 use std::error::Error;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::{thread, time::Duration};
 
 pub const DB_FILE_NAME: &str = "kasl.db";
+/// How long SQLite itself waits for a lock held by another process before giving up.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+const RETRY_ATTEMPTS: u32 = 5;
+const RETRY_DELAY: Duration = Duration::from_millis(200);
+
+thread_local! {
+    static SHARED_CONNECTION: RefCell<Option<Rc<Connection>>> = const { RefCell::new(None) };
+}
+
 pub struct Db {
-    pub conn: Connection,
+    pub conn: Rc<Connection>,
 }
 
 impl Db {
+    /// Returns the connection shared by all tables on this thread, opening and migrating the database on first use.
     pub fn new() -> Result<Db, Box<dyn Error>> {
+        if let Some(conn) = SHARED_CONNECTION.with(|shared| shared.borrow().clone()) {
+            return Ok(Db { conn });
+        }
+
+        Ok(Self::share(Self::open()?))
+    }
+
+    /// Makes `conn` the connection shared on this thread, e.g. an in-memory database in tests.
+    /// `Db::transaction` and every table created with `new()` use it from then on.
+    pub fn share(conn: Connection) -> Db {
+        let conn = Rc::new(conn);
+        SHARED_CONNECTION.with(|shared| *shared.borrow_mut() = Some(conn.clone()));
+
+        Db { conn }
+    }
+
+    fn open() -> Result<Connection, Box<dyn Error>> {
         let db_file_path = DataStorage::new().get_path(DB_FILE_NAME)?;
-        let conn: Connection = Connection::open(db_file_path)?;
+        let mut conn: Connection = Connection::open(db_file_path)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        // WAL lets the CLI read while the watcher writes.
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+        let version = migrations::version(&conn)?;
+        if version > migrations::latest() {
+            return Err(KaslError::database(format!(
+                "The database schema version {} is newer than this kasl build supports ({})\n\
+                 Update kasl with `kasl update`, or restore a backup taken at version {} or lower (kasl.db.v<VERSION>-<TIME>.bak in {})",
+                version,
+                migrations::latest(),
+                migrations::latest(),
+                DataStorage::new().get_path("")?.display()
+            )));
+        }
+        if version < migrations::latest() && migrations::has_data(&conn)? {
+            Self::backup(&conn)?;
+        }
+        Self::retry(|| migrations::migrate(&mut conn))?;
+
+        Ok(conn)
+    }
+
+    /// Writes a consistent copy of the database next to it, named after its schema version and the current time.
+    pub fn backup(conn: &Connection) -> Result<PathBuf, Box<dyn Error>> {
+        let file_name = format!("{}.v{}-{}.bak", DB_FILE_NAME, migrations::version(conn)?, Local::now().format("%Y%m%d%H%M%S"));
+        let backup_path = DataStorage::new().get_path(&file_name)?;
+        conn.execute("VACUUM INTO ?1", [backup_path.to_string_lossy()])?;
+
+        Ok(backup_path)
+    }
+
+    /// Runs `operation` in one transaction on `conn`: all changes are committed when it succeeds
+    /// and rolled back when it fails. Nested calls join the transaction that is already open.
+    pub fn transaction<T, F>(conn: &Connection, operation: F) -> Result<T, Box<dyn Error>>
+    where
+        F: FnOnce() -> Result<T, Box<dyn Error>>,
+    {
+        if !conn.is_autocommit() {
+            return operation();
+        }
+        let transaction = conn.unchecked_transaction()?;
+        let result = operation()?;
+        transaction.commit()?;
+
+        Ok(result)
+    }
 
-        Ok(Db { conn })
+    /// Runs `operation` again with a growing delay while the database stays busy or locked after the busy timeout.
+    pub fn retry<T, F>(mut operation: F) -> Result<T>
+    where
+        F: FnMut() -> Result<T>,
+    {
+        let mut attempt = 1;
+        loop {
+            match operation() {
+                Err(rusqlite::Error::SqliteFailure(error, _))
+                    if matches!(error.code, ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked) && attempt < RETRY_ATTEMPTS =>
+                {
+                    thread::sleep(RETRY_DELAY * attempt);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
     }
 }
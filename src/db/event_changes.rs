@@ -0,0 +1,70 @@
+use super::db::Db;
+use chrono::{NaiveDate, NaiveDateTime};
+use rusqlite::{params, Connection};
+use std::{error::Error, rc::Rc};
+
+/// Audit trail of manual corrections to recorded events, such as a retroactive workday start.
+pub const SCHEMA_EVENT_CHANGES: &str = "CREATE TABLE IF NOT EXISTS event_changes (
+    id INTEGER NOT NULL PRIMARY KEY,
+    changed_at TIMESTAMP NOT NULL,
+    date DATE NOT NULL,
+    action TEXT NOT NULL,
+    before TIMESTAMP,
+    after TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_event_changes_date ON event_changes (date);";
+const INSERT_CHANGE: &str =
+    "INSERT INTO event_changes (changed_at, date, action, before, after) VALUES (datetime(CURRENT_TIMESTAMP, 'localtime'), ?1, ?2, ?3, ?4)";
+const SELECT_DAILY_CHANGES: &str = "SELECT changed_at, action, before, after FROM event_changes WHERE date = ?1 ORDER BY id";
+
+pub const ACTION_START: &str = "start";
+
+/// One correction: `before` is `None` when the event was created rather than moved.
+#[derive(Debug)]
+pub struct EventChange {
+    pub changed_at: NaiveDateTime,
+    pub action: String,
+    pub before: Option<NaiveDateTime>,
+    pub after: NaiveDateTime,
+}
+
+#[derive(Debug)]
+pub struct EventChanges {
+    pub conn: Rc<Connection>,
+}
+
+impl EventChanges {
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        Ok(Self::with_connection(Db::new()?.conn))
+    }
+
+    pub fn with_connection(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+
+    pub fn insert(&mut self, date: NaiveDate, action: &str, before: Option<NaiveDateTime>, after: NaiveDateTime) -> Result<(), Box<dyn Error>> {
+        self.conn
+            .execute(INSERT_CHANGE, params![date.format("%Y-%m-%d").to_string(), action, before, after])?;
+
+        Ok(())
+    }
+
+    pub fn fetch(&mut self, date: NaiveDate) -> Result<Vec<EventChange>, Box<dyn Error>> {
+        let mut stmt = self.conn.prepare(SELECT_DAILY_CHANGES)?;
+        let change_iter = stmt.query_map(params![date.format("%Y-%m-%d").to_string()], |row| {
+            Ok(EventChange {
+                changed_at: row.get(0)?,
+                action: row.get(1)?,
+                before: row.get(2)?,
+                after: row.get(3)?,
+            })
+        })?;
+
+        let mut changes = vec![];
+        for change in change_iter {
+            changes.push(change?);
+        }
+
+        Ok(changes)
+    }
+}
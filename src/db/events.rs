@@ -1,10 +1,10 @@
 use super::db::Db;
 use crate::libs::event::{Event, EventType};
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime};
 use rusqlite::{params, Connection, OptionalExtension, Result};
-use std::error::Error;
+use std::{error::Error, rc::Rc};
 
-const SCHEMA_EVENTS: &str = "CREATE TABLE IF NOT EXISTS events (
+pub const SCHEMA_EVENTS: &str = "CREATE TABLE IF NOT EXISTS events (
     id INTEGER NOT NULL PRIMARY KEY,
     start TIMESTAMP NOT NULL,
     end TIMESTAMP
@@ -13,21 +13,23 @@ const INSERT_EVENT: &str = "INSERT INTO events (start) VALUES (datetime(CURRENT_
 const SELECT_LAST_EVENT: &str = "SELECT id, end FROM events ORDER BY id DESC LIMIT 1";
 const UPDATE_EVENT: &str = "UPDATE events SET end = datetime(CURRENT_TIMESTAMP, 'localtime') WHERE id = ?1";
 const SELECT_DAILY_EVENTS: &str = "SELECT id, start, end FROM events WHERE date(start) = date(?1, 'localtime') ORDER BY start";
-const SELECT_MONTHLY_EVENTS: &str = "SELECT id, start, end FROM events
-    WHERE strftime('%Y-%m', start) = strftime('%Y-%m', ?1) 
-    AND date(start) >= date(?1, 'start of month')
-    AND date(start) < date(?1, 'start of day', '+1 day', '-1 day');";
+const SELECT_RANGE_EVENTS: &str = "SELECT id, start, end FROM events WHERE date(start) BETWEEN date(?1) AND date(?2) ORDER BY start";
+const UPDATE_EVENT_INTERVAL: &str = "UPDATE events SET start = ?2, end = ?3 WHERE id = ?1";
+const DELETE_EVENT: &str = "DELETE FROM events WHERE id = ?1";
+const SELECT_ALL_EVENTS: &str = "SELECT id, start, end FROM events ORDER BY start";
+const DELETE_DAILY_EVENTS: &str = "DELETE FROM events WHERE date(start) = date(?1)";
+const SELECT_DAILY_IDS: &str = "SELECT id FROM events WHERE date(start) = date(?1) ORDER BY id";
+const INSERT_EVENT_INTERVAL: &str = "INSERT INTO events (start, end) VALUES (?1, ?2)";
+const RESTORE_EVENT: &str = "INSERT OR REPLACE INTO events (id, start, end) VALUES (?1, ?2, ?3)";
 
 pub enum SelectRequest {
     Daily,
-    Monthly,
 }
 
 impl SelectRequest {
     fn value(&self) -> &str {
         match *self {
             Self::Daily => SELECT_DAILY_EVENTS,
-            Self::Monthly => SELECT_MONTHLY_EVENTS,
         }
     }
 }
@@ -40,15 +42,16 @@ pub struct EventSummary {
 
 #[derive(Debug)]
 pub struct Events {
-    pub conn: Connection,
+    pub conn: Rc<Connection>,
 }
 
 impl Events {
     pub fn new() -> Result<Events, Box<dyn Error>> {
-        let db = Db::new()?;
-        db.conn.execute(&SCHEMA_EVENTS, [])?;
+        Ok(Self::with_connection(Db::new()?.conn))
+    }
 
-        Ok(Events { conn: db.conn })
+    pub fn with_connection(conn: Rc<Connection>) -> Events {
+        Events { conn }
     }
 
     pub fn fetch(&mut self, select_request: SelectRequest, date: NaiveDate) -> Result<Vec<Event>, Box<dyn Error>> {
@@ -70,13 +73,101 @@ impl Events {
         Ok(events)
     }
 
+    pub fn fetch_range(&mut self, from: NaiveDate, to: NaiveDate) -> Result<Vec<Event>, Box<dyn Error>> {
+        let mut stmt = self.conn.prepare(SELECT_RANGE_EVENTS)?;
+        let event_iter = stmt.query_map(params![from.format("%Y-%m-%d").to_string(), to.format("%Y-%m-%d").to_string()], |row| {
+            Ok(Event {
+                id: row.get(0)?,
+                start: row.get(1)?,
+                end: row.get(2)?,
+                duration: None,
+            })
+        })?;
+
+        let mut events = vec![];
+        for event in event_iter {
+            events.push(event?);
+        }
+
+        Ok(events)
+    }
+
+    pub fn fetch_all(&mut self) -> Result<Vec<Event>, Box<dyn Error>> {
+        let mut stmt = self.conn.prepare(SELECT_ALL_EVENTS)?;
+        let event_iter = stmt.query_map([], |row| {
+            Ok(Event {
+                id: row.get(0)?,
+                start: row.get(1)?,
+                end: row.get(2)?,
+                duration: None,
+            })
+        })?;
+
+        let mut events = vec![];
+        for event in event_iter {
+            events.push(event?);
+        }
+
+        Ok(events)
+    }
+
+    pub fn restore(&mut self, events: &[Event]) -> Result<usize, Box<dyn Error>> {
+        Db::transaction(&self.conn, || {
+            for event in events {
+                self.conn.execute(RESTORE_EVENT, params![event.id, event.start, event.end])?;
+            }
+
+            Ok(events.len())
+        })
+    }
+
+    /// Replaces all events of a day with the given intervals in one transaction. The intervals take over the ids
+    /// the day already had, in order of their start, so the newest event of the database stays the newest one.
+    pub fn replace_day(&mut self, date: NaiveDate, events: &[Event]) -> Result<usize, Box<dyn Error>> {
+        Db::transaction(&self.conn, || {
+            let date = date.format("%Y-%m-%d").to_string();
+            let ids = self
+                .conn
+                .prepare(SELECT_DAILY_IDS)?
+                .query_map(params![date], |row| row.get::<_, i32>(0))?
+                .collect::<Result<Vec<_>>>()?;
+            self.conn.execute(DELETE_DAILY_EVENTS, params![date])?;
+            let mut events = events.to_vec();
+            events.sort_by_key(|event| event.start);
+            for (index, event) in events.iter().enumerate() {
+                match ids.get(index) {
+                    Some(id) => self.conn.execute(RESTORE_EVENT, params![id, event.start, event.end])?,
+                    None => self.conn.execute(INSERT_EVENT_INTERVAL, params![event.start, event.end])?,
+                };
+            }
+
+            Ok(events.len())
+        })
+    }
+
+    pub fn insert_interval(&mut self, start: NaiveDateTime, end: Option<NaiveDateTime>) -> Result<(), Box<dyn Error>> {
+        self.conn.execute(INSERT_EVENT_INTERVAL, params![start, end])?;
+
+        Ok(())
+    }
+
+    pub fn update(&mut self, event: &Event) -> Result<(), Box<dyn Error>> {
+        self.conn.execute(UPDATE_EVENT_INTERVAL, params![event.id, event.start, event.end])?;
+
+        Ok(())
+    }
+
+    pub fn delete(&mut self, id: i32) -> Result<(), Box<dyn Error>> {
+        self.conn.execute(DELETE_EVENT, params![id])?;
+
+        Ok(())
+    }
+
     pub fn insert(&mut self, event_type: &EventType) -> Result<()> {
-        let _ = match event_type {
+        Db::retry(|| match event_type {
             EventType::Start => self.start(),
             EventType::End => self.end(),
-        };
-
-        Ok(())
+        })
     }
 
     fn start(&mut self) -> Result<()> {
@@ -86,7 +177,7 @@ impl Events {
     }
 
     fn end(&mut self) -> Result<()> {
-        let transaction = self.conn.transaction()?;
+        let transaction = self.conn.unchecked_transaction()?;
 
         let maybe_row = transaction
             .query_row(SELECT_LAST_EVENT, [], |row| Ok((row.get::<_, i32>(0)?, row.get::<_, Option<String>>(1)?)))
@@ -103,3 +194,76 @@ impl Events {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::memory_db;
+
+    fn at(day: u32, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("2024-03-{:02} {}", day, time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn interval(start: NaiveDateTime, end: Option<NaiveDateTime>) -> Event {
+        Event {
+            id: 0,
+            start,
+            end,
+            duration: None,
+        }
+    }
+
+    fn ids_and_times(events: &[Event]) -> Vec<(i32, NaiveDateTime, Option<NaiveDateTime>)> {
+        events.iter().map(|event| (event.id, event.start, event.end)).collect()
+    }
+
+    #[test]
+    fn replaced_day_keeps_its_ids() {
+        let mut events = Events::with_connection(memory_db().unwrap());
+        events.insert_interval(at(4, "09:00"), Some(at(4, "12:00"))).unwrap();
+        events.insert_interval(at(4, "13:00"), Some(at(4, "17:00"))).unwrap();
+        events.insert_interval(at(5, "09:00"), Some(at(5, "17:00"))).unwrap();
+
+        events
+            .replace_day(NaiveDate::from_ymd_opt(2024, 3, 4).unwrap(), &[interval(at(4, "09:00"), Some(at(4, "16:00")))])
+            .unwrap();
+
+        assert_eq!(
+            ids_and_times(&events.fetch_all().unwrap()),
+            vec![(1, at(4, "09:00"), Some(at(4, "16:00"))), (3, at(5, "09:00"), Some(at(5, "17:00")))]
+        );
+    }
+
+    #[test]
+    fn extra_intervals_get_new_ids() {
+        let mut events = Events::with_connection(memory_db().unwrap());
+        events.insert_interval(at(4, "09:00"), Some(at(4, "17:00"))).unwrap();
+
+        events
+            .replace_day(
+                NaiveDate::from_ymd_opt(2024, 3, 4).unwrap(),
+                &[interval(at(4, "13:00"), Some(at(4, "17:00"))), interval(at(4, "09:00"), Some(at(4, "12:00")))],
+            )
+            .unwrap();
+
+        assert_eq!(
+            ids_and_times(&events.fetch_all().unwrap()),
+            vec![(1, at(4, "09:00"), Some(at(4, "12:00"))), (2, at(4, "13:00"), Some(at(4, "17:00")))]
+        );
+    }
+
+    #[test]
+    fn end_still_closes_the_open_event_after_an_older_day_is_replaced() {
+        let mut events = Events::with_connection(memory_db().unwrap());
+        events.insert_interval(at(4, "09:00"), Some(at(4, "12:00"))).unwrap();
+        events.insert_interval(at(4, "13:00"), Some(at(4, "17:00"))).unwrap();
+        events.insert_interval(at(5, "09:00"), None).unwrap();
+
+        events
+            .replace_day(NaiveDate::from_ymd_opt(2024, 3, 4).unwrap(), &[interval(at(4, "09:00"), Some(at(4, "16:00")))])
+            .unwrap();
+        events.insert(&EventType::End).unwrap();
+
+        assert!(events.fetch_all().unwrap().iter().all(|event| event.end.is_some()));
+    }
+}
@@ -0,0 +1,59 @@
+use super::db::Db;
+use crate::libs::health::Heartbeat;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::{error::Error, rc::Rc};
+
+/// Single row kept fresh by a running `kasl watch`; removed again on a clean shutdown.
+pub const SCHEMA_HEARTBEAT: &str = "CREATE TABLE IF NOT EXISTS heartbeat (
+    id INTEGER NOT NULL PRIMARY KEY CHECK (id = 1),
+    pid INTEGER NOT NULL,
+    beat_at TIMESTAMP NOT NULL,
+    last_active_at TIMESTAMP NOT NULL
+);";
+const UPSERT_HEARTBEAT: &str = "INSERT INTO heartbeat (id, pid, beat_at, last_active_at) VALUES (1, ?1, ?2, ?3)
+    ON CONFLICT (id) DO UPDATE SET pid = excluded.pid, beat_at = excluded.beat_at, last_active_at = excluded.last_active_at";
+const DELETE_HEARTBEAT: &str = "DELETE FROM heartbeat";
+const SELECT_HEARTBEAT: &str = "SELECT pid, beat_at, last_active_at FROM heartbeat WHERE id = 1";
+
+#[derive(Debug)]
+pub struct Heartbeats {
+    pub conn: Rc<Connection>,
+}
+
+impl Heartbeats {
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        Ok(Self::with_connection(Db::new()?.conn))
+    }
+
+    pub fn with_connection(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+
+    pub fn beat(&mut self, heartbeat: &Heartbeat) -> Result<(), Box<dyn Error>> {
+        Db::retry(|| {
+            self.conn
+                .execute(UPSERT_HEARTBEAT, params![heartbeat.pid, heartbeat.beat_at, heartbeat.last_active_at])
+        })?;
+
+        Ok(())
+    }
+
+    pub fn clear(&mut self) -> Result<(), Box<dyn Error>> {
+        Db::retry(|| self.conn.execute(DELETE_HEARTBEAT, []))?;
+
+        Ok(())
+    }
+
+    pub fn fetch(&mut self) -> Result<Option<Heartbeat>, Box<dyn Error>> {
+        Ok(self
+            .conn
+            .query_row(SELECT_HEARTBEAT, [], |row| {
+                Ok(Heartbeat {
+                    pid: row.get(0)?,
+                    beat_at: row.get(1)?,
+                    last_active_at: row.get(2)?,
+                })
+            })
+            .optional()?)
+    }
+}
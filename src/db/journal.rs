@@ -0,0 +1,78 @@
+use super::db::Db;
+use crate::libs::journal::JournalEntry;
+use chrono::NaiveDate;
+use rusqlite::{params, Connection};
+use std::{error::Error, rc::Rc};
+
+pub const SCHEMA_JOURNAL: &str = "CREATE TABLE IF NOT EXISTS journal (
+    id INTEGER NOT NULL PRIMARY KEY,
+    timestamp TIMESTAMP NOT NULL,
+    text TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_journal_timestamp ON journal (timestamp);";
+const INSERT_ENTRY: &str = "INSERT INTO journal (timestamp, text) VALUES (datetime(CURRENT_TIMESTAMP, 'localtime'), ?1)";
+const RESTORE_ENTRY: &str = "INSERT OR REPLACE INTO journal (id, timestamp, text) VALUES (?1, ?2, ?3)";
+const APPEND_ENTRY: &str = "INSERT INTO journal (timestamp, text) VALUES (?1, ?2)";
+const SELECT_DAILY_ENTRIES: &str = "SELECT id, timestamp, text FROM journal WHERE date(timestamp) = date(?1) ORDER BY timestamp, id";
+const SELECT_ALL_ENTRIES: &str = "SELECT id, timestamp, text FROM journal ORDER BY timestamp, id";
+
+#[derive(Debug)]
+pub struct Journal {
+    pub conn: Rc<Connection>,
+}
+
+impl Journal {
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        Ok(Self::with_connection(Db::new()?.conn))
+    }
+
+    pub fn with_connection(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+
+    pub fn insert(&mut self, text: &str) -> Result<(), Box<dyn Error>> {
+        self.conn.execute(INSERT_ENTRY, params![text])?;
+
+        Ok(())
+    }
+
+    /// Inserts an entry from another database under a new ID, keeping its timestamp.
+    pub fn append(&mut self, entry: &JournalEntry) -> Result<(), Box<dyn Error>> {
+        self.conn.execute(APPEND_ENTRY, params![entry.timestamp, entry.text])?;
+
+        Ok(())
+    }
+
+    /// Entries written on `date`, oldest first; `None` returns every entry.
+    pub fn fetch(&mut self, date: Option<NaiveDate>) -> Result<Vec<JournalEntry>, Box<dyn Error>> {
+        let mut stmt = match date {
+            Some(_) => self.conn.prepare(SELECT_DAILY_ENTRIES)?,
+            None => self.conn.prepare(SELECT_ALL_ENTRIES)?,
+        };
+        let date = date.map(|date| date.format("%Y-%m-%d").to_string());
+        let entry_iter = stmt.query_map(rusqlite::params_from_iter(date), |row| {
+            Ok(JournalEntry {
+                id: row.get(0)?,
+                timestamp: row.get(1)?,
+                text: row.get(2)?,
+            })
+        })?;
+
+        let mut entries = vec![];
+        for entry in entry_iter {
+            entries.push(entry?);
+        }
+
+        Ok(entries)
+    }
+
+    pub fn restore(&mut self, entries: &[JournalEntry]) -> Result<usize, Box<dyn Error>> {
+        Db::transaction(&self.conn, || {
+            for entry in entries {
+                self.conn.execute(RESTORE_ENTRY, params![entry.id, entry.timestamp, entry.text])?;
+            }
+
+            Ok(entries.len())
+        })
+    }
+}
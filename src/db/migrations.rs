@@ -0,0 +1,171 @@
+use super::{
+    event_changes::SCHEMA_EVENT_CHANGES, events::SCHEMA_EVENTS, heartbeat::SCHEMA_HEARTBEAT, journal::SCHEMA_JOURNAL, projects::SCHEMA_PROJECTS,
+    reminders::SCHEMA_REMINDERS, reports::SCHEMA_REPORT_SUBMISSIONS, task_notes::SCHEMA_TASK_NOTES, tasks::SCHEMA_TASKS,
+};
+use rusqlite::{Connection, Result};
+
+const SCHEMA_TASKS_DELETED_AT: &str = "ALTER TABLE tasks ADD COLUMN deleted_at TIMESTAMP;
+CREATE INDEX IF NOT EXISTS idx_tasks_deleted_at ON tasks (deleted_at);";
+const SCHEMA_REPORT_SUBMISSIONS_STATUS: &str = "ALTER TABLE report_submissions ADD COLUMN status INTEGER NOT NULL DEFAULT 200;";
+const SCHEMA_TASKS_EXTERNAL_ID: &str = "ALTER TABLE tasks ADD COLUMN source TEXT;
+ALTER TABLE tasks ADD COLUMN external_id TEXT;
+CREATE INDEX IF NOT EXISTS idx_tasks_external_id ON tasks (source, external_id);";
+const SCHEMA_TASKS_EXTERNAL_URL: &str = "ALTER TABLE tasks ADD COLUMN external_url TEXT;";
+const SCHEMA_TASKS_PROJECT_ID: &str = "ALTER TABLE tasks ADD COLUMN project_id INTEGER;
+CREATE INDEX IF NOT EXISTS idx_tasks_project_id ON tasks (project_id);";
+
+const DROP_EVENTS_AND_TASKS: &str = "DROP TABLE tasks;
+DROP TABLE events;";
+const DROP_TASKS_DELETED_AT: &str = "DROP INDEX IF EXISTS idx_tasks_deleted_at;
+ALTER TABLE tasks DROP COLUMN deleted_at;";
+const DROP_REPORT_SUBMISSIONS: &str = "DROP TABLE report_submissions;";
+const DROP_REPORT_SUBMISSIONS_STATUS: &str = "ALTER TABLE report_submissions DROP COLUMN status;";
+const DROP_TASKS_EXTERNAL_ID: &str = "DROP INDEX IF EXISTS idx_tasks_external_id;
+ALTER TABLE tasks DROP COLUMN external_id;
+ALTER TABLE tasks DROP COLUMN source;";
+const DROP_TASKS_EXTERNAL_URL: &str = "ALTER TABLE tasks DROP COLUMN external_url;";
+const DROP_TASK_NOTES: &str = "DROP TABLE task_notes;";
+const DROP_JOURNAL: &str = "DROP TABLE journal;";
+const DROP_PROJECTS: &str = "DROP INDEX IF EXISTS idx_tasks_project_id;
+ALTER TABLE tasks DROP COLUMN project_id;
+DROP TABLE projects;";
+const DROP_EVENT_CHANGES: &str = "DROP TABLE event_changes;";
+const DROP_REMINDERS: &str = "DROP TABLE reminders;";
+const DROP_HEARTBEAT: &str = "DROP TABLE heartbeat;";
+
+struct Migration {
+    up: &'static [&'static str],
+    down: &'static str,
+}
+
+/// Schema changes in order of application; the index + 1 is stored in `PRAGMA user_version`.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        up: &[SCHEMA_EVENTS, SCHEMA_TASKS],
+        down: DROP_EVENTS_AND_TASKS,
+    },
+    Migration {
+        up: &[SCHEMA_TASKS_DELETED_AT],
+        down: DROP_TASKS_DELETED_AT,
+    },
+    Migration {
+        up: &[SCHEMA_REPORT_SUBMISSIONS],
+        down: DROP_REPORT_SUBMISSIONS,
+    },
+    Migration {
+        up: &[SCHEMA_REPORT_SUBMISSIONS_STATUS],
+        down: DROP_REPORT_SUBMISSIONS_STATUS,
+    },
+    Migration {
+        up: &[SCHEMA_TASKS_EXTERNAL_ID],
+        down: DROP_TASKS_EXTERNAL_ID,
+    },
+    Migration {
+        up: &[SCHEMA_TASKS_EXTERNAL_URL],
+        down: DROP_TASKS_EXTERNAL_URL,
+    },
+    Migration {
+        up: &[SCHEMA_TASK_NOTES],
+        down: DROP_TASK_NOTES,
+    },
+    Migration {
+        up: &[SCHEMA_JOURNAL],
+        down: DROP_JOURNAL,
+    },
+    Migration {
+        up: &[SCHEMA_PROJECTS, SCHEMA_TASKS_PROJECT_ID],
+        down: DROP_PROJECTS,
+    },
+    Migration {
+        up: &[SCHEMA_EVENT_CHANGES],
+        down: DROP_EVENT_CHANGES,
+    },
+    Migration {
+        up: &[SCHEMA_REMINDERS],
+        down: DROP_REMINDERS,
+    },
+    Migration {
+        up: &[SCHEMA_HEARTBEAT],
+        down: DROP_HEARTBEAT,
+    },
+];
+
+/// Schema version this build migrates to.
+pub fn latest() -> usize {
+    MIGRATIONS.len()
+}
+
+pub fn version(conn: &Connection) -> Result<usize> {
+    conn.pragma_query_value(None, "user_version", |row| row.get(0))
+}
+
+/// Whether the file already holds kasl data; databases from before schema versioning have tables at version 0.
+pub fn has_data(conn: &Connection) -> Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'events')",
+        [],
+        |row| row.get(0),
+    )
+}
+
+pub fn migrate(conn: &mut Connection) -> Result<()> {
+    let version = self::version(conn)?;
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let transaction = conn.transaction()?;
+        for statement in migration.up.iter() {
+            transaction.execute_batch(statement)?;
+        }
+        transaction.pragma_update(None, "user_version", index + 1)?;
+        transaction.commit()?;
+    }
+
+    Ok(())
+}
+
+/// Reverts migrations newest first until the schema is at version `to`; the data they added is dropped.
+pub fn rollback(conn: &Connection, to: usize) -> Result<()> {
+    let version = self::version(conn)?;
+    for index in (to..version.min(MIGRATIONS.len())).rev() {
+        let transaction = conn.unchecked_transaction()?;
+        transaction.execute_batch(MIGRATIONS[index].down)?;
+        transaction.pragma_update(None, "user_version", index)?;
+        transaction.commit()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_file_has_no_data() {
+        let conn = Connection::open_in_memory().unwrap();
+
+        assert!(!has_data(&conn).unwrap());
+    }
+
+    #[test]
+    fn unversioned_legacy_database_has_data() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE events (id INTEGER NOT NULL PRIMARY KEY, start TIMESTAMP NOT NULL)")
+            .unwrap();
+
+        assert_eq!(version(&conn).unwrap(), 0);
+        assert!(has_data(&conn).unwrap());
+    }
+
+    #[test]
+    fn migrated_database_rolls_back_and_forth() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        assert_eq!(version(&conn).unwrap(), latest());
+
+        rollback(&conn, 1).unwrap();
+        assert_eq!(version(&conn).unwrap(), 1);
+        assert!(has_data(&conn).unwrap());
+        migrate(&mut conn).unwrap();
+        assert_eq!(version(&conn).unwrap(), latest());
+    }
+}
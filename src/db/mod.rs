@@ -1,3 +1,11 @@
 pub mod db;
+pub mod event_changes;
 pub mod events;
+pub mod heartbeat;
+pub mod journal;
+pub mod migrations;
+pub mod projects;
+pub mod reminders;
+pub mod reports;
+pub mod task_notes;
 pub mod tasks;
@@ -0,0 +1,104 @@
+use super::db::Db;
+use crate::libs::project::Project;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::{error::Error, rc::Rc};
+
+pub const SCHEMA_PROJECTS: &str = "CREATE TABLE IF NOT EXISTS projects (
+    id INTEGER NOT NULL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    client TEXT,
+    tags TEXT NOT NULL DEFAULT '',
+    hourly_rate REAL
+);";
+const INSERT_PROJECT: &str = "INSERT INTO projects (name, client, tags, hourly_rate) VALUES (?1, ?2, ?3, ?4) RETURNING id";
+const UPDATE_PROJECT: &str = "UPDATE projects SET name = ?2, client = ?3, tags = ?4, hourly_rate = ?5 WHERE id = ?1";
+const RESTORE_PROJECT: &str = "INSERT OR REPLACE INTO projects (id, name, client, tags, hourly_rate) VALUES (?1, ?2, ?3, ?4, ?5)";
+const DELETE_PROJECT: &str = "DELETE FROM projects WHERE id = ?1";
+const UNASSIGN_TASKS: &str = "UPDATE tasks SET project_id = NULL WHERE project_id = ?1";
+const SELECT_PROJECTS: &str = "SELECT id, name, client, tags, hourly_rate FROM projects ORDER BY name";
+const SELECT_PROJECT: &str = "SELECT id, name, client, tags, hourly_rate FROM projects WHERE id = ?1 OR name = ?2";
+
+#[derive(Debug)]
+pub struct Projects {
+    pub conn: Rc<Connection>,
+}
+
+impl Projects {
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        Ok(Self::with_connection(Db::new()?.conn))
+    }
+
+    pub fn with_connection(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+
+    pub fn insert(&mut self, project: &Project) -> Result<i32, Box<dyn Error>> {
+        Ok(self.conn.query_row(
+            INSERT_PROJECT,
+            params![project.name, project.client, project.tags.join(","), project.hourly_rate],
+            |row| row.get(0),
+        )?)
+    }
+
+    pub fn update(&mut self, project: &Project) -> Result<(), Box<dyn Error>> {
+        self.conn.execute(
+            UPDATE_PROJECT,
+            params![project.id, project.name, project.client, project.tags.join(","), project.hourly_rate],
+        )?;
+
+        Ok(())
+    }
+
+    /// Deletes a project; its tasks stay and lose the project.
+    pub fn delete(&mut self, id: i32) -> Result<usize, Box<dyn Error>> {
+        Db::transaction(&self.conn, || {
+            let count = self.conn.execute(UNASSIGN_TASKS, params![id])?;
+            self.conn.execute(DELETE_PROJECT, params![id])?;
+
+            Ok(count)
+        })
+    }
+
+    pub fn fetch(&mut self) -> Result<Vec<Project>, Box<dyn Error>> {
+        let mut stmt = self.conn.prepare(SELECT_PROJECTS)?;
+        let project_iter = stmt.query_map([], Self::project)?;
+
+        let mut projects = vec![];
+        for project in project_iter {
+            projects.push(project?);
+        }
+
+        Ok(projects)
+    }
+
+    /// Looks a project up by ID or by name.
+    pub fn find(&mut self, project: &str) -> Result<Option<Project>, Box<dyn Error>> {
+        Ok(self
+            .conn
+            .query_row(SELECT_PROJECT, params![project.parse::<i32>().ok(), project], Self::project)
+            .optional()?)
+    }
+
+    pub fn restore(&mut self, projects: &[Project]) -> Result<usize, Box<dyn Error>> {
+        Db::transaction(&self.conn, || {
+            for project in projects {
+                self.conn.execute(
+                    RESTORE_PROJECT,
+                    params![project.id, project.name, project.client, project.tags.join(","), project.hourly_rate],
+                )?;
+            }
+
+            Ok(projects.len())
+        })
+    }
+
+    fn project(row: &Row) -> rusqlite::Result<Project> {
+        Ok(Project {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            client: row.get(2)?,
+            tags: Project::parse_tags(&row.get::<_, String>(3)?),
+            hourly_rate: row.get(4)?,
+        })
+    }
+}
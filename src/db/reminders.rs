@@ -0,0 +1,58 @@
+use super::db::Db;
+use crate::libs::reminder::Reminder;
+use rusqlite::{params, Connection};
+use std::{error::Error, rc::Rc};
+
+pub const SCHEMA_REMINDERS: &str = "CREATE TABLE IF NOT EXISTS reminders (
+    id INTEGER NOT NULL PRIMARY KEY,
+    minutes INTEGER NOT NULL,
+    message TEXT NOT NULL,
+    command TEXT
+);";
+const INSERT_REMINDER: &str = "INSERT INTO reminders (minutes, message, command) VALUES (?1, ?2, ?3) RETURNING id";
+const DELETE_REMINDER: &str = "DELETE FROM reminders WHERE id = ?1";
+const SELECT_REMINDERS: &str = "SELECT id, minutes, message, command FROM reminders ORDER BY id";
+
+#[derive(Debug)]
+pub struct Reminders {
+    pub conn: Rc<Connection>,
+}
+
+impl Reminders {
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        Ok(Self::with_connection(Db::new()?.conn))
+    }
+
+    pub fn with_connection(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+
+    pub fn insert(&mut self, reminder: &Reminder) -> Result<i32, Box<dyn Error>> {
+        Ok(self
+            .conn
+            .query_row(INSERT_REMINDER, params![reminder.minutes, reminder.message, reminder.command], |row| row.get(0))?)
+    }
+
+    pub fn delete(&mut self, id: i32) -> Result<usize, Box<dyn Error>> {
+        Ok(self.conn.execute(DELETE_REMINDER, params![id])?)
+    }
+
+    pub fn fetch(&mut self) -> Result<Vec<Reminder>, Box<dyn Error>> {
+        let mut stmt = self.conn.prepare(SELECT_REMINDERS)?;
+        let reminder_iter = stmt.query_map([], |row| {
+            Ok(Reminder {
+                id: row.get(0)?,
+                minutes: row.get(1)?,
+                message: row.get(2)?,
+                command: row.get(3)?,
+            })
+        })?;
+
+        let mut reminders = vec![];
+        for reminder in reminder_iter {
+            reminders.push(reminder?);
+        }
+
+        Ok(reminders)
+    }
+}
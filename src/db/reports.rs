@@ -0,0 +1,95 @@
+use super::db::Db;
+use crate::libs::report::ReportSubmission;
+use chrono::NaiveDate;
+use rusqlite::{params, Connection};
+use std::{collections::HashSet, error::Error, rc::Rc};
+
+pub const SCHEMA_REPORT_SUBMISSIONS: &str = "CREATE TABLE IF NOT EXISTS report_submissions (
+    id INTEGER NOT NULL PRIMARY KEY,
+    date DATE NOT NULL,
+    submitted_at TIMESTAMP NOT NULL,
+    payload TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_report_submissions_date ON report_submissions (date);";
+const INSERT_SUBMISSION: &str =
+    "INSERT INTO report_submissions (date, submitted_at, payload, status) VALUES (?1, datetime(CURRENT_TIMESTAMP, 'localtime'), ?2, ?3)";
+const WHERE_SUCCESSFUL: &str = "status BETWEEN 200 AND 299";
+const SELECT_SUBMISSIONS: &str = "SELECT date, submitted_at, payload, status FROM report_submissions WHERE date = ?1";
+const SELECT_FIRST_DATE: &str = "SELECT MIN(date) FROM report_submissions";
+
+#[derive(Debug)]
+pub enum SubmissionFilter {
+    All,
+    Successful,
+}
+
+#[derive(Debug)]
+pub struct Reports {
+    pub conn: Rc<Connection>,
+}
+
+impl Reports {
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        Ok(Self::with_connection(Db::new()?.conn))
+    }
+
+    pub fn with_connection(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+
+    /// Records a submission attempt with the HTTP status the server answered with.
+    pub fn insert(&mut self, date: &NaiveDate, payload: &str, status: u16) -> Result<(), Box<dyn Error>> {
+        Db::retry(|| self.conn.execute(INSERT_SUBMISSION, params![date, payload, status]))?;
+
+        Ok(())
+    }
+
+    /// The earliest date with a submission attempt; submissions are tracked from then on.
+    pub fn fetch_first_date(&mut self) -> Result<Option<NaiveDate>, Box<dyn Error>> {
+        Ok(self.conn.query_row(SELECT_FIRST_DATE, [], |row| row.get(0))?)
+    }
+
+    pub fn fetch_submitted_dates(&mut self, from: NaiveDate, to: NaiveDate) -> Result<HashSet<NaiveDate>, Box<dyn Error>> {
+        let query = format!(
+            "SELECT DISTINCT date FROM report_submissions WHERE date BETWEEN ?1 AND ?2 AND {}",
+            WHERE_SUCCESSFUL
+        );
+        let mut stmt = self.conn.prepare(&query)?;
+        let date_iter = stmt.query_map(params![from, to], |row| row.get::<_, NaiveDate>(0))?;
+
+        let mut dates = HashSet::new();
+        for date in date_iter {
+            dates.insert(date?);
+        }
+
+        Ok(dates)
+    }
+
+    pub fn fetch(&mut self, date: &NaiveDate, filter: SubmissionFilter) -> Result<Vec<ReportSubmission>, Box<dyn Error>> {
+        let query = match filter {
+            SubmissionFilter::All => format!("{} ORDER BY id", SELECT_SUBMISSIONS),
+            SubmissionFilter::Successful => format!("{} AND {} ORDER BY id", SELECT_SUBMISSIONS, WHERE_SUCCESSFUL),
+        };
+        let mut stmt = self.conn.prepare(&query)?;
+        let submission_iter = stmt.query_map(params![date], |row| {
+            Ok(ReportSubmission {
+                date: row.get(0)?,
+                submitted_at: row.get(1)?,
+                payload: row.get(2)?,
+                status: row.get(3)?,
+            })
+        })?;
+
+        let mut submissions = vec![];
+        for submission in submission_iter {
+            submissions.push(submission?);
+        }
+
+        Ok(submissions)
+    }
+
+    /// The most recent successful submission for `date`.
+    pub fn fetch_last(&mut self, date: &NaiveDate) -> Result<Option<ReportSubmission>, Box<dyn Error>> {
+        Ok(self.fetch(date, SubmissionFilter::Successful)?.pop())
+    }
+}
@@ -0,0 +1,85 @@
+use super::db::Db;
+use crate::libs::task::TaskNote;
+use rusqlite::{params, Connection};
+use std::{error::Error, rc::Rc};
+
+pub const SCHEMA_TASK_NOTES: &str = "CREATE TABLE IF NOT EXISTS task_notes (
+    id INTEGER NOT NULL PRIMARY KEY,
+    task_id INTEGER NOT NULL,
+    timestamp TIMESTAMP NOT NULL,
+    text TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_task_notes_task_id ON task_notes (task_id);";
+const INSERT_NOTE: &str = "INSERT INTO task_notes (task_id, timestamp, text) VALUES (?1, datetime(CURRENT_TIMESTAMP, 'localtime'), ?2)";
+const RESTORE_NOTE: &str = "INSERT OR REPLACE INTO task_notes (id, task_id, timestamp, text) VALUES (?1, ?2, ?3, ?4)";
+const APPEND_NOTE: &str = "INSERT INTO task_notes (task_id, timestamp, text) VALUES (?1, ?2, ?3)";
+const DELETE_ORPHANED_NOTES: &str =
+    "DELETE FROM task_notes WHERE task_id NOT IN (SELECT id FROM tasks UNION SELECT task_id FROM tasks WHERE task_id IS NOT NULL)";
+const SELECT_NOTES: &str = "SELECT id, task_id, timestamp, text FROM task_notes WHERE task_id = ?1 ORDER BY timestamp, id";
+const SELECT_ALL_NOTES: &str = "SELECT id, task_id, timestamp, text FROM task_notes ORDER BY timestamp, id";
+
+#[derive(Debug)]
+pub struct TaskNotes {
+    pub conn: Rc<Connection>,
+}
+
+impl TaskNotes {
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        Ok(Self::with_connection(Db::new()?.conn))
+    }
+
+    pub fn with_connection(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+
+    pub fn insert(&mut self, task_id: i32, text: &str) -> Result<(), Box<dyn Error>> {
+        self.conn.execute(INSERT_NOTE, params![task_id, text])?;
+
+        Ok(())
+    }
+
+    /// Inserts a note from another database under a new ID, keeping its timestamp.
+    pub fn append(&mut self, note: &TaskNote) -> Result<(), Box<dyn Error>> {
+        self.conn.execute(APPEND_NOTE, params![note.task_id, note.timestamp, note.text])?;
+
+        Ok(())
+    }
+
+    /// Removes notes whose task chain has no records left.
+    pub fn delete_orphaned(&mut self) -> Result<usize, Box<dyn Error>> {
+        Ok(self.conn.execute(DELETE_ORPHANED_NOTES, [])?)
+    }
+
+    /// Notes of a task across all of its daily records, oldest first; `None` returns every note.
+    pub fn fetch(&mut self, task_id: Option<i32>) -> Result<Vec<TaskNote>, Box<dyn Error>> {
+        let mut stmt = match task_id {
+            Some(_) => self.conn.prepare(SELECT_NOTES)?,
+            None => self.conn.prepare(SELECT_ALL_NOTES)?,
+        };
+        let note_iter = stmt.query_map(rusqlite::params_from_iter(task_id), |row| {
+            Ok(TaskNote {
+                id: row.get(0)?,
+                task_id: row.get(1)?,
+                timestamp: row.get(2)?,
+                text: row.get(3)?,
+            })
+        })?;
+
+        let mut notes = vec![];
+        for note in note_iter {
+            notes.push(note?);
+        }
+
+        Ok(notes)
+    }
+
+    pub fn restore(&mut self, notes: &[TaskNote]) -> Result<usize, Box<dyn Error>> {
+        Db::transaction(&self.conn, || {
+            for note in notes {
+                self.conn.execute(RESTORE_NOTE, params![note.id, note.task_id, note.timestamp, note.text])?;
+            }
+
+            Ok(notes.len())
+        })
+    }
+}
@@ -1,9 +1,9 @@
 use super::db::Db;
 use crate::libs::task::{Task, TaskFilter};
-use rusqlite::{params, Connection, Result, Statement, ToSql};
-use std::{error::Error, vec};
+use rusqlite::{params, params_from_iter, Connection, Result, Statement, ToSql};
+use std::{error::Error, rc::Rc, vec};
 
-const SCHEMA_TASKS: &str = "CREATE TABLE IF NOT EXISTS tasks (
+pub const SCHEMA_TASKS: &str = "CREATE TABLE IF NOT EXISTS tasks (
     id INTEGER NOT NULL PRIMARY KEY,
     task_id INTEGER NOT NULL ON CONFLICT REPLACE DEFAULT 0,
     timestamp TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
@@ -12,46 +12,128 @@ const SCHEMA_TASKS: &str = "CREATE TABLE IF NOT EXISTS tasks (
     completeness INTEGER NOT NULL ON CONFLICT REPLACE DEFAULT 100,
     excluded_from_search BOOLEAN NOT NULL ON CONFLICT REPLACE DEFAULT FALSE
 );";
-const INSERT_TASK: &str = "INSERT INTO tasks (task_id, timestamp, name, comment, completeness, excluded_from_search) VALUES 
-    (?, datetime(CURRENT_TIMESTAMP, 'localtime'), ?, ?, ?, ?) RETURNING id";
+const INSERT_TASK: &str =
+    "INSERT INTO tasks (task_id, timestamp, name, comment, completeness, excluded_from_search, source, external_id, external_url, project_id) VALUES 
+    (?, datetime(CURRENT_TIMESTAMP, 'localtime'), ?, ?, ?, ?, ?, ?, ?, ?) RETURNING id";
+const RESTORE_TASK: &str =
+    "INSERT OR REPLACE INTO tasks (id, task_id, timestamp, name, comment, completeness, excluded_from_search, deleted_at, source, external_id, external_url, project_id)
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
+const APPEND_TASK: &str =
+    "INSERT INTO tasks (timestamp, name, comment, completeness, excluded_from_search, deleted_at, source, external_id, external_url, project_id)
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?) RETURNING id";
+const UPDATE_COMPLETENESS: &str = "UPDATE tasks SET completeness = ?2 WHERE id = ?1 AND deleted_at IS NULL";
 const UPDATE_TASK_ID: &str = "UPDATE tasks SET task_id = ? WHERE id = ?";
-const SELECT_TASKS: &str = "SELECT * FROM tasks";
-const WHERE_DATE: &str = "WHERE date(timestamp) = date(?1, 'localtime')";
-const WHERE_ID_IN: &str = "WHERE task_id IN";
-const WHERE_INCOMPLETE: &str = "WHERE
+const TRASH_TASKS: &str = "UPDATE tasks SET deleted_at = datetime(CURRENT_TIMESTAMP, 'localtime') WHERE deleted_at IS NULL AND id IN";
+const UNTRASH_TASKS: &str = "UPDATE tasks SET deleted_at = NULL WHERE deleted_at IS NOT NULL AND id IN";
+const ASSIGN_PROJECT: &str = "UPDATE tasks SET project_id = ? WHERE deleted_at IS NULL AND";
+const PURGE_TASKS: &str = "DELETE FROM tasks WHERE deleted_at IS NOT NULL";
+const SELECT_TASKS: &str =
+    "SELECT id, task_id, timestamp, name, comment, completeness, excluded_from_search, deleted_at, source, external_id, external_url, project_id FROM tasks WHERE deleted_at IS NULL";
+const SELECT_DELETED_TASKS: &str =
+    "SELECT id, task_id, timestamp, name, comment, completeness, excluded_from_search, deleted_at, source, external_id, external_url, project_id FROM tasks WHERE deleted_at IS NOT NULL";
+const WHERE_DATE: &str = "AND date(timestamp) = date(?1, 'localtime')";
+const WHERE_RANGE: &str = "AND date(timestamp) BETWEEN ?1 AND ?2";
+const WHERE_ID_IN: &str = "AND task_id IN";
+const WHERE_ROW_ID_IN: &str = "AND id IN";
+const WHERE_SOURCE: &str = "AND source = ?1";
+const WHERE_EXTERNAL_ID: &str = "AND source = ?1 AND external_id = ?2 ORDER BY id DESC LIMIT 1";
+const WHERE_INCOMPLETE: &str = "AND
   completeness < 100 AND
   task_id NOT IN (SELECT task_id FROM tasks WHERE DATE(timestamp) = DATE('now')) AND
   (task_id, completeness) IN (SELECT task_id, MAX(completeness) FROM tasks
-  WHERE DATE(timestamp) BETWEEN datetime(CURRENT_TIMESTAMP, 'localtime', '-15 day') AND datetime(CURRENT_TIMESTAMP, 'localtime', '-1 day')
+  WHERE deleted_at IS NULL AND DATE(timestamp) BETWEEN datetime(CURRENT_TIMESTAMP, 'localtime', '-15 day') AND datetime(CURRENT_TIMESTAMP, 'localtime', '-1 day')
   GROUP BY task_id)
   GROUP BY task_id";
 
 #[derive(Debug)]
 pub struct Tasks {
-    pub conn: Connection,
+    pub conn: Rc<Connection>,
     pub id: Option<i32>,
 }
 
 impl Tasks {
     pub fn new() -> Result<Self, Box<dyn Error>> {
-        let db = Db::new()?;
-        db.conn.execute(&SCHEMA_TASKS, [])?;
+        Ok(Self::with_connection(Db::new()?.conn))
+    }
 
-        Ok(Self { conn: db.conn, id: None })
+    pub fn with_connection(conn: Rc<Connection>) -> Self {
+        Self { conn, id: None }
     }
 
     pub fn insert(&mut self, task: &Task) -> Result<&mut Self, Box<dyn Error>> {
-        self.id = self.conn.query_row(
-            INSERT_TASK,
-            params![task.task_id, task.name, task.comment, task.completeness, task.excluded_from_search],
+        self.id = Db::retry(|| {
+            self.conn.query_row(
+                INSERT_TASK,
+                params![
+                    task.task_id,
+                    task.name,
+                    task.comment,
+                    task.completeness,
+                    task.excluded_from_search,
+                    task.source,
+                    task.external_id,
+                    task.external_url,
+                    task.project_id
+                ],
+                |row| row.get(0),
+            )
+        })?;
+
+        Ok(self)
+    }
+
+    pub fn restore(&mut self, tasks: &[Task]) -> Result<usize, Box<dyn Error>> {
+        Db::transaction(&self.conn, || {
+            for task in tasks {
+                self.conn.execute(
+                    RESTORE_TASK,
+                    params![
+                        task.id,
+                        task.task_id,
+                        task.timestamp,
+                        task.name,
+                        task.comment,
+                        task.completeness,
+                        task.excluded_from_search,
+                        task.deleted_at,
+                        task.source,
+                        task.external_id,
+                        task.external_url,
+                        task.project_id
+                    ],
+                )?;
+            }
+
+            Ok(tasks.len())
+        })
+    }
+
+    /// Inserts a task from another database under a new ID, keeping its timestamp, and links it to the chain
+    /// `chain_id`, or starts a new chain with it.
+    pub fn append(&mut self, task: &Task, chain_id: Option<i32>) -> Result<i32, Box<dyn Error>> {
+        let id: i32 = self.conn.query_row(
+            APPEND_TASK,
+            params![
+                task.timestamp,
+                task.name,
+                task.comment,
+                task.completeness,
+                task.excluded_from_search,
+                task.deleted_at,
+                task.source,
+                task.external_id,
+                task.external_url,
+                task.project_id
+            ],
             |row| row.get(0),
         )?;
+        self.conn.execute(UPDATE_TASK_ID, params![chain_id.unwrap_or(id), id])?;
 
-        Ok(self)
+        Ok(id)
     }
 
     pub fn update_id(&mut self) -> Result<&mut Self, Box<dyn Error>> {
-        self.conn.execute(UPDATE_TASK_ID, params![self.id, self.id])?;
+        Db::retry(|| self.conn.execute(UPDATE_TASK_ID, params![self.id, self.id]))?;
 
         Ok(self)
     }
@@ -66,12 +148,27 @@ impl Tasks {
     pub fn fetch(&mut self, filter: TaskFilter) -> Result<Vec<Task>, Box<dyn Error>> {
         let (mut stmt, params): (Statement, Vec<Box<dyn ToSql>>) = match filter {
             TaskFilter::All => (self.conn.prepare(SELECT_TASKS)?, vec![]),
+            TaskFilter::Deleted => (self.conn.prepare(SELECT_DELETED_TASKS)?, vec![]),
             TaskFilter::Date(date) => (self.conn.prepare(&format!("{} {}", SELECT_TASKS, WHERE_DATE))?, vec![Box::new(date)]),
+            TaskFilter::Range(from, to) => (
+                self.conn.prepare(&format!("{} {}", SELECT_TASKS, WHERE_RANGE))?,
+                vec![Box::new(from), Box::new(to)],
+            ),
             TaskFilter::Incomplete => (self.conn.prepare(&format!("{} {}", SELECT_TASKS, WHERE_INCOMPLETE))?, vec![]),
             TaskFilter::ByIds(ids) => {
                 let ids_params: Vec<Box<dyn ToSql>> = ids.clone().into_iter().map(|id| Box::new(id) as Box<dyn ToSql>).collect();
                 (self.conn.prepare(&Self::query_by_ids(&ids))?, ids_params)
             }
+            TaskFilter::ByExternalId(source, external_id) => (
+                self.conn.prepare(&format!("{} {}", SELECT_TASKS, WHERE_EXTERNAL_ID))?,
+                vec![Box::new(source), Box::new(external_id)],
+            ),
+            TaskFilter::BySource(source) => (self.conn.prepare(&format!("{} {}", SELECT_TASKS, WHERE_SOURCE))?, vec![Box::new(source)]),
+            TaskFilter::ByRowIds(ids) => {
+                let ids_params: Vec<Box<dyn ToSql>> = ids.clone().into_iter().map(|id| Box::new(id) as Box<dyn ToSql>).collect();
+                let query = Self::ids_clause(&format!("{} {}", SELECT_TASKS, WHERE_ROW_ID_IN), &ids);
+                (self.conn.prepare(&query)?, ids_params)
+            }
         };
 
         let params_refs: Vec<&dyn ToSql> = params.iter().map(|p| &**p).collect();
@@ -84,6 +181,11 @@ impl Tasks {
                 comment: row.get(4)?,
                 completeness: row.get(5)?,
                 excluded_from_search: row.get(6)?,
+                deleted_at: row.get(7)?,
+                source: row.get(8)?,
+                external_id: row.get(9)?,
+                external_url: row.get(10)?,
+                project_id: row.get(11)?,
             })
         })?;
         let mut tasks = Vec::new();
@@ -94,6 +196,44 @@ impl Tasks {
         Ok(tasks)
     }
 
+    pub fn update_completeness(&mut self, id: i32, completeness: i32) -> Result<&mut Self, Box<dyn Error>> {
+        Db::retry(|| self.conn.execute(UPDATE_COMPLETENESS, params![id, completeness.clamp(0, 100)]))?;
+
+        Ok(self)
+    }
+
+    /// Assigns task chains, i.e. all daily records of the tasks with the given chain IDs, to a project or clears it with `None`.
+    pub fn assign_project(&mut self, chain_ids: &[i32], project_id: Option<i32>) -> Result<usize, Box<dyn Error>> {
+        let placeholders = vec!["?"; chain_ids.len()].join(", ");
+        let query = format!("{} (id IN ({}) OR task_id IN ({}))", ASSIGN_PROJECT, placeholders, placeholders);
+        let mut params: Vec<Box<dyn ToSql>> = vec![Box::new(project_id)];
+        params.extend(chain_ids.iter().chain(chain_ids.iter()).map(|id| Box::new(*id) as Box<dyn ToSql>));
+
+        Ok(Db::retry(|| self.conn.execute(&query, params_from_iter(params.iter().map(|p| &**p))))?)
+    }
+
+    /// Moves tasks to the trash; returns the number of affected tasks.
+    pub fn trash(&mut self, ids: &[i32]) -> Result<usize, Box<dyn Error>> {
+        Ok(Db::retry(|| self.conn.execute(&Self::ids_clause(TRASH_TASKS, ids), params_from_iter(ids)))?)
+    }
+
+    pub fn untrash(&mut self, ids: &[i32]) -> Result<usize, Box<dyn Error>> {
+        Ok(Db::retry(|| self.conn.execute(&Self::ids_clause(UNTRASH_TASKS, ids), params_from_iter(ids)))?)
+    }
+
+    /// Permanently deletes trashed tasks, all of them when `ids` is empty.
+    pub fn purge(&mut self, ids: &[i32]) -> Result<usize, Box<dyn Error>> {
+        if ids.is_empty() {
+            return Ok(Db::retry(|| self.conn.execute(PURGE_TASKS, []))?);
+        }
+        let query = Self::ids_clause(&format!("{} AND id IN", PURGE_TASKS), ids);
+        Ok(Db::retry(|| self.conn.execute(&query, params_from_iter(ids)))?)
+    }
+
+    fn ids_clause(query: &str, ids: &[i32]) -> String {
+        format!("{} ({})", query, vec!["?"; ids.len()].join(", "))
+    }
+
     fn query_by_ids(ids: &Vec<i32>) -> String {
         format!("{} {} ({})", SELECT_TASKS, WHERE_ID_IN, vec!["?"; ids.len()].join(", "))
     }
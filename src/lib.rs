@@ -0,0 +1,7 @@
+//! Library half of kasl: the database tables, the domain modules and the API clients, so they can be used
+//! and tested outside the command-line interface. Enable the `testing` feature for the fixtures in [`testing`].
+pub mod api;
+pub mod db;
+pub mod libs;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
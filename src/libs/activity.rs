@@ -0,0 +1,372 @@
+use chrono::{Duration, NaiveDateTime};
+use device_query::{DeviceQuery, DeviceState, Keycode, MouseState};
+use std::error::Error;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::{thread, time};
+
+const POLL_INTERVAL: time::Duration = time::Duration::from_millis(100);
+/// How often the watcher compares the time since the last activity with the threshold.
+pub const CHECK_INTERVAL: time::Duration = time::Duration::from_secs(5);
+pub const INACTIVITY_THRESHOLD: time::Duration = time::Duration::from_secs(10);
+/// How long the input hook may stay silent before the system idle time is asked whether input really stopped.
+pub const HOOK_SILENCE: time::Duration = time::Duration::from_secs(300);
+
+/// Input that tells the watcher whether the user is active right now.
+pub trait ActivitySource {
+    fn is_active(&mut self) -> bool;
+
+    /// How long to wait between two `is_active` calls.
+    fn poll_interval(&self) -> time::Duration {
+        POLL_INTERVAL
+    }
+}
+
+/// Keyboard and mouse state read through `device_query`.
+pub struct DeviceActivity {
+    device_state: DeviceState,
+    /// Pointer position at the previous poll, to notice movement.
+    last_coords: Option<(i32, i32)>,
+}
+
+impl DeviceActivity {
+    pub fn new() -> Self {
+        Self {
+            device_state: DeviceState::new(),
+            last_coords: None,
+        }
+    }
+}
+
+impl Default for DeviceActivity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActivitySource for DeviceActivity {
+    fn is_active(&mut self) -> bool {
+        let mouse: MouseState = self.device_state.get_mouse();
+        let keys: Vec<Keycode> = self.device_state.get_keys();
+
+        input_seen(&mut self.last_coords, &mouse, &keys)
+    }
+}
+
+/// A pressed button or key, or a pointer that moved since the previous poll, whose position `last_coords` keeps.
+fn input_seen(last_coords: &mut Option<(i32, i32)>, mouse: &MouseState, keys: &[Keycode]) -> bool {
+    let moved = last_coords.replace(mouse.coords).is_some_and(|coords| coords != mouse.coords);
+
+    moved || mouse.button_pressed.iter().any(|pressed| *pressed) || !keys.is_empty()
+}
+
+/// Degraded macOS backend that needs no Accessibility permission: the time since the last input event, as kept
+/// by the HID system and listed by `ioreg`. It is read less often than device state, so pauses are less precise.
+pub struct IdleTimeActivity;
+
+impl IdleTimeActivity {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn idle_time() -> Option<time::Duration> {
+        let output = Command::new("ioreg")
+            .args(["-c", "IOHIDSystem", "-d", "4", "-k", "HIDIdleTime"])
+            .output()
+            .ok()?;
+
+        parse_hid_idle_time(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+impl Default for IdleTimeActivity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActivitySource for IdleTimeActivity {
+    fn is_active(&mut self) -> bool {
+        Self::idle_time().is_some_and(|idle_time| idle_time < self.poll_interval())
+    }
+
+    fn poll_interval(&self) -> time::Duration {
+        time::Duration::from_secs(1)
+    }
+}
+
+/// Reads `primary` until it has seen no input for `silence` while `fallback` still does, which means the input
+/// hook behind `primary` stopped working; from then on only `fallback` is read.
+pub struct FallbackActivity<P, F> {
+    primary: P,
+    fallback: F,
+    silence: time::Duration,
+    last_input: time::Instant,
+    last_probe: Option<time::Instant>,
+    switched: bool,
+}
+
+impl<P: ActivitySource, F: ActivitySource> FallbackActivity<P, F> {
+    pub fn new(primary: P, fallback: F, silence: time::Duration) -> Self {
+        Self {
+            primary,
+            fallback,
+            silence,
+            last_input: time::Instant::now(),
+            last_probe: None,
+            switched: false,
+        }
+    }
+}
+
+impl<P: ActivitySource, F: ActivitySource> ActivitySource for FallbackActivity<P, F> {
+    fn is_active(&mut self) -> bool {
+        if self.switched {
+            return self.fallback.is_active();
+        }
+        if self.primary.is_active() {
+            self.last_input = time::Instant::now();
+            return true;
+        }
+        // The fallback is slower to read, so it is only asked at its own pace once the primary went quiet.
+        let probe_due = self.last_probe.is_none_or(|last_probe| last_probe.elapsed() >= self.fallback.poll_interval());
+        if self.last_input.elapsed() < self.silence || !probe_due {
+            return false;
+        }
+        self.last_probe = Some(time::Instant::now());
+        if !self.fallback.is_active() {
+            return false;
+        }
+        self.switched = true;
+        println!("Keyboard and mouse input stopped arriving while the system still registers input, switching to the system idle time");
+
+        true
+    }
+
+    fn poll_interval(&self) -> time::Duration {
+        match self.switched {
+            true => self.fallback.poll_interval(),
+            false => self.primary.poll_interval(),
+        }
+    }
+}
+
+/// Reads the `"HIDIdleTime" = <nanoseconds>` line of `ioreg` output.
+pub fn parse_hid_idle_time(output: &str) -> Option<time::Duration> {
+    output
+        .lines()
+        .find_map(|line| line.split_once("\"HIDIdleTime\" = ")?.1.trim().parse::<u64>().ok())
+        .map(time::Duration::from_nanos)
+}
+
+/// Polls the source built by `make_source` on a background thread and stamps `last_active` on activity.
+/// The source is created inside the thread because some backends cannot be moved between threads.
+pub fn spawn<S, F>(make_source: F, last_active: Arc<Mutex<time::Instant>>)
+where
+    S: ActivitySource,
+    F: FnOnce() -> S + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut source = make_source();
+        loop {
+            if source.is_active() {
+                *last_active.lock().unwrap() = time::Instant::now();
+            }
+            thread::sleep(source.poll_interval());
+        }
+    });
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Transition {
+    PauseStarted,
+    ActivityResumed,
+}
+
+/// Turns the time since the last activity into pause start and end transitions.
+#[derive(Debug)]
+pub struct Monitor {
+    threshold: time::Duration,
+    idle: bool,
+}
+
+impl Monitor {
+    pub fn new(threshold: time::Duration) -> Self {
+        Self { threshold, idle: false }
+    }
+
+    pub fn update(&mut self, inactive_for: time::Duration) -> Option<Transition> {
+        match (self.idle, inactive_for >= self.threshold) {
+            (false, true) => {
+                self.idle = true;
+                Some(Transition::PauseStarted)
+            }
+            (true, false) => {
+                self.idle = false;
+                Some(Transition::ActivityResumed)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Runs a recorded activity timeline through the monitor, checking it every `CHECK_INTERVAL` as `kasl watch` does,
+/// and returns the detected pauses as (last activity before, first activity after) pairs.
+pub fn replay(activity: &[NaiveDateTime]) -> Result<Vec<(NaiveDateTime, NaiveDateTime)>, Box<dyn Error>> {
+    let mut activity = activity.to_vec();
+    activity.sort();
+    let (Some(first), Some(last)) = (activity.first().copied(), activity.last().copied()) else {
+        return Ok(vec![]);
+    };
+    let check_interval = Duration::from_std(CHECK_INTERVAL)?;
+    let mut monitor = Monitor::new(INACTIVITY_THRESHOLD);
+    let mut pauses = vec![];
+    let mut pause_start = first;
+    let mut next = 0;
+    let mut last_active = first;
+    let mut now = first;
+    while now <= last + check_interval {
+        while next < activity.len() && activity[next] <= now {
+            last_active = activity[next];
+            next += 1;
+        }
+        match monitor.update((now - last_active).to_std()?) {
+            Some(Transition::PauseStarted) => pause_start = last_active,
+            Some(Transition::ActivityResumed) => {
+                let pause_end = activity.iter().copied().find(|time| *time > pause_start).unwrap_or(last_active);
+                pauses.push((pause_start, pause_end));
+            }
+            None => {}
+        }
+        now += check_interval;
+    }
+
+    Ok(pauses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A source that always reports the same state.
+    struct FixedActivity(bool);
+
+    impl ActivitySource for FixedActivity {
+        fn is_active(&mut self) -> bool {
+            self.0
+        }
+    }
+
+    fn secs(seconds: u64) -> time::Duration {
+        time::Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn hid_idle_time_is_read_from_ioreg_output() {
+        let output = r#"+-o IOHIDSystem  <class IOHIDSystem, id 0x100000482, registered, matched, active, busy 0 (0 ms), retain 22>
+    {
+      "HIDIdleTime" = 2500000000
+      "HIDParameters" = {"HIDMouseAcceleration"=45056}
+    }"#;
+
+        assert_eq!(parse_hid_idle_time(output), Some(secs(2) + time::Duration::from_millis(500)));
+        assert_eq!(parse_hid_idle_time("ioreg: no such class"), None);
+    }
+
+    #[test]
+    fn input_is_a_press_or_a_pointer_move() {
+        let mouse = |coords, pressed| MouseState {
+            coords,
+            button_pressed: vec![false, pressed, false, false, false, false],
+        };
+        let mut last_coords = None;
+
+        assert!(!input_seen(&mut last_coords, &mouse((10, 10), false), &[]));
+        assert!(!input_seen(&mut last_coords, &mouse((10, 10), false), &[]));
+        assert!(input_seen(&mut last_coords, &mouse((12, 10), false), &[]));
+        assert!(input_seen(&mut last_coords, &mouse((12, 10), true), &[]));
+        assert!(input_seen(&mut last_coords, &mouse((12, 10), false), &[Keycode::A]));
+        assert!(!input_seen(&mut last_coords, &mouse((12, 10), false), &[]));
+    }
+
+    #[test]
+    fn fallback_takes_over_when_only_it_still_sees_input() {
+        let mut activity = FallbackActivity::new(FixedActivity(false), FixedActivity(true), time::Duration::ZERO);
+
+        assert!(activity.is_active());
+        assert!(activity.switched);
+        assert!(activity.is_active());
+    }
+
+    #[test]
+    fn fallback_is_not_used_while_the_primary_source_works_or_everything_is_idle() {
+        let mut working = FallbackActivity::new(FixedActivity(true), FixedActivity(true), time::Duration::ZERO);
+        let mut idle = FallbackActivity::new(FixedActivity(false), FixedActivity(false), time::Duration::ZERO);
+        let mut recent = FallbackActivity::new(FixedActivity(false), FixedActivity(true), secs(3600));
+
+        assert!(working.is_active() && !working.switched);
+        assert!(!idle.is_active() && !idle.switched);
+        assert!(!recent.is_active() && !recent.switched);
+    }
+
+    #[test]
+    fn monitor_reports_each_transition_once() {
+        let mut monitor = Monitor::new(secs(10));
+
+        assert_eq!(monitor.update(secs(5)), None);
+        assert_eq!(monitor.update(secs(10)), Some(Transition::PauseStarted));
+        assert_eq!(monitor.update(secs(60)), None);
+        assert_eq!(monitor.update(secs(0)), Some(Transition::ActivityResumed));
+        assert_eq!(monitor.update(secs(1)), None);
+    }
+
+    fn at(time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("2024-03-04 {}", time), "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    /// Activity every second from `from` for `seconds` seconds.
+    fn steady(from: &str, seconds: i64) -> Vec<NaiveDateTime> {
+        (0..seconds).map(|second| at(from) + Duration::seconds(second)).collect()
+    }
+
+    #[test]
+    fn replay_without_activity_finds_no_pauses() {
+        assert!(replay(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn replay_of_continuous_activity_finds_no_pauses() {
+        assert!(replay(&steady("09:00:00", 120)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn replay_finds_a_pause_between_the_surrounding_activity() {
+        let mut activity = steady("09:00:00", 30);
+        activity.extend(steady("09:05:00", 30));
+
+        assert_eq!(replay(&activity).unwrap(), vec![(at("09:00:29"), at("09:05:00"))]);
+    }
+
+    #[test]
+    fn replay_ignores_gaps_shorter_than_the_threshold_and_the_order_of_samples() {
+        let mut activity = steady("09:00:00", 10);
+        activity.extend(steady("09:00:15", 10));
+        activity.extend(steady("09:10:00", 10));
+        activity.reverse();
+
+        assert_eq!(replay(&activity).unwrap(), vec![(at("09:00:24"), at("09:10:00"))]);
+    }
+
+    #[test]
+    fn spawned_source_stamps_activity() {
+        let started = time::Instant::now();
+        let active = Arc::new(Mutex::new(started));
+        let idle = Arc::new(Mutex::new(started));
+        spawn(|| FixedActivity(true), active.clone());
+        spawn(|| FixedActivity(false), idle.clone());
+        thread::sleep(POLL_INTERVAL * 3);
+
+        assert!(*active.lock().unwrap() > started);
+        assert_eq!(*idle.lock().unwrap(), started);
+    }
+}
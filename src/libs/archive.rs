@@ -0,0 +1,90 @@
+use super::{
+    compression,
+    diag::REDACTED,
+    error::KaslError,
+    event::Event,
+    interactive,
+    journal::JournalEntry,
+    project::Project,
+    task::{Task, TaskNote},
+};
+use chrono::Local;
+use dialoguer::{theme::ColorfulTheme, Password};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+pub const ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Archive {
+    pub version: u32,
+    pub exported_at: String,
+    pub events: Vec<Event>,
+    pub tasks: Vec<Task>,
+    #[serde(default)]
+    pub task_notes: Vec<TaskNote>,
+    #[serde(default)]
+    pub journal: Vec<JournalEntry>,
+    #[serde(default)]
+    pub projects: Vec<Project>,
+}
+
+impl Archive {
+    pub fn new(events: Vec<Event>, tasks: Vec<Task>, task_notes: Vec<TaskNote>, journal: Vec<JournalEntry>, projects: Vec<Project>) -> Self {
+        Self {
+            version: ARCHIVE_VERSION,
+            exported_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            events,
+            tasks,
+            task_notes,
+            journal,
+            projects,
+        }
+    }
+
+    /// Hides task and project names behind pseudonyms and replaces note and journal texts, keeping all timings.
+    pub fn redact(&mut self) {
+        self.tasks = self.tasks.iter().map(Task::redacted).collect();
+        self.projects = self.projects.iter().map(Project::redacted).collect();
+        for note in self.task_notes.iter_mut() {
+            note.text = REDACTED.to_string();
+        }
+        for entry in self.journal.iter_mut() {
+            entry.text = REDACTED.to_string();
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let data = fs::read(path)?;
+        let archive_bytes = match compression::is_zip(&data) {
+            true => {
+                let password = match compression::is_encrypted_zip(&data) {
+                    true if interactive::is_disabled() => return Err(interactive::required("The archive password", "import without --yes")),
+                    true => Some(
+                        Password::with_theme(&ColorfulTheme::default())
+                            .with_prompt("Enter the password of the archive")
+                            .interact()?,
+                    ),
+                    false => None,
+                };
+                compression::unzip(&data, password.as_deref())?
+            }
+            false => compression::gunzip_if_compressed(data)?,
+        };
+        let archive: Archive = serde_json::from_slice(&archive_bytes)?;
+        if archive.version > ARCHIVE_VERSION {
+            return Err(KaslError::validation(format!(
+                "Unsupported archive version {}, expected {} or lower",
+                archive.version, ARCHIVE_VERSION
+            )));
+        }
+
+        Ok(archive)
+    }
+
+    pub fn to_json(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(serde_json::to_vec_pretty(&self)?)
+    }
+}
@@ -0,0 +1,10 @@
+use arboard::Clipboard;
+use std::error::Error;
+
+/// Places `text` into the system clipboard.
+pub fn copy(text: &str) -> Result<(), Box<dyn Error>> {
+    let mut clipboard = Clipboard::new()?;
+    clipboard.set_text(text)?;
+
+    Ok(())
+}
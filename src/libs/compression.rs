@@ -0,0 +1,396 @@
+use super::error::KaslError;
+use aes::{
+    cipher::{generic_array::GenericArray, BlockEncrypt, NewBlockCipher},
+    Aes256,
+};
+use chrono::{Datelike, Local, Timelike};
+use clap::ValueEnum;
+use flate2::{
+    read::{DeflateDecoder, GzDecoder},
+    write::{DeflateEncoder, GzEncoder},
+    Compression as Level, Crc,
+};
+use ring::{
+    hmac, pbkdf2,
+    rand::{SecureRandom, SystemRandom},
+};
+use std::{
+    error::Error,
+    io::{Read, Write},
+    num::NonZeroU32,
+};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const LOCAL_HEADER_SIGNATURE: u32 = 0x04034b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x02014b50;
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x06054b50;
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATE: u16 = 8;
+/// WinZip AES (AE-2): AES-256 in CTR mode with an HMAC-SHA1 tag, readable by 7-Zip, WinZip and macOS Archive Utility.
+const METHOD_AES: u16 = 99;
+const AES_EXTRA_FIELD: u16 = 0x9901;
+const AES_STRENGTH_256: u8 = 3;
+const AES_SALT_LEN: usize = 16;
+const AES_KEY_LEN: usize = 32;
+const AES_AUTH_CODE_LEN: usize = 10;
+const AES_KDF_ITERATIONS: u32 = 1000;
+const FLAG_ENCRYPTED: u16 = 0x0001;
+/// Sizes and CRC follow the data instead of the local header, as archivers that stream their output write them.
+const FLAG_DATA_DESCRIPTOR: u16 = 0x0008;
+const FLAG_UTF8_NAME: u16 = 0x0800;
+/// Fixed part of a local file header, from the signature to the extra field length.
+const LOCAL_HEADER_LEN: usize = 30;
+const CENTRAL_HEADER_LEN: usize = 46;
+const END_OF_CENTRAL_DIRECTORY_LEN: usize = 22;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Compression {
+    Zip,
+    Gz,
+}
+
+impl Compression {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Zip => "zip",
+            Self::Gz => "json.gz",
+        }
+    }
+}
+
+pub fn gzip(data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Level::default());
+    encoder.write_all(data)?;
+
+    Ok(encoder.finish()?)
+}
+
+/// Unpacks gzip data and passes anything else through, so readers accept both plain and compressed files.
+pub fn gunzip_if_compressed(data: Vec<u8>) -> Result<Vec<u8>, Box<dyn Error>> {
+    if !data.starts_with(&GZIP_MAGIC) {
+        return Ok(data);
+    }
+    let mut decompressed = Vec::new();
+    GzDecoder::new(data.as_slice()).read_to_end(&mut decompressed)?;
+
+    Ok(decompressed)
+}
+
+pub fn is_zip(data: &[u8]) -> bool {
+    data.starts_with(&LOCAL_HEADER_SIGNATURE.to_le_bytes())
+}
+
+pub fn is_encrypted_zip(data: &[u8]) -> bool {
+    is_zip(data) && data.len() >= LOCAL_HEADER_LEN && u16_at(data, 6) & FLAG_ENCRYPTED != 0
+}
+
+/// Contents of the first file of a zip archive, stored, deflated or WinZip AES encrypted as `zip` writes them.
+pub fn unzip(data: &[u8], password: Option<&str>) -> Result<Vec<u8>, Box<dyn Error>> {
+    if !is_zip(data) || data.len() < LOCAL_HEADER_LEN {
+        return Err("Not a zip archive".into());
+    }
+    let flags = u16_at(data, 6);
+    let mut method = u16_at(data, 8);
+    let (crc, body_len) = match flags & FLAG_DATA_DESCRIPTOR {
+        0 => (u32_at(data, 14), u32_at(data, 18) as usize),
+        _ => first_central_entry(data).ok_or("The zip archive has no central directory")?,
+    };
+    let name_len = u16_at(data, 26) as usize;
+    let extra_len = u16_at(data, 28) as usize;
+    let body_start = LOCAL_HEADER_LEN + name_len + extra_len;
+    let body = data.get(body_start..body_start + body_len).ok_or("The zip archive is truncated")?;
+    if flags & FLAG_ENCRYPTED != 0 && method != METHOD_AES {
+        return Err("The zip archive uses an unsupported encryption, only AES-256 is supported".into());
+    }
+
+    let body = match method {
+        METHOD_AES => {
+            let extra = &data[LOCAL_HEADER_LEN + name_len..body_start];
+            method = aes_method(extra).ok_or("The zip archive has no AES extra field")?;
+            decrypt(body, password.ok_or("The zip archive is encrypted and needs a password")?)?
+        }
+        _ => body.to_vec(),
+    };
+    let contents = match method {
+        METHOD_STORED => body,
+        METHOD_DEFLATE => {
+            let mut inflated = Vec::new();
+            DeflateDecoder::new(body.as_slice()).read_to_end(&mut inflated)?;
+            inflated
+        }
+        method => return Err(format!("Unsupported zip compression method {}", method).into()),
+    };
+    // AE-2 archives leave the CRC at zero and rely on the authentication code checked by `decrypt`.
+    if crc != 0 {
+        let mut actual = Crc::new();
+        actual.update(&contents);
+        if actual.sum() != crc {
+            return Err("The zip archive is corrupted, its checksum does not match".into());
+        }
+    }
+
+    Ok(contents)
+}
+
+/// CRC and compressed size of the first file as listed in the central directory at the end of the archive.
+fn first_central_entry(data: &[u8]) -> Option<(u32, usize)> {
+    let end = (0..=data.len().checked_sub(END_OF_CENTRAL_DIRECTORY_LEN)?)
+        .rev()
+        .find(|&offset| u32_at(data, offset) == END_OF_CENTRAL_DIRECTORY_SIGNATURE)?;
+    let central = u32_at(data, end + 16) as usize;
+    if central + CENTRAL_HEADER_LEN > data.len() || u32_at(data, central) != CENTRAL_HEADER_SIGNATURE {
+        return None;
+    }
+
+    Some((u32_at(data, central + 16), u32_at(data, central + 20) as usize))
+}
+
+/// The compression method stored in the WinZip AES extra field.
+fn aes_method(mut extra: &[u8]) -> Option<u16> {
+    while extra.len() >= 4 {
+        let (id, len) = (u16_at(extra, 0), u16_at(extra, 2) as usize);
+        let field = extra.get(4..4 + len)?;
+        if id == AES_EXTRA_FIELD && field.len() == 7 {
+            return Some(u16_at(field, 5));
+        }
+        extra = &extra[4 + len..];
+    }
+
+    None
+}
+
+fn u16_at(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+/// A zip archive holding the single file `file_name`, AES-256 encrypted when a password is given.
+pub fn zip(file_name: &str, data: &[u8], password: Option<&str>) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Level::default());
+    encoder.write_all(data)?;
+    let deflated = encoder.finish()?;
+
+    // AE-2 leaves the CRC empty; the authentication code protects the contents instead.
+    let (method, flags, crc, body, extra) = match password {
+        Some(password) => {
+            let extra = [
+                &AES_EXTRA_FIELD.to_le_bytes()[..],
+                &7u16.to_le_bytes(),
+                &2u16.to_le_bytes(),
+                b"AE",
+                &[AES_STRENGTH_256],
+                &METHOD_DEFLATE.to_le_bytes(),
+            ]
+            .concat();
+            (METHOD_AES, FLAG_ENCRYPTED | FLAG_UTF8_NAME, 0, encrypt(&deflated, password)?, extra)
+        }
+        None => {
+            let mut crc = Crc::new();
+            crc.update(data);
+            (METHOD_DEFLATE, FLAG_UTF8_NAME, crc.sum(), deflated, vec![])
+        }
+    };
+    let version: u16 = match password {
+        Some(_) => 51,
+        None => 20,
+    };
+    let (time, date) = dos_timestamp();
+    let name = file_name.as_bytes();
+    // Fields shared by the local and the central header, from "version needed" to "extra field length".
+    let common = [
+        &version.to_le_bytes()[..],
+        &flags.to_le_bytes(),
+        &method.to_le_bytes(),
+        &time.to_le_bytes(),
+        &date.to_le_bytes(),
+        &crc.to_le_bytes(),
+        &(body.len() as u32).to_le_bytes(),
+        &(data.len() as u32).to_le_bytes(),
+        &(name.len() as u16).to_le_bytes(),
+        &(extra.len() as u16).to_le_bytes(),
+    ]
+    .concat();
+
+    let mut zip = [&LOCAL_HEADER_SIGNATURE.to_le_bytes()[..], &common, name, &extra, &body].concat();
+    let central_offset = zip.len() as u32;
+    let central = [
+        &CENTRAL_HEADER_SIGNATURE.to_le_bytes()[..],
+        &version.to_le_bytes(),
+        &common,
+        &[0u8; 10], // comment length, disk number, internal and external attributes
+        &0u32.to_le_bytes(),
+        name,
+        &extra,
+    ]
+    .concat();
+    zip.extend_from_slice(&central);
+    zip.extend_from_slice(
+        &[
+            &END_OF_CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes()[..],
+            &[0u8; 4], // disk numbers
+            &1u16.to_le_bytes(),
+            &1u16.to_le_bytes(),
+            &(central.len() as u32).to_le_bytes(),
+            &central_offset.to_le_bytes(),
+            &0u16.to_le_bytes(),
+        ]
+        .concat(),
+    );
+
+    Ok(zip)
+}
+
+/// Salt, password verifier, the data encrypted with AES-256-CTR and the truncated HMAC-SHA1 of it, as WinZip AES lays them out.
+fn encrypt(data: &[u8], password: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut salt = [0u8; AES_SALT_LEN];
+    SystemRandom::new().fill(&mut salt).map_err(|_| "Failed to generate a random salt")?;
+    let (encryption_key, auth_key, verifier) = derive_keys(&salt, password);
+    let mut encrypted = data.to_vec();
+    apply_keystream(&encryption_key, &mut encrypted);
+    let tag = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, &auth_key), &encrypted);
+
+    Ok([&salt[..], &verifier, &encrypted, &tag.as_ref()[..AES_AUTH_CODE_LEN]].concat())
+}
+
+/// Reverses `encrypt`, checking the password verifier and the authentication code first.
+fn decrypt(body: &[u8], password: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    if body.len() < AES_SALT_LEN + 2 + AES_AUTH_CODE_LEN {
+        return Err("The zip archive is truncated".into());
+    }
+    let (salt, rest) = body.split_at(AES_SALT_LEN);
+    let (stored_verifier, rest) = rest.split_at(2);
+    let (encrypted, auth_code) = rest.split_at(rest.len() - AES_AUTH_CODE_LEN);
+    let (encryption_key, auth_key, verifier) = derive_keys(salt, password);
+    if verifier != stored_verifier {
+        return Err(KaslError::validation("Wrong password for the encrypted archive"));
+    }
+    let tag = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, &auth_key), encrypted);
+    if &tag.as_ref()[..AES_AUTH_CODE_LEN] != auth_code {
+        return Err("The encrypted archive is corrupted, its authentication code does not match".into());
+    }
+    let mut decrypted = encrypted.to_vec();
+    apply_keystream(&encryption_key, &mut decrypted);
+
+    Ok(decrypted)
+}
+
+/// Encryption key, authentication key and password verifier derived from `password`.
+fn derive_keys(salt: &[u8], password: &str) -> ([u8; AES_KEY_LEN], [u8; AES_KEY_LEN], [u8; 2]) {
+    let mut keys = [0u8; AES_KEY_LEN * 2 + 2];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA1,
+        NonZeroU32::new(AES_KDF_ITERATIONS).unwrap(),
+        salt,
+        password.as_bytes(),
+        &mut keys,
+    );
+    let mut encryption_key = [0u8; AES_KEY_LEN];
+    let mut auth_key = [0u8; AES_KEY_LEN];
+    encryption_key.copy_from_slice(&keys[..AES_KEY_LEN]);
+    auth_key.copy_from_slice(&keys[AES_KEY_LEN..AES_KEY_LEN * 2]);
+
+    (encryption_key, auth_key, [keys[AES_KEY_LEN * 2], keys[AES_KEY_LEN * 2 + 1]])
+}
+
+/// AES-256-CTR, which encrypts and decrypts alike.
+fn apply_keystream(key: &[u8], data: &mut [u8]) {
+    let cipher = Aes256::new(GenericArray::from_slice(key));
+    for (index, chunk) in data.chunks_mut(16).enumerate() {
+        // The counter starts at 1 and is stored little-endian.
+        let mut block = GenericArray::clone_from_slice(&((index as u128 + 1).to_le_bytes()));
+        cipher.encrypt_block(&mut block);
+        chunk.iter_mut().zip(block.iter()).for_each(|(byte, key)| *byte ^= key);
+    }
+}
+
+fn dos_timestamp() -> (u16, u16) {
+    let now = Local::now();
+    let time = (now.hour() << 11 | now.minute() << 5 | (now.second() / 2)) as u16;
+    let date = ((now.year().max(1980) - 1980) as u32) << 9 | now.month() << 5 | now.day();
+
+    (time, date as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &[u8] = br#"{"version":1,"events":[],"tasks":[]}"#;
+
+    #[test]
+    fn gzip_round_trip() {
+        let compressed = gzip(DATA).unwrap();
+
+        assert!(compressed.starts_with(&GZIP_MAGIC));
+        assert_eq!(gunzip_if_compressed(compressed).unwrap(), DATA);
+    }
+
+    #[test]
+    fn plain_data_passes_through_gunzip() {
+        assert_eq!(gunzip_if_compressed(DATA.to_vec()).unwrap(), DATA);
+    }
+
+    #[test]
+    fn zip_round_trip() {
+        let zip = zip("kasl.json", DATA, None).unwrap();
+
+        assert!(is_zip(&zip) && !is_encrypted_zip(&zip));
+        assert_eq!(unzip(&zip, None).unwrap(), DATA);
+    }
+
+    #[test]
+    fn encrypted_zip_round_trip() {
+        let zip = zip("kasl.json", DATA, Some("secret")).unwrap();
+
+        assert!(is_encrypted_zip(&zip));
+        assert_eq!(unzip(&zip, Some("secret")).unwrap(), DATA);
+    }
+
+    #[test]
+    fn encrypted_zip_rejects_a_wrong_or_missing_password() {
+        let zip = zip("kasl.json", DATA, Some("secret")).unwrap();
+
+        assert!(unzip(&zip, Some("guess")).is_err());
+        assert!(unzip(&zip, None).is_err());
+    }
+
+    #[test]
+    fn tampered_encrypted_zip_is_rejected() {
+        let mut zip = zip("kasl.json", DATA, Some("secret")).unwrap();
+        let body_start = LOCAL_HEADER_LEN + "kasl.json".len() + 11 + AES_SALT_LEN + 2;
+        zip[body_start] ^= 1;
+
+        assert!(unzip(&zip, Some("secret")).is_err());
+    }
+
+    // Fixtures written by Info-ZIP `zip` and libarchive `bsdtar --format zip`, the latter streaming with data
+    // descriptors and, for the encrypted one, `--options zip:encryption=aes256 --passphrase secret`.
+    const FIXTURE: &[u8] = include_bytes!("../../tests/fixtures/kasl.json");
+
+    #[test]
+    fn reads_a_deflated_zip_from_info_zip() {
+        let zip = include_bytes!("../../tests/fixtures/info-zip.zip");
+
+        assert!(!is_encrypted_zip(zip));
+        assert_eq!(unzip(zip, None).unwrap(), FIXTURE);
+    }
+
+    #[test]
+    fn reads_a_streamed_zip_from_libarchive() {
+        let zip = include_bytes!("../../tests/fixtures/libarchive.zip");
+
+        assert_eq!(unzip(zip, None).unwrap(), FIXTURE);
+    }
+
+    #[test]
+    fn reads_an_aes_256_zip_from_libarchive() {
+        let zip = include_bytes!("../../tests/fixtures/libarchive-aes256.zip");
+
+        assert!(is_encrypted_zip(zip));
+        assert_eq!(unzip(zip, Some("secret")).unwrap(), FIXTURE);
+        assert!(unzip(zip, Some("guess")).is_err());
+    }
+}
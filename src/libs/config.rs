@@ -1,7 +1,19 @@
+use super::contract::ContractConfig;
 use super::data_storage::DataStorage;
+use super::display::DisplayConfig;
+use super::error::KaslError;
+use super::productivity::BreakConfig;
+use super::rate::RateConfig;
+use super::report::ReportConfig;
+use super::retention::RetentionConfig;
+use super::rounding::RoundingConfig;
+use crate::api::endpoint::EndpointConfig;
 use crate::api::gitlab::GitLabConfig;
 use crate::api::jira::JiraConfig;
+use crate::api::notion::NotionConfig;
 use crate::api::si::SiConfig;
+use crate::api::trello::TrelloConfig;
+use crate::api::webdav::WebDavConfig;
 use dialoguer::{theme::ColorfulTheme, MultiSelect};
 use serde::{Deserialize, Serialize};
 use std::env;
@@ -22,25 +34,53 @@ pub struct ConfigModule {
 pub struct Config {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub si: Option<SiConfig>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub gitlab: Option<GitLabConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "GitLabConfig::deserialize_list")]
+    pub gitlab: Vec<GitLabConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub jira: Option<JiraConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notion: Option<NotionConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trello: Option<TrelloConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate: Option<RateConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report: Option<ReportConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retention: Option<RetentionConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rounding: Option<RoundingConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<EndpointConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webdav: Option<WebDavConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract: Option<ContractConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub breaks: Option<BreakConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<DisplayConfig>,
+    /// Logs every API request to `api.log`; see `api::http`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub api_debug: bool,
 }
 
 impl Config {
     pub fn read() -> Result<Config, Box<dyn Error>> {
         let config_file_path = DataStorage::new().get_path(CONFIG_FILE_NAME)?;
-        let config_str = fs::read_to_string(config_file_path)?;
-        let config: Config = serde_json::from_str(&config_str)?;
+        let config_str = fs::read_to_string(config_file_path).map_err(|e| KaslError::config(format!("Failed to read config: {}", e)))?;
+        let config: Config = serde_json::from_str(&config_str).map_err(|e| KaslError::config(format!("Invalid config: {}", e)))?;
 
         Ok(config)
     }
 
     pub fn save(&self) -> Result<(), Box<dyn Error>> {
         let config_file_path = DataStorage::new().get_path(CONFIG_FILE_NAME)?;
-        let config_file = File::create(config_file_path)?;
+        let tmp_file_path = config_file_path.with_extension("json.tmp");
+        let config_file = File::create(&tmp_file_path)?;
         serde_json::to_writer_pretty(&config_file, &self)?;
+        config_file.sync_all()?;
+        fs::rename(tmp_file_path, config_file_path)?;
 
         Ok(())
     }
@@ -50,11 +90,38 @@ impl Config {
             Ok(config) => config,
             Err(_) => Config {
                 si: None,
-                gitlab: None,
+                gitlab: vec![],
                 jira: None,
+                notion: None,
+                trello: None,
+                rate: None,
+                report: None,
+                retention: None,
+                rounding: None,
+                endpoint: None,
+                webdav: None,
+                contract: None,
+                breaks: None,
+                display: None,
+                api_debug: false,
             },
         };
-        let node_descriptions = vec![SiConfig::module(), GitLabConfig::module(), JiraConfig::module()];
+        let node_descriptions = vec![
+            SiConfig::module(),
+            GitLabConfig::module(),
+            JiraConfig::module(),
+            NotionConfig::module(),
+            TrelloConfig::module(),
+            RateConfig::module(),
+            ReportConfig::module(),
+            RetentionConfig::module(),
+            RoundingConfig::module(),
+            EndpointConfig::module(),
+            WebDavConfig::module(),
+            ContractConfig::module(),
+            BreakConfig::module(),
+            DisplayConfig::module(),
+        ];
         let selected_nodes = MultiSelect::with_theme(&ColorfulTheme::default())
             .with_prompt("Select nodes to configure")
             .items(&node_descriptions.iter().map(|module| &module.name).collect::<Vec<_>>())
@@ -65,11 +132,44 @@ impl Config {
                 config.si = Some(SiConfig::init(&config.si)?);
             }
             if GitLabConfig::module().key == node_descriptions[selection].key {
-                config.gitlab = Some(GitLabConfig::init(&config.gitlab)?);
+                config.gitlab = GitLabConfig::init_list(&config.gitlab)?;
             }
             if JiraConfig::module().key == node_descriptions[selection].key {
                 config.jira = Some(JiraConfig::init(&config.jira)?);
             }
+            if NotionConfig::module().key == node_descriptions[selection].key {
+                config.notion = Some(NotionConfig::init(&config.notion)?);
+            }
+            if TrelloConfig::module().key == node_descriptions[selection].key {
+                config.trello = Some(TrelloConfig::init(&config.trello)?);
+            }
+            if RateConfig::module().key == node_descriptions[selection].key {
+                config.rate = Some(RateConfig::init(&config.rate)?);
+            }
+            if ReportConfig::module().key == node_descriptions[selection].key {
+                config.report = Some(ReportConfig::init(&config.report)?);
+            }
+            if RetentionConfig::module().key == node_descriptions[selection].key {
+                config.retention = Some(RetentionConfig::init(&config.retention)?);
+            }
+            if RoundingConfig::module().key == node_descriptions[selection].key {
+                config.rounding = Some(RoundingConfig::init(&config.rounding)?);
+            }
+            if EndpointConfig::module().key == node_descriptions[selection].key {
+                config.endpoint = Some(EndpointConfig::init(&config.endpoint)?);
+            }
+            if WebDavConfig::module().key == node_descriptions[selection].key {
+                config.webdav = Some(WebDavConfig::init(&config.webdav)?);
+            }
+            if ContractConfig::module().key == node_descriptions[selection].key {
+                config.contract = Some(ContractConfig::init(&config.contract)?);
+            }
+            if BreakConfig::module().key == node_descriptions[selection].key {
+                config.breaks = Some(BreakConfig::init(&config.breaks)?);
+            }
+            if DisplayConfig::module().key == node_descriptions[selection].key {
+                config.display = Some(DisplayConfig::init(&config.display)?);
+            }
         }
 
         Ok(config)
@@ -0,0 +1,271 @@
+use super::{config::ConfigModule, error::KaslError, event::Event, summary::Period};
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use dialoguer::{theme::ColorfulTheme, Input};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+};
+
+/// Daily target used by the `kasl status` forecast when no contract is configured.
+pub const DEFAULT_DAILY_HOURS: f64 = 8.0;
+
+/// Hours owed per month and per day, and the core hours a workday has to cover.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ContractConfig {
+    pub monthly_hours: f64,
+    #[serde(default = "default_daily_hours")]
+    pub daily_hours: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub core_start: Option<NaiveTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub core_end: Option<NaiveTime>,
+}
+
+impl ContractConfig {
+    pub fn module() -> ConfigModule {
+        ConfigModule {
+            key: "contract".to_string(),
+            name: "Contract hours".to_string(),
+        }
+    }
+
+    pub fn init(config: &Option<Self>) -> Result<Self, Box<dyn Error>> {
+        let config = config.clone().unwrap_or(Self {
+            monthly_hours: 160.0,
+            daily_hours: DEFAULT_DAILY_HOURS,
+            core_start: None,
+            core_end: None,
+        });
+        let core_hours = match (config.core_start, config.core_end) {
+            (Some(start), Some(end)) => format!("{}-{}", start.format("%H:%M"), end.format("%H:%M")),
+            _ => "".to_string(),
+        };
+        println!("Contract settings");
+        let monthly_hours = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Enter the required working hours per month")
+            .default(config.monthly_hours)
+            .interact_text()?;
+        let daily_hours = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Enter the target working hours per day")
+            .default(config.daily_hours)
+            .interact_text()?;
+        let core_hours: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Enter the core hours as HH:MM-HH:MM (leave empty for none)")
+            .default(core_hours)
+            .allow_empty(true)
+            .interact_text()?;
+        let (core_start, core_end) = match core_hours.trim() {
+            "" => (None, None),
+            core_hours => {
+                let (start, end) = Self::parse_core_hours(core_hours)?;
+                (Some(start), Some(end))
+            }
+        };
+
+        Ok(Self {
+            monthly_hours,
+            daily_hours,
+            core_start,
+            core_end,
+        })
+    }
+
+    fn parse_core_hours(core_hours: &str) -> Result<(NaiveTime, NaiveTime), Box<dyn Error>> {
+        let invalid = || KaslError::validation(format!("Invalid core hours `{}`, use HH:MM-HH:MM", core_hours));
+        let (start, end) = core_hours.split_once('-').ok_or_else(invalid)?;
+        let start = NaiveTime::parse_from_str(start.trim(), "%H:%M").map_err(|_| invalid())?;
+        let end = NaiveTime::parse_from_str(end.trim(), "%H:%M").map_err(|_| invalid())?;
+        if start >= end {
+            return Err(invalid());
+        }
+
+        Ok((start, end))
+    }
+
+    pub fn target(&self) -> Duration {
+        hours(self.monthly_hours)
+    }
+
+    /// The configured daily target, or `DEFAULT_DAILY_HOURS` without a contract.
+    pub fn daily_target(config: Option<&Self>) -> Duration {
+        hours(config.map_or(DEFAULT_DAILY_HOURS, |config| config.daily_hours))
+    }
+}
+
+fn default_daily_hours() -> f64 {
+    DEFAULT_DAILY_HOURS
+}
+
+fn hours(hours: f64) -> Duration {
+    Duration::minutes((hours * 60.0).round() as i64)
+}
+
+/// Progress of a month against the contract hours.
+#[derive(Debug)]
+pub struct Compliance {
+    pub target: Duration,
+    pub worked: Duration,
+    /// Weekdays from `today` to the end of the month that are not rest days; today counts because it is not finished.
+    pub remaining_workdays: u32,
+    /// Finished weekdays that started after or ended before the core hours.
+    pub core_misses: Vec<NaiveDate>,
+}
+
+impl Compliance {
+    /// `worked` is the net working time of the finished days of `month`, rest days included.
+    pub fn new(
+        contract: &ContractConfig,
+        month: &Period,
+        today: NaiveDate,
+        worked: Duration,
+        days: &HashMap<NaiveDate, (Vec<Event>, Duration)>,
+        rest_dates: &HashSet<NaiveDate>,
+    ) -> Self {
+        let remaining_workdays = today
+            .max(month.first_day())
+            .iter_days()
+            .take_while(|date| *date <= month.last_day())
+            .filter(|date| is_weekday(*date) && !rest_dates.contains(date))
+            .count() as u32;
+        let mut core_misses: Vec<NaiveDate> = match (contract.core_start, contract.core_end) {
+            (Some(core_start), Some(core_end)) => days
+                .iter()
+                .filter(|(date, (events, _))| is_weekday(**date) && !events.is_empty())
+                .filter(|(_, (events, _))| {
+                    let first_start = events.iter().map(|event| event.start.time()).min();
+                    let last_end = events.iter().filter_map(|event| event.end).map(|end| end.time()).max();
+                    first_start.is_some_and(|start| start > core_start) || last_end.is_some_and(|end| end < core_end)
+                })
+                .map(|(date, _)| *date)
+                .collect(),
+            _ => vec![],
+        };
+        core_misses.sort();
+
+        Self {
+            target: contract.target(),
+            worked,
+            remaining_workdays,
+            core_misses,
+        }
+    }
+
+    pub fn fulfillment(&self) -> f64 {
+        match self.target > Duration::zero() {
+            true => self.worked.num_minutes() as f64 / self.target.num_minutes() as f64 * 100.0,
+            false => 100.0,
+        }
+    }
+
+    pub fn remaining(&self) -> Duration {
+        (self.target - self.worked).max(Duration::zero())
+    }
+
+    /// Time to work on each remaining workday to reach the target, if any workday is left.
+    pub fn required_average(&self) -> Option<Duration> {
+        (self.remaining_workdays > 0).then(|| self.remaining() / self.remaining_workdays as i32)
+    }
+}
+
+fn is_weekday(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn time(hour: u32, min: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, min, 0).unwrap()
+    }
+
+    fn contract(core_hours: Option<(NaiveTime, NaiveTime)>) -> ContractConfig {
+        ContractConfig {
+            monthly_hours: 160.0,
+            daily_hours: 8.0,
+            core_start: core_hours.map(|(start, _)| start),
+            core_end: core_hours.map(|(_, end)| end),
+        }
+    }
+
+    fn workday(day: u32, start: NaiveTime, end: Option<NaiveTime>) -> (NaiveDate, (Vec<Event>, Duration)) {
+        let event = Event {
+            id: day as i32,
+            start: date(day).and_time(start),
+            end: end.map(|end| date(day).and_time(end)),
+            duration: None,
+        };
+        (date(day), (vec![event], Duration::hours(8)))
+    }
+
+    fn compliance(contract: &ContractConfig, today: NaiveDate, worked: Duration, days: &[(NaiveDate, (Vec<Event>, Duration))]) -> Compliance {
+        let days: HashMap<_, _> = days.iter().cloned().collect();
+        let rest_dates = HashSet::from([date(29)]);
+
+        Compliance::new(contract, &Period::month(date(1)), today, worked, &days, &rest_dates)
+    }
+
+    #[test]
+    fn remaining_hours_are_spread_over_the_workdays_left() {
+        // March 2024 from Wednesday the 20th: 8 weekdays, minus the 29th as a rest day.
+        let compliance = compliance(&contract(None), date(20), Duration::hours(104), &[]);
+
+        assert_eq!(compliance.target, Duration::hours(160));
+        assert_eq!(compliance.fulfillment(), 65.0);
+        assert_eq!(compliance.remaining_workdays, 7);
+        assert_eq!(compliance.remaining(), Duration::hours(56));
+        assert_eq!(compliance.required_average(), Some(Duration::hours(8)));
+    }
+
+    #[test]
+    fn overtime_leaves_nothing_remaining() {
+        let compliance = compliance(&contract(None), date(31), Duration::hours(170), &[]);
+
+        assert!(compliance.fulfillment() > 100.0);
+        assert_eq!(compliance.remaining(), Duration::zero());
+        // March 31, 2024 is a Sunday.
+        assert_eq!(compliance.remaining_workdays, 0);
+        assert_eq!(compliance.required_average(), None);
+    }
+
+    #[test]
+    fn zero_hour_contract_is_always_fulfilled() {
+        let contract = ContractConfig {
+            monthly_hours: 0.0,
+            ..contract(None)
+        };
+
+        assert_eq!(compliance(&contract, date(20), Duration::zero(), &[]).fulfillment(), 100.0);
+    }
+
+    #[test]
+    fn weekdays_outside_the_core_hours_are_missed() {
+        let days = [
+            workday(4, time(9, 0), Some(time(17, 0))),
+            workday(5, time(10, 30), Some(time(18, 0))),
+            workday(6, time(8, 0), Some(time(15, 0))),
+            workday(7, time(9, 0), None),
+            // Saturday.
+            workday(9, time(12, 0), Some(time(14, 0))),
+        ];
+        let core_hours = Some((time(10, 0), time(16, 0)));
+
+        assert_eq!(
+            compliance(&contract(core_hours), date(20), Duration::hours(32), &days).core_misses,
+            vec![date(5), date(6)]
+        );
+        assert!(compliance(&contract(None), date(20), Duration::hours(32), &days).core_misses.is_empty());
+    }
+
+    #[test]
+    fn core_hours_must_be_an_ordered_range() {
+        assert_eq!(ContractConfig::parse_core_hours("09:30 - 15:00").unwrap(), (time(9, 30), time(15, 0)));
+        assert!(ContractConfig::parse_core_hours("15:00-09:30").is_err());
+        assert!(ContractConfig::parse_core_hours("9-15").is_err());
+    }
+}
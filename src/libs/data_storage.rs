@@ -1,18 +1,30 @@
 use serde::Deserialize;
-use std::env::consts::OS;
-use std::env::var;
+use std::env::{self, consts::OS, var};
 use std::error::Error;
 use std::path::{Path, PathBuf};
 use std::{fs, str};
 include!(concat!(env!("OUT_DIR"), "/app_metadata.rs"));
 
+pub const DATA_DIR_ENV: &str = "KASL_DATA_DIR";
+const PORTABLE_MARKER: &str = "kasl.portable";
+const PORTABLE_DATA_DIR: &str = "data";
+
 #[derive(Deserialize, Clone)]
 pub struct DataStorage {
     base_path: PathBuf,
 }
 
 impl DataStorage {
+    /// Resolves the data directory: `KASL_DATA_DIR` wins, then portable mode, then the platform default.
     pub fn new() -> Self {
+        if let Ok(data_dir) = var(DATA_DIR_ENV) {
+            return Self {
+                base_path: PathBuf::from(data_dir),
+            };
+        }
+        if let Some(portable_path) = Self::portable_path() {
+            return Self { base_path: portable_path };
+        }
         let base_path = match OS {
             "windows" => var("LOCALAPPDATA").unwrap_or_else(|_| ".".into()),
             "macos" => var("HOME").unwrap_or_else(|_| ".".into()) + "/Library/Application Support",
@@ -23,6 +35,16 @@ impl DataStorage {
         Self { base_path }
     }
 
+    /// Portable mode keeps all data next to the executable when a `kasl.portable` marker file lies beside it.
+    fn portable_path() -> Option<PathBuf> {
+        let current_exe_path = env::current_exe().ok()?;
+        let exe_dir = current_exe_path.parent()?;
+        match exe_dir.join(PORTABLE_MARKER).exists() {
+            true => Some(exe_dir.join(PORTABLE_DATA_DIR)),
+            false => None,
+        }
+    }
+
     pub fn get_path(&self, file_name: &str) -> Result<PathBuf, Box<dyn Error>> {
         if !self.base_path.exists() {
             fs::create_dir_all(&self.base_path)?;
@@ -30,3 +52,9 @@ impl DataStorage {
         Ok(self.base_path.join(file_name))
     }
 }
+
+impl Default for DataStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
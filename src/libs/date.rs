@@ -0,0 +1,128 @@
+use super::error::KaslError;
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveTime, Weekday};
+use std::error::Error;
+
+const SUPPORTED_FORMS: &str = "YYYY-MM-DD, today, yesterday, a weekday such as monday or last friday, or N days/weeks ago";
+
+/// Parses a date given on the command line relative to `today`.
+///
+/// A bare weekday is its latest occurrence up to today, `last <weekday>` its latest occurrence before today.
+/// Day-month orders such as `03/04/2024` are rejected as ambiguous rather than guessed.
+pub fn parse(input: &str, today: NaiveDate) -> Result<NaiveDate, Box<dyn Error>> {
+    let normalized = input.trim().to_lowercase();
+    let words: Vec<&str> = normalized.split_whitespace().collect();
+    if let Ok(date) = NaiveDate::parse_from_str(&normalized, "%Y-%m-%d") {
+        return Ok(date);
+    }
+    let date = match words.as_slice() {
+        ["today"] => Some(today),
+        ["yesterday"] => today.pred_opt(),
+        ["last", weekday] => weekday
+            .parse::<Weekday>()
+            .ok()
+            .map(|weekday| latest(weekday, today.pred_opt().unwrap_or(today))),
+        [weekday] if weekday.parse::<Weekday>().is_ok() => weekday.parse::<Weekday>().ok().map(|weekday| latest(weekday, today)),
+        [count, unit, "ago"] => {
+            let count: i64 = count
+                .parse()
+                .map_err(|_| KaslError::validation(format!("Invalid number `{}` in date `{}`", count, input.trim())))?;
+            let offset = match *unit {
+                "day" | "days" => Duration::try_days(count),
+                "week" | "weeks" => Duration::try_weeks(count),
+                _ => return Err(KaslError::validation(format!("Invalid date `{}`, use {}", input.trim(), SUPPORTED_FORMS))),
+            };
+            let date = offset.and_then(|offset| today.checked_sub_signed(offset));
+            Some(date.ok_or_else(|| KaslError::validation(format!("Date `{}` is out of range", input.trim())))?)
+        }
+        [numeric] if numeric.contains(['/', '.']) => {
+            return Err(KaslError::validation(format!(
+                "Ambiguous date `{}`, the order of day and month is unclear; use YYYY-MM-DD",
+                input.trim()
+            )))
+        }
+        _ => None,
+    };
+
+    Ok(date.ok_or_else(|| KaslError::validation(format!("Invalid date `{}`, use {}", input.trim(), SUPPORTED_FORMS)))?)
+}
+
+/// Value parser for clap date arguments, relative to the local date.
+pub fn parse_arg(input: &str) -> Result<NaiveDate, String> {
+    parse(input, Local::now().date_naive()).map_err(|e| e.to_string())
+}
+
+/// Value parser for clap time-of-day arguments such as `08:30`.
+pub fn parse_time_arg(input: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(input.trim(), "%H:%M").map_err(|_| format!("Invalid time `{}`, use HH:MM", input.trim()))
+}
+
+/// The latest `weekday` on or before `day`.
+fn latest(weekday: Weekday, day: NaiveDate) -> NaiveDate {
+    let days_back = (7 + day.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+
+    day - Duration::days(days_back as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A Wednesday.
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 8, 14).unwrap()
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 8, day).unwrap()
+    }
+
+    #[test]
+    fn iso_dates_and_keywords() {
+        assert_eq!(parse("2024-08-01", today()).unwrap(), date(1));
+        assert_eq!(parse(" Today ", today()).unwrap(), today());
+        assert_eq!(parse("yesterday", today()).unwrap(), date(13));
+    }
+
+    #[test]
+    fn weekdays() {
+        assert_eq!(parse("wednesday", today()).unwrap(), today());
+        assert_eq!(parse("last wednesday", today()).unwrap(), date(7));
+        assert_eq!(parse("monday", today()).unwrap(), date(12));
+        assert_eq!(parse("last fri", today()).unwrap(), date(9));
+    }
+
+    #[test]
+    fn days_and_weeks_ago() {
+        assert_eq!(parse("1 day ago", today()).unwrap(), date(13));
+        assert_eq!(parse("3 days ago", today()).unwrap(), date(11));
+        assert_eq!(parse("2 weeks ago", today()).unwrap(), NaiveDate::from_ymd_opt(2024, 7, 31).unwrap());
+    }
+
+    #[test]
+    fn relative_dates_count_calendar_days_across_dst_switches() {
+        let after_spring_forward = NaiveDate::from_ymd_opt(2024, 4, 1).unwrap();
+        let after_fall_back = NaiveDate::from_ymd_opt(2024, 10, 28).unwrap();
+
+        assert_eq!(parse("1 day ago", after_spring_forward).unwrap(), NaiveDate::from_ymd_opt(2024, 3, 31).unwrap());
+        assert_eq!(
+            parse("2 days ago", after_spring_forward).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 30).unwrap()
+        );
+        assert_eq!(parse("1 week ago", after_fall_back).unwrap(), NaiveDate::from_ymd_opt(2024, 10, 21).unwrap());
+        assert_eq!(parse("last sunday", after_fall_back).unwrap(), NaiveDate::from_ymd_opt(2024, 10, 27).unwrap());
+    }
+
+    #[test]
+    fn huge_offsets_are_rejected_instead_of_panicking() {
+        assert!(parse("9223372036854775807 days ago", today()).is_err());
+        assert!(parse("100000000 weeks ago", today()).is_err());
+        assert!(parse("99999999999999999999 days ago", today()).is_err());
+    }
+
+    #[test]
+    fn ambiguous_and_unknown_forms_are_rejected() {
+        assert!(parse("03/04/2024", today()).is_err());
+        assert!(parse("3 months ago", today()).is_err());
+        assert!(parse("someday", today()).is_err());
+    }
+}
@@ -0,0 +1,51 @@
+use super::{config::Config, error::KaslError};
+use crate::api::webdav::WebDav;
+use clap::ValueEnum;
+use std::{error::Error, fs, path::PathBuf};
+
+/// Where `kasl export` writes its file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DestinationKind {
+    Local,
+    Webdav,
+}
+
+pub enum Destination {
+    Local(PathBuf),
+    WebDav(WebDav, String),
+}
+
+impl Destination {
+    /// Resolves `kind` against the configuration; remote destinations use only the file name of `path`.
+    pub fn new(kind: DestinationKind, path: PathBuf) -> Result<Self, Box<dyn Error>> {
+        let file_name = || path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        Ok(match kind {
+            DestinationKind::Local => Self::Local(path),
+            DestinationKind::Webdav => {
+                let config = Config::read()?
+                    .webdav
+                    .ok_or_else(|| KaslError::config("WebDAV is not configured, set it up with `kasl init`"))?;
+                Self::WebDav(WebDav::new(&config), file_name())
+            }
+        })
+    }
+
+    /// Human-readable target, used in dry-run and progress messages.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Local(path) => path.display().to_string(),
+            Self::WebDav(_, file_name) => format!("WebDAV ({})", file_name),
+        }
+    }
+
+    /// Writes `contents` and returns where they ended up.
+    pub async fn write(&self, contents: Vec<u8>) -> Result<String, Box<dyn Error>> {
+        match self {
+            Self::Local(path) => {
+                fs::write(path, contents)?;
+                Ok(path.display().to_string())
+            }
+            Self::WebDav(webdav, file_name) => webdav.upload(file_name, contents).await,
+        }
+    }
+}
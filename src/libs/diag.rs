@@ -0,0 +1,176 @@
+use super::data_storage::DataStorage;
+use chrono::NaiveDateTime;
+use serde_json::Value;
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const ACTIVITY_LOG_FILE: &str = "activity.log";
+/// The activity log keeps only this many of the latest entries.
+const MAX_ACTIVITY_RECORDS: usize = 10_000;
+pub const REDACTED: &str = "<redacted>";
+
+/// Records activity timestamps for bug reports; key codes and mouse positions are never written.
+///
+/// The log is rotated: once the current file holds half of the capacity it replaces the previous one
+/// (`activity.log.1`) and a new file is started, so both together never exceed the capacity.
+pub struct ActivityLog {
+    path: PathBuf,
+    capacity: usize,
+    count: usize,
+    last: Option<NaiveDateTime>,
+}
+
+impl ActivityLog {
+    pub fn open() -> Result<Self, Box<dyn Error>> {
+        Self::open_at(DataStorage::new().get_path(ACTIVITY_LOG_FILE)?, MAX_ACTIVITY_RECORDS)
+    }
+
+    fn open_at(path: PathBuf, capacity: usize) -> Result<Self, Box<dyn Error>> {
+        let count = Self::read_file(&path)?.len();
+        let mut log = Self {
+            path,
+            capacity,
+            count,
+            last: None,
+        };
+        if log.count >= log.file_capacity() {
+            log.rotate()?;
+        }
+
+        Ok(log)
+    }
+
+    pub fn record(&mut self, time: NaiveDateTime) -> Result<(), Box<dyn Error>> {
+        if self.last == Some(time) {
+            return Ok(());
+        }
+        if self.count >= self.file_capacity() {
+            self.rotate()?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", time.format("%Y-%m-%dT%H:%M:%S"))?;
+        self.count += 1;
+        self.last = Some(time);
+
+        Ok(())
+    }
+
+    fn file_capacity(&self) -> usize {
+        (self.capacity / 2).max(1)
+    }
+
+    fn rotate(&mut self) -> Result<(), Box<dyn Error>> {
+        fs::rename(&self.path, Self::previous_path(&self.path))?;
+        self.count = 0;
+
+        Ok(())
+    }
+
+    fn previous_path(path: &Path) -> PathBuf {
+        let mut previous = path.as_os_str().to_owned();
+        previous.push(".1");
+
+        PathBuf::from(previous)
+    }
+
+    /// The latest recorded entries, oldest first.
+    pub fn read() -> Result<Vec<NaiveDateTime>, Box<dyn Error>> {
+        Self::read_at(&DataStorage::new().get_path(ACTIVITY_LOG_FILE)?, MAX_ACTIVITY_RECORDS)
+    }
+
+    fn read_at(path: &Path, capacity: usize) -> Result<Vec<NaiveDateTime>, Box<dyn Error>> {
+        let mut records = Self::read_file(&Self::previous_path(path))?;
+        records.extend(Self::read_file(path)?);
+
+        Ok(records.split_off(records.len().saturating_sub(capacity)))
+    }
+
+    fn read_file(path: &Path) -> Result<Vec<NaiveDateTime>, Box<dyn Error>> {
+        if !path.exists() {
+            return Ok(vec![]);
+        }
+
+        Ok(fs::read_to_string(path)?
+            .lines()
+            .filter_map(|line| NaiveDateTime::parse_from_str(line, "%Y-%m-%dT%H:%M:%S").ok())
+            .collect())
+    }
+}
+
+/// Replaces every string in the configuration with a placeholder, keeping its structure, numbers and flags.
+pub fn redact(value: &mut Value) {
+    match value {
+        Value::String(text) if !text.is_empty() => *text = REDACTED.to_string(),
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        Value::Object(fields) => fields.values_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+/// Stable placeholder for a name, e.g. `Task 1f8b3c2a`: equal names map to equal placeholders, so records stay
+/// distinguishable without revealing them. Uses 32-bit FNV-1a, which is not meant to resist guessing of short names.
+pub fn pseudonym(prefix: &str, text: &str) -> String {
+    let hash = text.bytes().fold(0x811c9dc5u32, |hash, byte| (hash ^ byte as u32).wrapping_mul(0x01000193));
+
+    format!("{} {:08x}", prefix, hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn log_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("kasl-activity-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        dir.join(ACTIVITY_LOG_FILE)
+    }
+
+    fn times(count: i64) -> Vec<NaiveDateTime> {
+        let start = NaiveDateTime::parse_from_str("2024-03-04 09:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        (0..count).map(|second| start + Duration::seconds(second)).collect()
+    }
+
+    #[test]
+    fn log_never_holds_more_than_its_capacity() {
+        let path = log_path("capacity");
+        let mut log = ActivityLog::open_at(path.clone(), 10).unwrap();
+        let recorded = times(23);
+        for time in recorded.iter() {
+            log.record(*time).unwrap();
+        }
+        let stored = ActivityLog::read_file(&path).unwrap().len() + ActivityLog::read_file(&ActivityLog::previous_path(&path)).unwrap().len();
+
+        assert!((5..=10).contains(&stored));
+        assert_eq!(ActivityLog::read_at(&path, 10).unwrap(), recorded[23 - stored..]);
+    }
+
+    #[test]
+    fn repeated_timestamps_are_recorded_once() {
+        let path = log_path("repeated");
+        let mut log = ActivityLog::open_at(path.clone(), 10).unwrap();
+        let time = times(1)[0];
+        log.record(time).unwrap();
+        log.record(time).unwrap();
+
+        assert_eq!(ActivityLog::read_at(&path, 10).unwrap(), vec![time]);
+    }
+
+    #[test]
+    fn reopening_a_full_log_rotates_it() {
+        let path = log_path("reopen");
+        let recorded = times(8);
+        let lines: Vec<String> = recorded.iter().map(|time| time.format("%Y-%m-%dT%H:%M:%S").to_string()).collect();
+        fs::write(&path, lines.join("\n") + "\n").unwrap();
+        let mut log = ActivityLog::open_at(path.clone(), 10).unwrap();
+        let next = recorded[7] + Duration::seconds(1);
+        log.record(next).unwrap();
+
+        assert_eq!(ActivityLog::read_file(&path).unwrap(), vec![next]);
+        assert_eq!(ActivityLog::read_at(&path, 10).unwrap().len(), 9);
+    }
+}
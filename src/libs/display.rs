@@ -0,0 +1,117 @@
+use super::config::ConfigModule;
+use dialoguer::{theme::ColorfulTheme, Confirm, Select};
+use serde::{Deserialize, Serialize};
+use std::{env, error::Error};
+
+/// Colors of the state icon in color-aware status bar formats.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorTheme {
+    /// Green while working, yellow while away.
+    #[default]
+    Default,
+    /// Blue while working, orange while away, distinguishable with red-green color blindness.
+    Colorblind,
+    /// No colors at all.
+    None,
+}
+
+/// How kasl draws state icons and colors, for terminals and logs that mangle symbols or escape codes.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct DisplayConfig {
+    /// Plain ASCII icons instead of the ●/◐/○ symbols.
+    #[serde(default)]
+    pub ascii: bool,
+    #[serde(default)]
+    pub theme: ColorTheme,
+}
+
+impl DisplayConfig {
+    pub fn module() -> ConfigModule {
+        ConfigModule {
+            key: "display".to_string(),
+            name: "Display".to_string(),
+        }
+    }
+
+    pub fn init(config: &Option<Self>) -> Result<Self, Box<dyn Error>> {
+        let config = config.clone().unwrap_or_default();
+        let themes = [ColorTheme::Default, ColorTheme::Colorblind, ColorTheme::None];
+        println!("Display settings");
+        let ascii = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt("Use plain ASCII icons instead of symbols?")
+            .default(config.ascii)
+            .interact()?;
+        let theme = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select the color theme")
+            .items(&["Default (green/yellow)", "Colorblind (blue/orange)", "No colors"])
+            .default(themes.iter().position(|theme| *theme == config.theme).unwrap_or(0))
+            .interact()?;
+
+        Ok(Self { ascii, theme: themes[theme] })
+    }
+
+    /// The icon of a workday state: working, away or not started.
+    pub fn icon(&self, active: bool, started: bool) -> &'static str {
+        match (self.ascii, active, started) {
+            (false, true, _) => "●",
+            (false, false, true) => "◐",
+            (false, false, false) => "○",
+            (true, true, _) => "+",
+            (true, false, true) => "~",
+            (true, false, false) => "-",
+        }
+    }
+
+    /// The color name of a workday state for tmux, or none when colors are off by theme or `NO_COLOR`.
+    pub fn color(&self, active: bool) -> Option<&'static str> {
+        if env::var_os("NO_COLOR").is_some_and(|no_color| !no_color.is_empty()) {
+            return None;
+        }
+        match (self.theme, active) {
+            (ColorTheme::Default, true) => Some("green"),
+            (ColorTheme::Default, false) => Some("yellow"),
+            (ColorTheme::Colorblind, true) => Some("colour33"),
+            (ColorTheme::Colorblind, false) => Some("colour208"),
+            (ColorTheme::None, _) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbols_by_default() {
+        let display = DisplayConfig::default();
+
+        assert_eq!(
+            [display.icon(true, true), display.icon(false, true), display.icon(false, false)],
+            ["●", "◐", "○"]
+        );
+    }
+
+    #[test]
+    fn ascii_icons() {
+        let display = DisplayConfig {
+            ascii: true,
+            ..DisplayConfig::default()
+        };
+
+        assert_eq!(
+            [display.icon(true, true), display.icon(false, true), display.icon(false, false)],
+            ["+", "~", "-"]
+        );
+    }
+
+    #[test]
+    fn monochrome_theme_has_no_colors() {
+        let display = DisplayConfig {
+            theme: ColorTheme::None,
+            ..DisplayConfig::default()
+        };
+
+        assert_eq!((display.color(true), display.color(false)), (None, None));
+    }
+}
@@ -0,0 +1,22 @@
+use super::error::KaslError;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+pub fn enable() {
+    DRY_RUN.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+/// Prints what a mutating command would have done instead of doing it.
+pub fn print(message: &str) {
+    println!("[dry run] {}", message);
+}
+
+/// Rejects a dry run of a command whose effects cannot be previewed.
+pub fn unsupported(command: &str) -> Box<KaslError> {
+    KaslError::validation(format!("`{}` does not support --dry-run", command))
+}
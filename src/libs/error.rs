@@ -0,0 +1,129 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Config,
+    Database,
+    Network,
+    Validation,
+}
+
+impl ErrorKind {
+    /// Process exit code for the failure class; 1 stays the generic failure and 2 is used by clap for usage errors.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Config => 3,
+            Self::Database => 4,
+            Self::Network => 5,
+            Self::Validation => 6,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct KaslError {
+    pub kind: ErrorKind,
+    message: String,
+}
+
+impl KaslError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Box<Self> {
+        Box::new(Self { kind, message: message.into() })
+    }
+
+    pub fn config(message: impl Into<String>) -> Box<Self> {
+        Self::new(ErrorKind::Config, message)
+    }
+
+    pub fn database(message: impl Into<String>) -> Box<Self> {
+        Self::new(ErrorKind::Database, message)
+    }
+
+    pub fn network(message: impl Into<String>) -> Box<Self> {
+        Self::new(ErrorKind::Network, message)
+    }
+
+    pub fn validation(message: impl Into<String>) -> Box<Self> {
+        Self::new(ErrorKind::Validation, message)
+    }
+}
+
+impl fmt::Display for KaslError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for KaslError {}
+
+/// Classifies an error by walking its source chain; library errors are mapped to the matching kind.
+pub fn error_kind(error: &(dyn Error + 'static)) -> Option<ErrorKind> {
+    let mut current: Option<&(dyn Error + 'static)> = Some(error);
+    while let Some(error) = current {
+        // `?` on a `Box<KaslError>` boxes it once more, so both shapes end up in the chain.
+        if let Some(kasl_error) = error.downcast_ref::<KaslError>() {
+            return Some(kasl_error.kind);
+        }
+        if let Some(kasl_error) = error.downcast_ref::<Box<KaslError>>() {
+            return Some(kasl_error.kind);
+        }
+        if error.is::<rusqlite::Error>() {
+            return Some(ErrorKind::Database);
+        }
+        if error.is::<reqwest::Error>() {
+            return Some(ErrorKind::Network);
+        }
+        current = error.source();
+    }
+
+    None
+}
+
+pub fn exit_code(error: &(dyn Error + 'static)) -> i32 {
+    error_kind(error).map_or(1, |kind| kind.exit_code())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KINDS: [ErrorKind; 4] = [ErrorKind::Config, ErrorKind::Database, ErrorKind::Network, ErrorKind::Validation];
+
+    fn returned(kind: ErrorKind) -> Result<(), Box<dyn Error>> {
+        Err(KaslError::new(kind, "failed"))
+    }
+
+    fn propagated(kind: ErrorKind) -> Result<(), Box<dyn Error>> {
+        Err(KaslError::new(kind, "failed"))?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn exit_code_of_returned_errors() {
+        for kind in KINDS {
+            assert_eq!(exit_code(returned(kind).unwrap_err().as_ref()), kind.exit_code());
+        }
+    }
+
+    #[test]
+    fn exit_code_of_errors_propagated_with_question_mark() {
+        for kind in KINDS {
+            assert_eq!(exit_code(propagated(kind).unwrap_err().as_ref()), kind.exit_code());
+        }
+    }
+
+    #[test]
+    fn exit_codes_are_distinct() {
+        assert_eq!(KINDS.map(|kind| kind.exit_code()), [3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn library_errors_are_classified() {
+        let error: Box<dyn Error> = rusqlite::Error::QueryReturnedNoRows.into();
+        assert_eq!(exit_code(error.as_ref()), 4);
+        let error: Box<dyn Error> = "plain".into();
+        assert_eq!(exit_code(error.as_ref()), 1);
+    }
+}
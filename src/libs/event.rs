@@ -1,8 +1,9 @@
 use chrono::{
     prelude::{Local, NaiveDateTime},
-    Datelike, Duration, NaiveDate,
+    Datelike, Duration, LocalResult, NaiveDate, TimeZone,
 };
 use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, HashSet},
     fmt,
@@ -23,19 +24,34 @@ impl fmt::Display for EventType {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Event {
     pub id: i32,
     pub start: NaiveDateTime,
     pub end: Option<NaiveDateTime>,
+    #[serde(skip)]
     pub duration: Option<Duration>,
 }
 
+/// Time that really passed between two local timestamps of `tz`, so an interval across a DST switch counts the
+/// hour the clocks skipped or repeated. A time inside the skipped hour is read as one hour later, as clocks show
+/// it after the switch; a time in the repeated hour is taken as its first occurrence.
+pub fn elapsed<Tz: TimeZone>(tz: &Tz, start: NaiveDateTime, end: NaiveDateTime) -> Duration {
+    let to_utc = |local: NaiveDateTime| match tz.from_local_datetime(&local) {
+        LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => Some(time.naive_utc()),
+        LocalResult::None => tz.from_local_datetime(&(local + Duration::hours(1))).earliest().map(|time| time.naive_utc()),
+    };
+    match (to_utc(start), to_utc(end)) {
+        (Some(start), Some(end)) => end - start,
+        _ => end - start,
+    }
+}
+
 impl Event {
     fn with_calculated_duration(&self) -> Self {
         match self.end {
             Some(end) => Self {
-                duration: Some(end.signed_duration_since(self.start)),
+                duration: Some(elapsed(&Local, self.start, end)),
                 ..*self
             },
             None => Self { ..*self },
@@ -136,10 +152,13 @@ impl EventGroupDuration for HashMap<NaiveDate, Vec<Event>> {
     }
 }
 
+/// Formatted events and working time per day, with the formatted total and daily average.
+pub type FormatDurations = (HashMap<NaiveDate, (Vec<FormatEvent>, String)>, String, String);
+
 pub trait EventGroupTotalDuration {
     fn add_rest_dates(&mut self, rest_dates: HashSet<NaiveDate>, duration: Duration) -> (HashMap<NaiveDate, (Vec<Event>, Duration)>, Duration);
     fn total_duration(&mut self) -> (HashMap<NaiveDate, (Vec<Event>, Duration)>, Duration);
-    fn format(&mut self) -> (HashMap<NaiveDate, (Vec<FormatEvent>, String)>, String, String);
+    fn format(&mut self) -> FormatDurations;
 }
 
 impl EventGroupTotalDuration for (HashMap<NaiveDate, (Vec<Event>, Duration)>, Duration) {
@@ -169,7 +188,7 @@ impl EventGroupTotalDuration for (HashMap<NaiveDate, (Vec<Event>, Duration)>, Du
         (self.0.clone(), total_duration)
     }
 
-    fn format(&mut self) -> (HashMap<NaiveDate, (Vec<FormatEvent>, String)>, String, String) {
+    fn format(&mut self) -> FormatDurations {
         let mut event_group: HashMap<NaiveDate, (Vec<FormatEvent>, String)> = HashMap::new();
         for (date, events) in self.0.iter() {
             event_group.insert(*date, events.clone().format());
@@ -195,6 +214,12 @@ pub struct FormatEvent {
 }
 
 impl FormatEvent {
+    /// Signed difference, e.g. `+02:30` or `-00:45`.
+    pub fn format_delta(delta: Duration) -> String {
+        let sign = if delta < Duration::zero() { "-" } else { "+" };
+        format!("{}{}", sign, Self::format_duration(Some(delta.abs())))
+    }
+
     pub fn format_duration(duration_opt: Option<Duration>) -> String {
         duration_opt.map_or_else(
             || "--:--".to_string(),
@@ -226,3 +251,94 @@ impl FormatEvents for (Vec<Event>, Duration) {
         (events, FormatEvent::format_duration(Some(self.1)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, NaiveTime};
+
+    /// Central European time in 2024: clocks go from 02:00 to 03:00 on March 31 and back from 03:00 to 02:00 on October 27.
+    #[derive(Clone, Copy)]
+    struct Cet;
+
+    fn winter() -> FixedOffset {
+        FixedOffset::east_opt(3600).unwrap()
+    }
+
+    fn summer() -> FixedOffset {
+        FixedOffset::east_opt(7200).unwrap()
+    }
+
+    fn at(date: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    impl TimeZone for Cet {
+        type Offset = FixedOffset;
+
+        fn from_offset(_: &FixedOffset) -> Self {
+            Cet
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            match *local {
+                local if local < at("2024-03-31 02:00") => LocalResult::Single(winter()),
+                local if local < at("2024-03-31 03:00") => LocalResult::None,
+                local if local < at("2024-10-27 02:00") => LocalResult::Single(summer()),
+                local if local < at("2024-10-27 03:00") => LocalResult::Ambiguous(summer(), winter()),
+                _ => LocalResult::Single(winter()),
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            self.offset_from_utc_datetime(&utc.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            match *utc >= at("2024-03-31 01:00") && *utc < at("2024-10-27 01:00") {
+                true => summer(),
+                false => winter(),
+            }
+        }
+    }
+
+    #[test]
+    fn ordinary_day_counts_the_clock_difference() {
+        assert_eq!(elapsed(&Cet, at("2024-03-04 09:00"), at("2024-03-04 17:30")), Duration::minutes(510));
+    }
+
+    #[test]
+    fn spring_forward_night_is_an_hour_shorter() {
+        assert_eq!(elapsed(&Cet, at("2024-03-30 22:00"), at("2024-03-31 06:00")), Duration::hours(7));
+        assert_eq!(elapsed(&Cet, at("2024-03-31 01:30"), at("2024-03-31 03:30")), Duration::hours(1));
+    }
+
+    #[test]
+    fn time_in_the_skipped_hour_is_read_after_the_switch() {
+        assert_eq!(elapsed(&Cet, at("2024-03-31 02:30"), at("2024-03-31 04:00")), Duration::minutes(30));
+    }
+
+    #[test]
+    fn fall_back_night_is_an_hour_longer() {
+        assert_eq!(elapsed(&Cet, at("2024-10-26 22:00"), at("2024-10-27 06:00")), Duration::hours(9));
+        assert_eq!(elapsed(&Cet, at("2024-10-27 01:30"), at("2024-10-27 03:30")), Duration::hours(3));
+    }
+
+    #[test]
+    fn intervals_are_grouped_by_their_start_day_on_switch_days() {
+        let event = |start: &str, end: &str| Event {
+            id: 0,
+            start: at(start),
+            end: Some(at(end)),
+            duration: None,
+        };
+        let days = vec![event("2024-03-30 20:00", "2024-03-30 23:00"), event("2024-03-31 09:00", "2024-03-31 17:00")].group_events();
+
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[&NaiveDate::from_ymd_opt(2024, 3, 31).unwrap()].len(), 1);
+    }
+}
@@ -0,0 +1,107 @@
+use chrono::{Duration, NaiveDateTime};
+use std::fmt;
+use std::time;
+
+/// How often `kasl watch` refreshes its heartbeat.
+pub const HEARTBEAT_INTERVAL: time::Duration = time::Duration::from_secs(60);
+/// Heartbeats older than this mean the watcher died without shutting down.
+pub const HEARTBEAT_TIMEOUT_MINUTES: i64 = 3;
+/// A live watcher that has seen no input this long during an open workday has likely lost its input hook,
+/// which would make the day look pause-free.
+pub const INPUT_SILENCE_MINUTES: i64 = 120;
+
+#[derive(Debug, Clone)]
+pub struct Heartbeat {
+    pub pid: u32,
+    pub beat_at: NaiveDateTime,
+    pub last_active_at: NaiveDateTime,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Alert {
+    /// The watcher stopped beating at the given time.
+    WatcherSilent(NaiveDateTime),
+    /// The watcher is alive but saw its last input at the given time.
+    InputSilent(NaiveDateTime),
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::WatcherSilent(beat_at) => write!(f, "kasl watch has been silent since {}, it may have crashed", beat_at.format("%Y-%m-%d %H:%M")),
+            Self::InputSilent(last_active_at) => write!(
+                f,
+                "kasl watch has seen no input since {}, the input hook may have stopped working",
+                last_active_at.format("%H:%M")
+            ),
+        }
+    }
+}
+
+/// Checks the stored heartbeat at `now`; input silence only matters while the workday is open.
+pub fn check(heartbeat: Option<&Heartbeat>, now: NaiveDateTime, workday_open: bool) -> Option<Alert> {
+    let heartbeat = heartbeat?;
+    if now - heartbeat.beat_at > Duration::minutes(HEARTBEAT_TIMEOUT_MINUTES) {
+        return Some(Alert::WatcherSilent(heartbeat.beat_at));
+    }
+    (workday_open && now - heartbeat.last_active_at > Duration::minutes(INPUT_SILENCE_MINUTES)).then_some(Alert::InputSilent(heartbeat.last_active_at))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("2024-03-04 {}", time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn heartbeat(beat_at: &str, last_active_at: &str) -> Heartbeat {
+        Heartbeat {
+            pid: 1,
+            beat_at: at(beat_at),
+            last_active_at: at(last_active_at),
+        }
+    }
+
+    #[test]
+    fn no_heartbeat_means_no_alert() {
+        assert_eq!(check(None, at("12:00"), true), None);
+    }
+
+    #[test]
+    fn fresh_heartbeat_with_recent_input_is_healthy() {
+        assert_eq!(check(Some(&heartbeat("11:59", "11:30")), at("12:00"), true), None);
+    }
+
+    #[test]
+    fn stale_heartbeat_means_the_watcher_is_silent() {
+        let alert = check(Some(&heartbeat("11:50", "11:50")), at("12:00"), false);
+
+        assert_eq!(alert, Some(Alert::WatcherSilent(at("11:50"))));
+    }
+
+    #[test]
+    fn heartbeat_at_the_timeout_is_still_alive() {
+        let beat_at = at("12:00") - Duration::minutes(HEARTBEAT_TIMEOUT_MINUTES);
+
+        assert_eq!(
+            check(
+                Some(&Heartbeat {
+                    beat_at,
+                    ..heartbeat("12:00", "12:00")
+                }),
+                at("12:00"),
+                false
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn long_input_silence_alerts_only_during_an_open_workday() {
+        let heartbeat = heartbeat("11:59", "09:00");
+
+        assert_eq!(check(Some(&heartbeat), at("12:00"), true), Some(Alert::InputSilent(at("09:00"))));
+        assert_eq!(check(Some(&heartbeat), at("12:00"), false), None);
+    }
+}
@@ -0,0 +1,30 @@
+use super::error::KaslError;
+use dialoguer::{theme::ColorfulTheme, Confirm};
+use std::{
+    error::Error,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+/// Answers confirmations with yes and prompts with their defaults, so kasl can run from cron jobs and CI.
+pub fn assume_yes() {
+    ASSUME_YES.store(true, Ordering::Relaxed);
+}
+
+pub fn is_disabled() -> bool {
+    ASSUME_YES.load(Ordering::Relaxed)
+}
+
+pub fn confirm(prompt: &str, default: bool) -> Result<bool, Box<dyn Error>> {
+    if is_disabled() {
+        return Ok(true);
+    }
+
+    Ok(Confirm::with_theme(&ColorfulTheme::default()).with_prompt(prompt).default(default).interact()?)
+}
+
+/// Error for input that has no default and cannot be asked for with `--yes`; `hint` says how to provide it instead.
+pub fn required(what: &str, hint: &str) -> Box<KaslError> {
+    KaslError::validation(format!("{} cannot be asked for with --yes, {}", what, hint))
+}
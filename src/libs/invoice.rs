@@ -0,0 +1,281 @@
+use super::{project::Project, rate::RateConfig, task::Task};
+use chrono::{Duration, NaiveDate};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+pub const NO_PROJECT: &str = "No project";
+
+#[derive(Debug)]
+pub struct InvoiceDay {
+    pub date: NaiveDate,
+    pub duration: Duration,
+    /// Hourly rate of the day, blended from the rates of the projects worked on.
+    pub hourly_rate: f64,
+    pub amount: f64,
+    /// Names of the projects worked on that day.
+    pub projects: Vec<String>,
+}
+
+/// Time and amount billed for one project over the month.
+#[derive(Debug)]
+pub struct InvoiceProject {
+    pub name: String,
+    pub client: Option<String>,
+    pub duration: Duration,
+    pub amount: f64,
+}
+
+#[derive(Debug)]
+pub struct Invoice {
+    pub rate: RateConfig,
+    pub days: Vec<InvoiceDay>,
+    pub projects: Vec<InvoiceProject>,
+}
+
+impl Invoice {
+    /// The working time of every day is split evenly between the tasks recorded that day, each billed at its project's
+    /// rate or the configured one. Days without tasks and tasks without a project are billed on a `No project` line at the end.
+    pub fn new(rate: &RateConfig, durations: HashMap<NaiveDate, Duration>, tasks: &[Task], projects: &[Project]) -> Self {
+        let mut project_shares: HashMap<Option<i32>, (Duration, f64)> = HashMap::new();
+        let mut days: Vec<InvoiceDay> = durations
+            .into_iter()
+            .map(|(date, duration)| {
+                let date_prefix = date.format("%Y-%m-%d").to_string();
+                let mut project_ids: Vec<Option<i32>> = tasks
+                    .iter()
+                    .filter(|task| task.timestamp.as_deref().is_some_and(|timestamp| timestamp.starts_with(&date_prefix)))
+                    .map(|task| task.project_id.filter(|id| projects.iter().any(|project| project.id == Some(*id))))
+                    .collect();
+                if project_ids.is_empty() {
+                    project_ids.push(None);
+                }
+                let hourly_rates: Vec<Option<f64>> = project_ids
+                    .iter()
+                    .map(|project_id| projects.iter().find(|project| project_id.is_some() && project.id == *project_id)?.hourly_rate)
+                    .collect();
+                let day_rate = rate.blended(&hourly_rates);
+                let amount = day_rate.amount(duration);
+                // Overtime is shared in proportion to the rates, so the project amounts add up to the day.
+                let rate_sum = day_rate.hourly * project_ids.len() as f64;
+                for (project_id, hourly_rate) in project_ids.iter().zip(hourly_rates.iter()) {
+                    let share = project_shares.entry(*project_id).or_insert((Duration::zero(), 0.0));
+                    share.0 += duration / project_ids.len() as i32;
+                    share.1 += match rate_sum > 0.0 {
+                        true => amount * hourly_rate.unwrap_or(rate.hourly) / rate_sum,
+                        false => amount / project_ids.len() as f64,
+                    };
+                }
+                let mut day_projects: Vec<String> = Vec::new();
+                for project in projects.iter().filter(|project| project_ids.contains(&project.id)) {
+                    day_projects.push(project.name.clone());
+                }
+                InvoiceDay {
+                    date,
+                    duration,
+                    hourly_rate: day_rate.hourly,
+                    amount,
+                    projects: day_projects,
+                }
+            })
+            .collect();
+        days.sort_by_key(|day| day.date);
+
+        let mut invoice_projects: Vec<InvoiceProject> = projects
+            .iter()
+            .filter_map(|project| {
+                let (duration, amount) = project_shares.remove(&project.id)?;
+                Some(InvoiceProject {
+                    name: project.name.clone(),
+                    client: project.client.clone(),
+                    duration,
+                    amount,
+                })
+            })
+            .collect();
+        invoice_projects.sort_by_key(|invoice_project| std::cmp::Reverse(invoice_project.duration));
+        if let Some((duration, amount)) = project_shares.remove(&None) {
+            invoice_projects.push(InvoiceProject {
+                name: NO_PROJECT.to_string(),
+                client: None,
+                duration,
+                amount,
+            });
+        }
+
+        Self {
+            rate: rate.clone(),
+            days,
+            projects: invoice_projects,
+        }
+    }
+
+    pub fn total_duration(&self) -> Duration {
+        self.days.iter().fold(Duration::zero(), |total, day| total + day.duration)
+    }
+
+    pub fn total_amount(&self) -> f64 {
+        self.days.iter().map(|day| day.amount).sum()
+    }
+
+    /// Writes the invoice as CSV so it can be opened in Excel or any spreadsheet: the days with decimal hours and
+    /// their rates, a total line and, after an empty line, the breakdown by project.
+    pub fn save_csv(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut file = File::create(path)?;
+        writeln!(file, "date,hours,rate,amount,currency,projects")?;
+        for day in self.days.iter() {
+            writeln!(
+                file,
+                "{},{:.2},{:.2},{:.2},{},{}",
+                day.date.format("%Y-%m-%d"),
+                hours(day.duration),
+                day.hourly_rate,
+                day.amount,
+                self.rate.currency,
+                field(&day.projects.join("; "))
+            )?;
+        }
+        writeln!(
+            file,
+            "total,{:.2},,{:.2},{},",
+            hours(self.total_duration()),
+            self.total_amount(),
+            self.rate.currency
+        )?;
+        writeln!(file)?;
+        writeln!(file, "project,client,hours,amount,currency")?;
+        for project in self.projects.iter() {
+            writeln!(
+                file,
+                "{},{},{:.2},{:.2},{}",
+                field(&project.name),
+                field(project.client.as_deref().unwrap_or_default()),
+                hours(project.duration),
+                project.amount,
+                self.rate.currency
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn hours(duration: Duration) -> f64 {
+    duration.num_seconds() as f64 / 3600.0
+}
+
+/// Quotes a CSV field that contains a separator, a quote or a line break.
+fn field(value: &str) -> String {
+    match value.contains([',', '"', '\n']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate() -> RateConfig {
+        RateConfig {
+            hourly: 50.0,
+            currency: "EUR".to_string(),
+            daily_hours: 8.0,
+            overtime_multiplier: 1.5,
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn task(day: u32, project_id: Option<i32>) -> Task {
+        Task {
+            timestamp: Some(format!("{} 12:00:00", date(day).format("%Y-%m-%d"))),
+            project_id,
+            ..Task::new("Task", "", Some(100))
+        }
+    }
+
+    fn project(id: i32, name: &str) -> Project {
+        Project {
+            id: Some(id),
+            client: Some("Acme, Inc.".to_string()),
+            ..Project::new(name)
+        }
+    }
+
+    fn invoice() -> Invoice {
+        let durations = HashMap::from([(date(4), Duration::hours(8)), (date(5), Duration::hours(10)), (date(6), Duration::hours(2))]);
+        let tasks = [task(4, Some(1)), task(4, Some(2)), task(5, Some(1)), task(5, None)];
+
+        Invoice::new(&rate(), durations, &tasks, &[project(1, "Alpha"), project(2, "Beta")])
+    }
+
+    #[test]
+    fn splits_days_between_the_projects_of_their_tasks() {
+        let invoice = invoice();
+        let breakdown: Vec<(&str, i64, f64)> = invoice
+            .projects
+            .iter()
+            .map(|project| (project.name.as_str(), project.duration.num_minutes(), project.amount))
+            .collect();
+
+        // Mar 4: 8h at 50 split between Alpha and Beta, Mar 5: 8h at 50 + 2h overtime at 75 split between Alpha and no project,
+        // Mar 6: 2h without tasks.
+        assert_eq!(breakdown, vec![("Alpha", 540, 475.0), ("Beta", 240, 200.0), (NO_PROJECT, 420, 375.0)]);
+        assert_eq!(invoice.days[0].projects, vec!["Alpha", "Beta"]);
+        assert_eq!(invoice.total_amount(), invoice.projects.iter().map(|project| project.amount).sum::<f64>());
+    }
+
+    #[test]
+    fn projects_are_billed_at_their_own_rate() {
+        let alpha = Project {
+            hourly_rate: Some(80.0),
+            ..project(1, "Alpha")
+        };
+        let durations = HashMap::from([(date(4), Duration::hours(8)), (date(5), Duration::hours(10))]);
+        let tasks = [task(4, Some(1)), task(4, Some(2)), task(5, Some(1))];
+        let invoice = Invoice::new(&rate(), durations, &tasks, &[alpha, project(2, "Beta")]);
+        let amounts: Vec<(&str, f64)> = invoice.projects.iter().map(|project| (project.name.as_str(), project.amount)).collect();
+
+        // Mar 4: 4h at 80 and 4h at 50, Mar 5: 8h at 80 and 2h overtime at 120.
+        assert_eq!(invoice.days[0].hourly_rate, 65.0);
+        assert_eq!(invoice.days[1].amount, 880.0);
+        assert_eq!(amounts, vec![("Alpha", 1200.0), ("Beta", 200.0)]);
+    }
+
+    #[test]
+    fn tasks_of_deleted_projects_count_as_no_project() {
+        let durations = HashMap::from([(date(4), Duration::hours(4))]);
+        let invoice = Invoice::new(&rate(), durations, &[task(4, Some(9))], &[project(1, "Alpha")]);
+
+        assert_eq!(invoice.projects.len(), 1);
+        assert_eq!(invoice.projects[0].name, NO_PROJECT);
+        assert!(invoice.days[0].projects.is_empty());
+    }
+
+    #[test]
+    fn csv_has_decimal_hours_and_the_project_breakdown() {
+        let path = std::env::temp_dir().join(format!("kasl-invoice-{}.csv", std::process::id()));
+        invoice().save_csv(&path).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            csv,
+            "date,hours,rate,amount,currency,projects\n\
+             2024-03-04,8.00,50.00,400.00,EUR,Alpha; Beta\n\
+             2024-03-05,10.00,50.00,550.00,EUR,Alpha\n\
+             2024-03-06,2.00,50.00,100.00,EUR,\n\
+             total,20.00,,1050.00,EUR,\n\
+             \n\
+             project,client,hours,amount,currency\n\
+             Alpha,\"Acme, Inc.\",9.00,475.00,EUR\n\
+             Beta,\"Acme, Inc.\",4.00,200.00,EUR\n\
+             No project,,7.00,375.00,EUR\n"
+        );
+    }
+}
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+/// A free-form note about the workday that is not tied to any task.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JournalEntry {
+    pub id: i32,
+    pub timestamp: String,
+    pub text: String,
+}
@@ -0,0 +1,150 @@
+use super::event::Event;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// Unions overlapping and touching work intervals; an open interval absorbs everything that starts after it.
+pub fn union(mut events: Vec<Event>) -> Vec<Event> {
+    events.sort_by_key(|event| event.start);
+    let mut merged: Vec<Event> = vec![];
+    for event in events {
+        match merged.last_mut() {
+            Some(last) if !matches!(last.end, Some(end) if event.start > end) => {
+                last.end = match (last.end, event.end) {
+                    (Some(last_end), Some(end)) => Some(last_end.max(end)),
+                    _ => None,
+                };
+            }
+            _ => merged.push(Event { duration: None, ..event }),
+        }
+    }
+
+    merged
+}
+
+/// Combines two sets of events tracked on different devices day by day.
+///
+/// Work intervals of both sides are unioned, so a pause survives only where both devices saw one.
+/// Only days present in `remote` are returned; other local days are left untouched.
+pub fn merge_days(local: &[Event], remote: &[Event]) -> BTreeMap<NaiveDate, Vec<Event>> {
+    let mut days: BTreeMap<NaiveDate, Vec<Event>> = BTreeMap::new();
+    for event in remote.iter() {
+        days.entry(event.start.date()).or_default().push(event.clone());
+    }
+    for event in local.iter() {
+        if let Some(day_events) = days.get_mut(&event.start.date()) {
+            day_events.push(event.clone());
+        }
+    }
+
+    days.into_iter().map(|(date, day_events)| (date, union(day_events))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    fn at(day: u32, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("2024-03-{:02} {}", day, time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn event(day: u32, start: &str, end: Option<&str>) -> Event {
+        Event {
+            id: 0,
+            start: at(day, start),
+            end: end.map(|end| at(day, end)),
+            duration: None,
+        }
+    }
+
+    fn bounds(events: &[Event]) -> Vec<(NaiveDateTime, Option<NaiveDateTime>)> {
+        events.iter().map(|event| (event.start, event.end)).collect()
+    }
+
+    #[test]
+    fn union_joins_overlapping_and_touching_intervals() {
+        let events = vec![
+            event(4, "13:00", Some("14:00")),
+            event(4, "09:00", Some("11:00")),
+            event(4, "10:00", Some("12:00")),
+            event(4, "12:00", Some("12:30")),
+        ];
+
+        assert_eq!(
+            bounds(&union(events)),
+            vec![(at(4, "09:00"), Some(at(4, "12:30"))), (at(4, "13:00"), Some(at(4, "14:00")))]
+        );
+    }
+
+    #[test]
+    fn union_keeps_contained_intervals_inside_the_outer_one() {
+        let events = vec![event(4, "09:00", Some("17:00")), event(4, "10:00", Some("11:00"))];
+
+        assert_eq!(bounds(&union(events)), vec![(at(4, "09:00"), Some(at(4, "17:00")))]);
+    }
+
+    #[test]
+    fn open_interval_absorbs_the_later_ones() {
+        let events = vec![
+            event(4, "09:00", Some("10:00")),
+            event(4, "11:00", None),
+            event(4, "12:00", Some("13:00")),
+            event(4, "14:00", None),
+        ];
+
+        assert_eq!(bounds(&union(events)), vec![(at(4, "09:00"), Some(at(4, "10:00"))), (at(4, "11:00"), None)]);
+    }
+
+    #[test]
+    fn earlier_interval_reaching_into_an_open_one_keeps_it_open() {
+        let events = vec![event(4, "09:00", Some("12:00")), event(4, "11:00", None)];
+
+        assert_eq!(bounds(&union(events)), vec![(at(4, "09:00"), None)]);
+    }
+
+    #[test]
+    fn days_present_only_locally_stay_untouched() {
+        let local = vec![event(4, "09:00", Some("17:00")), event(5, "09:00", Some("17:00"))];
+        let remote = vec![event(5, "08:00", Some("10:00"))];
+        let days = merge_days(&local, &remote);
+
+        assert_eq!(days.keys().copied().collect::<Vec<_>>(), vec![at(5, "00:00").date()]);
+        assert_eq!(bounds(&days[&at(5, "00:00").date()]), vec![(at(5, "08:00"), Some(at(5, "17:00")))]);
+    }
+
+    #[test]
+    fn days_present_only_remotely_are_taken_as_they_are() {
+        let remote = vec![event(6, "09:00", Some("12:00")), event(6, "13:00", Some("17:00"))];
+        let days = merge_days(&[], &remote);
+
+        assert_eq!(
+            bounds(&days[&at(6, "00:00").date()]),
+            vec![(at(6, "09:00"), Some(at(6, "12:00"))), (at(6, "13:00"), Some(at(6, "17:00")))]
+        );
+    }
+
+    #[test]
+    fn pause_survives_only_where_both_sides_paused() {
+        // Both devices paused 12:00-12:30; only the laptop paused 15:00-16:00.
+        let local = vec![
+            event(4, "09:00", Some("12:00")),
+            event(4, "12:30", Some("15:00")),
+            event(4, "16:00", Some("18:00")),
+        ];
+        let remote = vec![event(4, "08:30", Some("12:00")), event(4, "12:30", Some("17:00"))];
+        let days = merge_days(&local, &remote);
+
+        assert_eq!(
+            bounds(&days[&at(4, "00:00").date()]),
+            vec![(at(4, "08:30"), Some(at(4, "12:00"))), (at(4, "12:30"), Some(at(4, "18:00")))]
+        );
+    }
+
+    #[test]
+    fn merged_events_have_their_duration_recomputed() {
+        let mut events = vec![event(4, "09:00", Some("10:00"))];
+        events[0].duration = Some(chrono::Duration::minutes(60));
+
+        assert!(union(events).iter().all(|event| event.duration.is_none()));
+    }
+}
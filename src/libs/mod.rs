@@ -1,8 +1,38 @@
+pub mod activity;
+pub mod archive;
+pub mod clipboard;
+pub mod compression;
 pub mod config;
+pub mod contract;
 pub mod data_storage;
+pub mod date;
+pub mod destination;
+pub mod diag;
+pub mod display;
+pub mod dry_run;
+pub mod error;
 pub mod event;
+pub mod health;
+pub mod interactive;
+pub mod invoice;
+pub mod journal;
+pub mod merge;
+pub mod oauth;
+pub mod permissions;
+pub mod pid;
+pub mod productivity;
+pub mod project;
+pub mod rate;
+pub mod reminder;
+pub mod report;
+pub mod retention;
+pub mod rounding;
 pub mod scheduler;
 pub mod secret;
+pub mod stats;
+pub mod status;
+pub mod summary;
 pub mod task;
 pub mod update;
+pub mod validation;
 pub mod view;
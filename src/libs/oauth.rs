@@ -0,0 +1,188 @@
+use super::{error::KaslError, interactive, secret::Secret};
+use crate::api::http::SendWithRetry;
+use chrono::Local;
+use reqwest::{Client, Url};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::{error::Error, process::Command, time::Duration};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    time::timeout,
+};
+
+/// Loopback port of the redirect URI, `http://127.0.0.1:47600/callback`; providers require it to be registered.
+pub const REDIRECT_PORT: u16 = 47600;
+const REDIRECT_PATH: &str = "/callback";
+/// How long the browser sign-in may take before the listener gives up.
+const AUTHORIZE_TIMEOUT: Duration = Duration::from_secs(300);
+/// Access tokens this close to expiry are refreshed before use.
+const EXPIRY_MARGIN_SECS: i64 = 60;
+/// Random bytes in the `state` parameter that ties the redirect to this sign-in.
+const STATE_LEN: usize = 16;
+const CALLBACK_PAGE: &str = "<html><body><h3>kasl is signed in, you can close this tab.</h3></body></html>";
+
+/// Tokens kept encrypted in `token_file`; providers that issue non-expiring tokens send neither a refresh token nor an expiry.
+#[derive(Serialize, Deserialize)]
+struct StoredToken {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_at: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<i64>,
+}
+
+/// OAuth2 authorization-code flow with a localhost redirect for integrations without personal tokens.
+pub struct OAuth {
+    pub authorize_url: String,
+    pub token_url: String,
+    pub client_id: String,
+    pub scopes: Vec<String>,
+    /// Provider-specific authorization parameters, e.g. Notion's `owner=user`.
+    pub extra_params: Vec<(String, String)>,
+    pub client_secret: Secret,
+    pub token_file: &'static str,
+}
+
+impl OAuth {
+    /// Valid access token: the stored one, a refreshed one, or one from a new browser sign-in.
+    pub async fn access_token(&self) -> Result<String, Box<dyn Error>> {
+        let stored = self.token_secret().get().and_then(|token| serde_json::from_str::<StoredToken>(&token).ok());
+        if let Some(stored) = stored {
+            let now = Local::now().timestamp();
+            if stored.expires_at.is_none_or(|expires_at| expires_at - EXPIRY_MARGIN_SECS > now) {
+                return Ok(stored.access_token);
+            }
+            if let Some(refresh_token) = stored.refresh_token {
+                // A revoked or expired refresh token falls through to a new sign-in.
+                if let Ok(token) = self.refresh(&refresh_token).await {
+                    return Ok(token);
+                }
+            }
+        }
+
+        self.authorize().await
+    }
+
+    fn token_secret(&self) -> Secret {
+        Secret::new(self.token_file, "")
+    }
+
+    fn redirect_uri() -> String {
+        format!("http://127.0.0.1:{}{}", REDIRECT_PORT, REDIRECT_PATH)
+    }
+
+    /// Opens the consent page and waits for the provider to redirect back with a code.
+    async fn authorize(&self) -> Result<String, Box<dyn Error>> {
+        if interactive::is_disabled() {
+            return Err(interactive::required("The browser sign-in", "sign in once without --yes"));
+        }
+        let mut state = [0u8; STATE_LEN];
+        SystemRandom::new().fill(&mut state).map_err(|_| "Failed to generate a random sign-in state")?;
+        let state: String = state.iter().map(|byte| format!("{:02x}", byte)).collect();
+        let mut params = vec![
+            ("client_id".to_string(), self.client_id.clone()),
+            ("redirect_uri".to_string(), Self::redirect_uri()),
+            ("response_type".to_string(), "code".to_string()),
+            ("state".to_string(), state.clone()),
+        ];
+        if !self.scopes.is_empty() {
+            params.push(("scope".to_string(), self.scopes.join(" ")));
+        }
+        params.extend(self.extra_params.iter().cloned());
+        let url = Url::parse_with_params(&self.authorize_url, &params)?;
+
+        let listener = TcpListener::bind(("127.0.0.1", REDIRECT_PORT))
+            .await
+            .map_err(|e| KaslError::network(format!("Cannot listen on port {} for the sign-in redirect: {}", REDIRECT_PORT, e)))?;
+        println!("Sign in to continue, if no browser opens visit:\n{}", url);
+        open_browser(url.as_str());
+        let code = timeout(AUTHORIZE_TIMEOUT, Self::receive_code(&listener, &state))
+            .await
+            .map_err(|_| KaslError::network("Timed out waiting for the sign-in to complete"))??;
+
+        self.request_token(
+            &[("grant_type", "authorization_code"), ("code", &code), ("redirect_uri", &Self::redirect_uri())],
+            None,
+        )
+        .await
+    }
+
+    /// Accepts redirects until one carries the expected state, then answers it with a short page.
+    async fn receive_code(listener: &TcpListener, state: &str) -> Result<String, Box<dyn Error>> {
+        loop {
+            let (mut stream, _) = listener.accept().await?;
+            let mut buffer = [0u8; 4096];
+            let read = stream.read(&mut buffer).await?;
+            let request = String::from_utf8_lossy(&buffer[..read]);
+            let Some(target) = request.lines().next().and_then(|line| line.split_whitespace().nth(1)) else {
+                continue;
+            };
+            let url = Url::parse(&format!("http://127.0.0.1{}", target))?;
+            if url.path() != REDIRECT_PATH {
+                continue;
+            }
+            let query = |name: &str| url.query_pairs().find(|(key, _)| key == name).map(|(_, value)| value.into_owned());
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                CALLBACK_PAGE.len(),
+                CALLBACK_PAGE
+            );
+            stream.write_all(response.as_bytes()).await?;
+            if query("state").as_deref() != Some(state) {
+                continue;
+            }
+            if let Some(error) = query("error") {
+                return Err(KaslError::network(format!("Sign-in was not completed: {}", error)));
+            }
+
+            return Ok(query("code").ok_or_else(|| KaslError::network("The sign-in redirect carried no code"))?);
+        }
+    }
+
+    async fn refresh(&self, refresh_token: &str) -> Result<String, Box<dyn Error>> {
+        self.request_token(&[("grant_type", "refresh_token"), ("refresh_token", refresh_token)], Some(refresh_token))
+            .await
+    }
+
+    /// Exchanges a code or refresh token and stores the result; `refresh_token` is kept when the provider does not rotate it.
+    async fn request_token(&self, form: &[(&str, &str)], refresh_token: Option<&str>) -> Result<String, Box<dyn Error>> {
+        let client_secret = self.client_secret.get_or_prompt()?;
+        let res = Client::new()
+            .post(&self.token_url)
+            .basic_auth(&self.client_id, Some(&client_secret))
+            .form(form)
+            .send_with_retry()
+            .await?;
+        if !res.status().is_success() {
+            return Err(KaslError::network(format!("Token request failed with status {}", res.status())));
+        }
+        let token = res.json::<TokenResponse>().await?;
+        let stored = StoredToken {
+            access_token: token.access_token.clone(),
+            refresh_token: token.refresh_token.or(refresh_token.map(str::to_string)),
+            expires_at: token.expires_in.map(|expires_in| Local::now().timestamp() + expires_in),
+        };
+        self.token_secret().set(&serde_json::to_string(&stored)?)?;
+
+        Ok(token.access_token)
+    }
+}
+
+fn open_browser(url: &str) {
+    let _ = match std::env::consts::OS {
+        "macos" => Command::new("open").arg(url).status(),
+        // `cmd /C start` would split the URL at every `&`.
+        "windows" => Command::new("rundll32").args(["url.dll,FileProtocolHandler", url]).status(),
+        _ => Command::new("xdg-open").arg(url).status(),
+    };
+}
@@ -0,0 +1,36 @@
+/// Settings pane where the user grants kasl access to keyboard and mouse state.
+pub const ACCESSIBILITY_SETTINGS_URL: &str = "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility";
+
+#[cfg(target_os = "macos")]
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    fn AXIsProcessTrusted() -> bool;
+}
+
+/// Reading input state on macOS needs the Accessibility permission; other platforms need nothing extra.
+#[cfg(target_os = "macos")]
+pub fn input_monitoring_allowed() -> bool {
+    unsafe { AXIsProcessTrusted() }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn input_monitoring_allowed() -> bool {
+    true
+}
+
+/// Why `kasl watch` cannot read input directly, for `kasl status` and `kasl doctor`.
+pub fn input_monitoring_warning() -> Option<String> {
+    (!input_monitoring_allowed()).then(|| {
+        format!(
+            "no Accessibility permission, `kasl watch` falls back to the system idle time; grant it in System Settings ({})",
+            ACCESSIBILITY_SETTINGS_URL
+        )
+    })
+}
+
+/// Opens the Accessibility pane of System Settings.
+pub fn open_settings() {
+    if cfg!(target_os = "macos") {
+        let _ = std::process::Command::new("open").arg(ACCESSIBILITY_SETTINGS_URL).status();
+    }
+}
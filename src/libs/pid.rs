@@ -0,0 +1,154 @@
+use super::{data_storage::DataStorage, event::FormatEvent};
+use chrono::{Local, NaiveDateTime};
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::process::{self, Command, Stdio};
+
+const PID_FILE: &str = "kasl-watch.pid";
+
+#[derive(Debug)]
+pub struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    /// Takes the single-instance lock, replacing a lock left behind by a process that is no longer running.
+    pub fn acquire() -> Result<Self, Box<dyn Error>> {
+        Self::acquire_at(DataStorage::new().get_path(PID_FILE)?)
+    }
+
+    /// Creates the lock file only if it does not exist yet, so two watchers starting at once cannot both take it.
+    fn acquire_at(path: PathBuf) -> Result<Self, Box<dyn Error>> {
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    write!(file, "{}\n{}", process::id(), Local::now().format("%Y-%m-%d %H:%M:%S"))?;
+
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    if let Some((pid, started_at)) = Self::read(&path) {
+                        if pid != process::id() && Self::is_running(pid) {
+                            let uptime = Local::now().naive_local() - started_at;
+                            return Err(format!(
+                                "kasl watch is already running (PID {}, uptime {})",
+                                pid,
+                                FormatEvent::format_duration(Some(uptime))
+                            )
+                            .into());
+                        }
+                    }
+                    // Stale lock: remove it and race for a fresh one; whoever loses sees the winner's PID.
+                    match fs::remove_file(&path) {
+                        Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+                        _ => {}
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// PID and start time of the running watcher, if there is one.
+    pub fn running() -> Option<(u32, NaiveDateTime)> {
+        let (pid, started_at) = Self::read(&DataStorage::new().get_path(PID_FILE).ok()?)?;
+
+        Self::is_running(pid).then_some((pid, started_at))
+    }
+
+    fn read(path: &PathBuf) -> Option<(u32, NaiveDateTime)> {
+        let content = fs::read_to_string(path).ok()?;
+        let mut lines = content.lines();
+        let pid = lines.next()?.trim().parse().ok()?;
+        let started_at = NaiveDateTime::parse_from_str(lines.next()?.trim(), "%Y-%m-%d %H:%M:%S").ok()?;
+
+        Some((pid, started_at))
+    }
+
+    #[cfg(windows)]
+    fn is_running(pid: u32) -> bool {
+        Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {}", pid), "/NH"])
+            .stderr(Stdio::null())
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+            .unwrap_or(false)
+    }
+
+    #[cfg(not(windows))]
+    fn is_running(pid: u32) -> bool {
+        Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pid_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("kasl-pid-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        dir.join(PID_FILE)
+    }
+
+    #[test]
+    fn lock_is_created_and_removed_on_drop() {
+        let path = pid_path("drop");
+        let pid_file = PidFile::acquire_at(path.clone()).unwrap();
+        assert_eq!(PidFile::read(&path).map(|(pid, _)| pid), Some(process::id()));
+
+        drop(pid_file);
+        assert!(!path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stale_lock_is_replaced() {
+        let path = pid_path("stale");
+        let mut child = Command::new("true").spawn().unwrap();
+        child.wait().unwrap();
+        fs::write(&path, format!("{}\n2024-03-04 09:00:00", child.id())).unwrap();
+
+        let _pid_file = PidFile::acquire_at(path.clone()).unwrap();
+        assert_eq!(PidFile::read(&path).map(|(pid, _)| pid), Some(process::id()));
+    }
+
+    #[test]
+    fn unreadable_lock_is_replaced() {
+        let path = pid_path("unreadable");
+        fs::write(&path, "garbage").unwrap();
+
+        let _pid_file = PidFile::acquire_at(path.clone()).unwrap();
+        assert_eq!(PidFile::read(&path).map(|(pid, _)| pid), Some(process::id()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lock_of_a_running_process_is_kept() {
+        let path = pid_path("running");
+        let mut child = Command::new("sleep").arg("5").spawn().unwrap();
+        fs::write(&path, format!("{}\n2024-03-04 09:00:00", child.id())).unwrap();
+
+        let result = PidFile::acquire_at(path.clone());
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        assert!(result.unwrap_err().to_string().contains("already running"));
+        assert_eq!(PidFile::read(&path).map(|(pid, _)| pid), Some(child.id()));
+    }
+}
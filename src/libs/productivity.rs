@@ -0,0 +1,170 @@
+use super::{config::ConfigModule, error::KaslError, stats::DayStats};
+use chrono::{Duration, NaiveDate};
+use dialoguer::{theme::ColorfulTheme, Input};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+/// A break of at least `break_minutes` is due once more than `after_hours` have been worked.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BreakRule {
+    pub after_hours: f64,
+    pub break_minutes: i64,
+}
+
+/// Required breaks; defaults to the German Arbeitszeitgesetz, 30 minutes after 6 hours and 45 after 9.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BreakConfig {
+    pub rules: Vec<BreakRule>,
+}
+
+impl BreakConfig {
+    pub fn module() -> ConfigModule {
+        ConfigModule {
+            key: "breaks".to_string(),
+            name: "Break rules".to_string(),
+        }
+    }
+
+    pub fn init(config: &Option<Self>) -> Result<Self, Box<dyn Error>> {
+        let config = config.clone().unwrap_or(Self {
+            rules: vec![
+                BreakRule {
+                    after_hours: 6.0,
+                    break_minutes: 30,
+                },
+                BreakRule {
+                    after_hours: 9.0,
+                    break_minutes: 45,
+                },
+            ],
+        });
+        let rules = config
+            .rules
+            .iter()
+            .map(|rule| format!("{}:{}", rule.after_hours, rule.break_minutes))
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!("Break rules settings");
+        let rules: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Enter the required breaks as HOURS:MINUTES pairs, e.g. 6:30, 9:45")
+            .default(rules)
+            .interact_text()?;
+
+        Ok(Self {
+            rules: Self::parse_rules(&rules)?,
+        })
+    }
+
+    fn parse_rules(rules: &str) -> Result<Vec<BreakRule>, Box<dyn Error>> {
+        rules
+            .split(',')
+            .map(|rule| {
+                let invalid = || KaslError::validation(format!("Invalid break rule `{}`, use HOURS:MINUTES, e.g. 6:30", rule.trim()));
+                let (after_hours, break_minutes) = rule.trim().split_once(':').ok_or_else(invalid)?;
+                Ok(BreakRule {
+                    after_hours: after_hours.trim().parse().map_err(|_| invalid())?,
+                    break_minutes: break_minutes.trim().parse().map_err(|_| invalid())?,
+                })
+            })
+            .collect()
+    }
+
+    /// The longest break any rule demands for `worked` time.
+    pub fn required_break(&self, worked: Duration) -> Duration {
+        self.rules
+            .iter()
+            .filter(|rule| worked.num_minutes() as f64 > rule.after_hours * 60.0)
+            .map(|rule| Duration::minutes(rule.break_minutes))
+            .max()
+            .unwrap_or(Duration::zero())
+    }
+
+    /// The shortfall of a day, if its pauses do not cover the required break.
+    pub fn evaluate(&self, date: NaiveDate, worked: Duration, pauses: &[Duration]) -> Option<BreakDebt> {
+        let taken = pauses.iter().fold(Duration::zero(), |total, pause| total + *pause);
+        let required = self.required_break(worked);
+
+        (taken < required).then_some(BreakDebt { date, worked, taken, required })
+    }
+
+    /// Days of `days` that fell short of the required breaks, oldest first.
+    pub fn debts(&self, days: &[DayStats]) -> Vec<BreakDebt> {
+        days.iter().filter_map(|day| self.evaluate(day.date, day.duration, &day.pauses)).collect()
+    }
+}
+
+/// A day whose pauses were shorter than the break its working time requires.
+#[derive(Debug)]
+pub struct BreakDebt {
+    pub date: NaiveDate,
+    pub worked: Duration,
+    pub taken: Duration,
+    pub required: Duration,
+}
+
+impl BreakDebt {
+    pub fn missing(&self) -> Duration {
+        self.required - self.taken
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, NaiveTime};
+
+    fn config() -> BreakConfig {
+        BreakConfig {
+            rules: BreakConfig::parse_rules("6:30, 9:45").unwrap(),
+        }
+    }
+
+    fn day(day: u32, worked: Duration, pauses: &[i64]) -> DayStats {
+        DayStats {
+            date: NaiveDate::from_ymd_opt(2024, 3, day).unwrap(),
+            start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+            duration: worked,
+            pauses: pauses.iter().map(|minutes| Duration::minutes(*minutes)).collect(),
+        }
+    }
+
+    #[test]
+    fn break_is_due_only_beyond_the_rule_hours() {
+        let config = config();
+
+        assert_eq!(config.required_break(Duration::hours(6)), Duration::zero());
+        assert_eq!(config.required_break(Duration::minutes(361)), Duration::minutes(30));
+        assert_eq!(config.required_break(Duration::hours(9)), Duration::minutes(30));
+        assert_eq!(config.required_break(Duration::minutes(541)), Duration::minutes(45));
+    }
+
+    #[test]
+    fn short_pauses_accrue_a_debt_and_enough_pauses_clear_it() {
+        let config = config();
+        let days = [
+            day(4, Duration::hours(8), &[10, 15]),
+            day(5, Duration::hours(8), &[20, 10]),
+            day(6, Duration::hours(10), &[30]),
+            day(7, Duration::hours(5), &[]),
+        ];
+        let debts = config.debts(&days);
+        let missing: Vec<(u32, i64)> = debts.iter().map(|debt| (debt.date.day(), debt.missing().num_minutes())).collect();
+
+        assert_eq!(missing, vec![(4, 5), (6, 15)]);
+        assert_eq!((debts[0].taken, debts[0].required), (Duration::minutes(25), Duration::minutes(30)));
+    }
+
+    #[test]
+    fn break_rules_are_parsed_from_hours_and_minutes() {
+        assert_eq!(
+            BreakConfig::parse_rules(" 4.5 : 15 ").unwrap(),
+            vec![BreakRule {
+                after_hours: 4.5,
+                break_minutes: 15
+            }]
+        );
+        assert!(BreakConfig::parse_rules("6h30").is_err());
+        assert!(BreakConfig::parse_rules("6:thirty").is_err());
+    }
+}
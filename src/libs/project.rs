@@ -0,0 +1,42 @@
+use super::diag;
+use serde::{Deserialize, Serialize};
+
+/// A client engagement that tasks can belong to.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Project {
+    pub id: Option<i32>,
+    pub name: String,
+    #[serde(default)]
+    pub client: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub hourly_rate: Option<f64>,
+}
+
+impl Project {
+    pub fn new(name: &str) -> Self {
+        Self {
+            id: None,
+            name: name.to_string(),
+            client: None,
+            tags: vec![],
+            hourly_rate: None,
+        }
+    }
+
+    /// Copy for sharing outside the team: the name is replaced with a pseudonym, the client and tags are dropped.
+    pub fn redacted(&self) -> Self {
+        Self {
+            name: diag::pseudonym("Project", &self.name),
+            client: None,
+            tags: vec![],
+            ..self.clone()
+        }
+    }
+
+    /// Splits a comma-separated list of tags, dropping empty ones.
+    pub fn parse_tags(tags: &str) -> Vec<String> {
+        tags.split(',').map(|tag| tag.trim().to_string()).filter(|tag| !tag.is_empty()).collect()
+    }
+}
@@ -0,0 +1,111 @@
+use super::config::ConfigModule;
+use chrono::Duration;
+use dialoguer::{theme::ColorfulTheme, Input};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RateConfig {
+    pub hourly: f64,
+    pub currency: String,
+    #[serde(default = "RateConfig::default_daily_hours")]
+    pub daily_hours: f64,
+    #[serde(default = "RateConfig::default_overtime_multiplier")]
+    pub overtime_multiplier: f64,
+}
+
+impl RateConfig {
+    pub fn module() -> ConfigModule {
+        ConfigModule {
+            key: "rate".to_string(),
+            name: "Hourly rate".to_string(),
+        }
+    }
+
+    fn default_daily_hours() -> f64 {
+        8.0
+    }
+
+    fn default_overtime_multiplier() -> f64 {
+        1.0
+    }
+
+    pub fn init(config: &Option<Self>) -> Result<Self, Box<dyn Error>> {
+        let config = config.clone().unwrap_or(Self {
+            hourly: 0.0,
+            currency: "USD".to_string(),
+            daily_hours: Self::default_daily_hours(),
+            overtime_multiplier: Self::default_overtime_multiplier(),
+        });
+        println!("Hourly rate settings");
+        Ok(Self {
+            hourly: Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Enter your hourly rate")
+                .default(config.hourly)
+                .interact_text()?,
+            currency: Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Enter the currency")
+                .default(config.currency)
+                .interact_text()?,
+            daily_hours: Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Enter the daily hours after which overtime starts")
+                .default(config.daily_hours)
+                .interact_text()?,
+            overtime_multiplier: Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Enter the overtime rate multiplier")
+                .default(config.overtime_multiplier)
+                .interact_text()?,
+        })
+    }
+
+    /// Earnings for one day: hours beyond `daily_hours` are paid with the overtime multiplier.
+    pub fn amount(&self, duration: Duration) -> f64 {
+        let hours = duration.num_minutes() as f64 / 60.0;
+        let regular_hours = hours.min(self.daily_hours);
+        let overtime_hours = (hours - self.daily_hours).max(0.0);
+
+        (regular_hours + overtime_hours * self.overtime_multiplier) * self.hourly
+    }
+
+    /// Rate of a day split evenly between work billed at the given hourly rates, `None` meaning the configured one.
+    pub fn blended(&self, hourly_rates: &[Option<f64>]) -> Self {
+        if hourly_rates.is_empty() {
+            return self.clone();
+        }
+        let hourly = hourly_rates.iter().map(|hourly_rate| hourly_rate.unwrap_or(self.hourly)).sum::<f64>() / hourly_rates.len() as f64;
+
+        Self { hourly, ..self.clone() }
+    }
+
+    pub fn format_amount(&self, amount: f64) -> String {
+        format!("{:.2} {}", amount, self.currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate() -> RateConfig {
+        RateConfig {
+            hourly: 50.0,
+            currency: "EUR".to_string(),
+            daily_hours: 8.0,
+            overtime_multiplier: 1.5,
+        }
+    }
+
+    #[test]
+    fn blended_rate_falls_back_to_the_configured_one() {
+        assert_eq!(rate().blended(&[]).hourly, 50.0);
+        assert_eq!(rate().blended(&[None, None]).hourly, 50.0);
+        assert_eq!(rate().blended(&[Some(80.0), None]).hourly, 65.0);
+        assert_eq!(rate().blended(&[Some(80.0), Some(100.0)]).hourly, 90.0);
+    }
+
+    #[test]
+    fn blended_rate_keeps_overtime() {
+        // 8h regular and 2h overtime at 1.5 of the project rate.
+        assert_eq!(rate().blended(&[Some(80.0)]).amount(Duration::hours(10)), 880.0);
+    }
+}
@@ -0,0 +1,61 @@
+use chrono::Local;
+use std::{process::Command, time::Duration};
+
+/// A recurring prompt shown by `kasl watch` after every `minutes` of active time.
+#[derive(Debug, Clone)]
+pub struct Reminder {
+    pub id: Option<i32>,
+    pub minutes: u32,
+    pub message: String,
+    /// Shell command run alongside the message, e.g. a desktop notification.
+    pub command: Option<String>,
+}
+
+impl Reminder {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.minutes as u64 * 60)
+    }
+
+    /// Prints the message and starts the command without waiting for it.
+    pub fn fire(&self) {
+        println!("[{}] Reminder: {}", Local::now().format("%H:%M:%S"), self.message);
+        if let Some(command) = &self.command {
+            let spawned = match std::env::consts::OS {
+                "windows" => Command::new("cmd").args(["/C", command]).spawn(),
+                _ => Command::new("sh").args(["-c", command]).spawn(),
+            };
+            if let Err(e) = spawned {
+                eprintln!("Failed to run the command of reminder {}: {}", self.id.unwrap_or_default(), e);
+            }
+        }
+    }
+}
+
+/// Counts active time per reminder; pauses stop the clock instead of resetting it.
+#[derive(Debug)]
+pub struct ReminderClock {
+    reminders: Vec<(Reminder, Duration)>,
+}
+
+impl ReminderClock {
+    pub fn new(reminders: Vec<Reminder>) -> Self {
+        Self {
+            reminders: reminders.into_iter().map(|reminder| (reminder, Duration::ZERO)).collect(),
+        }
+    }
+
+    /// Adds `active` time to every reminder and returns those that are due, restarting their count.
+    pub fn advance(&mut self, active: Duration) -> Vec<&Reminder> {
+        self.reminders
+            .iter_mut()
+            .filter_map(|(reminder, elapsed)| {
+                *elapsed += active;
+                if reminder.minutes == 0 || *elapsed < reminder.interval() {
+                    return None;
+                }
+                *elapsed = Duration::ZERO;
+                Some(&*reminder)
+            })
+            .collect()
+    }
+}
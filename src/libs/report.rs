@@ -0,0 +1,152 @@
+use super::config::ConfigModule;
+use crate::db::{events::Events, reports::Reports};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use dialoguer::{theme::ColorfulTheme, Confirm, Input};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, error::Error};
+
+/// How far back `report --pending` looks for workdays without a submitted report.
+pub const PENDING_DAYS: i64 = 30;
+
+#[derive(Debug, Clone)]
+pub struct ReportSubmission {
+    pub date: NaiveDate,
+    pub submitted_at: NaiveDateTime,
+    pub payload: String,
+    pub status: u16,
+}
+
+impl ReportSubmission {
+    /// One line per submitted interval: time range, duration and the tasks attached to it.
+    fn payload_lines(payload: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        let intervals: Vec<serde_json::Value> = serde_json::from_str(payload)?;
+        let as_str = |value: &serde_json::Value| value.as_str().unwrap_or_default().to_string();
+
+        Ok(intervals
+            .iter()
+            .map(|interval| {
+                format!(
+                    "{}-{} ({}) {}",
+                    as_str(&interval["from"]),
+                    as_str(&interval["to"]),
+                    as_str(&interval["total_ts"]),
+                    as_str(&interval["task"])
+                )
+            })
+            .collect())
+    }
+
+    /// Lines removed from (`-`) and added to (`+`) the previously submitted payload.
+    pub fn diff(&self, payload: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        let previous = Self::payload_lines(&self.payload)?;
+        let current = Self::payload_lines(payload)?;
+        let mut diff: Vec<String> = previous
+            .iter()
+            .filter(|line| !current.contains(line))
+            .map(|line| format!("- {}", line))
+            .collect();
+        diff.extend(current.iter().filter(|line| !previous.contains(line)).map(|line| format!("+ {}", line)));
+
+        Ok(diff)
+    }
+}
+
+/// Past days with recorded events but no submitted report, oldest first. Only days since the first recorded
+/// submission count: reports sent before kasl tracked submissions would otherwise all look pending.
+pub fn pending_dates(today: NaiveDate) -> Result<Vec<NaiveDate>, Box<dyn Error>> {
+    let Some(first_date) = Reports::new()?.fetch_first_date()? else {
+        return Ok(vec![]);
+    };
+    let from = (today - Duration::days(PENDING_DAYS)).max(first_date);
+    let to = today - Duration::days(1);
+    let worked_dates: BTreeSet<NaiveDate> = Events::new()?.fetch_range(from, to)?.iter().map(|event| event.start.date()).collect();
+    let submitted_dates = Reports::new()?.fetch_submitted_dates(from, to)?;
+
+    Ok(worked_dates.into_iter().filter(|date| !submitted_dates.contains(date)).collect())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReportConfig {
+    pub min_hours: f64,
+    #[serde(default)]
+    pub morning_summary: bool,
+}
+
+impl ReportConfig {
+    pub fn module() -> ConfigModule {
+        ConfigModule {
+            key: "report".to_string(),
+            name: "Report".to_string(),
+        }
+    }
+
+    pub fn init(config: &Option<Self>) -> Result<Self, Box<dyn Error>> {
+        let config = config.clone().unwrap_or(Self {
+            min_hours: 0.0,
+            morning_summary: false,
+        });
+        println!("Report settings");
+        Ok(Self {
+            min_hours: Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Enter the minimum working hours required to send a report")
+                .default(config.min_hours)
+                .interact_text()?,
+            morning_summary: Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt("Show yesterday's summary on the first command of the day?")
+                .default(config.morning_summary)
+                .interact()?,
+        })
+    }
+
+    pub fn min_duration(&self) -> Duration {
+        Duration::minutes((self.min_hours * 60.0).round() as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{memory_db, Workday};
+    use chrono::NaiveTime;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 8, day).unwrap()
+    }
+
+    fn workday(day: u32) -> Workday {
+        Workday::new(date(day), NaiveTime::from_hms_opt(9, 0, 0).unwrap()).until(NaiveTime::from_hms_opt(17, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn nothing_is_pending_before_the_first_submission() {
+        let conn = memory_db().unwrap();
+        workday(5).insert(&conn).unwrap();
+        workday(6).insert(&conn).unwrap();
+
+        assert!(pending_dates(date(10)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn days_since_the_first_submission_without_a_successful_one_are_pending() {
+        let conn = memory_db().unwrap();
+        for day in [2, 5, 6, 7, 8, 10] {
+            workday(day).insert(&conn).unwrap();
+        }
+        let mut reports = Reports::with_connection(conn.clone());
+        reports.insert(&date(5), "[]", 200).unwrap();
+        reports.insert(&date(7), "[]", 500).unwrap();
+
+        assert_eq!(pending_dates(date(10)).unwrap(), vec![date(6), date(7), date(8)]);
+    }
+
+    #[test]
+    fn only_the_pending_window_is_checked() {
+        let conn = memory_db().unwrap();
+        let today = date(1) + Duration::days(PENDING_DAYS + 5);
+        workday(2).insert(&conn).unwrap();
+        workday(20).insert(&conn).unwrap();
+        Reports::with_connection(conn.clone()).insert(&date(1), "[]", 200).unwrap();
+
+        assert_eq!(pending_dates(today).unwrap(), vec![date(20)]);
+    }
+}
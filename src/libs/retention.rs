@@ -0,0 +1,133 @@
+use super::{
+    config::ConfigModule,
+    event::{Event, EventGroup},
+};
+use chrono::{Datelike, Months, NaiveDate};
+use dialoguer::{theme::ColorfulTheme, Input};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, error::Error};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RetentionConfig {
+    pub raw_months: u32,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self { raw_months: 12 }
+    }
+}
+
+impl RetentionConfig {
+    pub fn module() -> ConfigModule {
+        ConfigModule {
+            key: "retention".to_string(),
+            name: "Data retention".to_string(),
+        }
+    }
+
+    pub fn init(config: &Option<Self>) -> Result<Self, Box<dyn Error>> {
+        let config = config.clone().unwrap_or_default();
+        println!("Data retention settings");
+        Ok(Self {
+            raw_months: Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Enter the number of months to keep individual work intervals")
+                .default(config.raw_months)
+                .interact_text()?,
+        })
+    }
+
+    /// First day whose work intervals are kept as recorded: the start of the month `raw_months` before `today`.
+    pub fn cutoff(&self, today: NaiveDate) -> NaiveDate {
+        let month_start = today.with_day(1).unwrap_or(today);
+        month_start.checked_sub_months(Months::new(self.raw_months)).unwrap_or(month_start)
+    }
+}
+
+/// Collapses every closed day before `cutoff` that has more than one interval into a single interval
+/// starting at the first start and lasting the day's net working time. The time is counted the way reports
+/// and summaries count it, with short pauses merged, so daily and monthly totals stay the same.
+pub fn compact(events: &[Event], cutoff: NaiveDate) -> BTreeMap<NaiveDate, Vec<Event>> {
+    let mut days: BTreeMap<NaiveDate, Vec<&Event>> = BTreeMap::new();
+    for event in events.iter().filter(|event| event.start.date() < cutoff) {
+        days.entry(event.start.date()).or_default().push(event);
+    }
+
+    days.into_iter()
+        .filter(|(_, day_events)| day_events.len() > 1 && day_events.iter().all(|event| event.end.is_some()))
+        .map(|(date, day_events)| {
+            let mut day_events: Vec<Event> = day_events.into_iter().cloned().collect();
+            day_events.sort_by_key(|event| event.start);
+            let start = day_events[0].start;
+            let (_, total) = day_events.merge().update_duration().total_duration();
+            let compacted = Event {
+                id: 0,
+                start,
+                end: Some(start + total),
+                duration: None,
+            };
+            (date, vec![compacted])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, NaiveDateTime};
+
+    fn at(day: u32, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("2024-03-{:02} {}", day, time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn event(day: u32, start: &str, end: Option<&str>) -> Event {
+        Event {
+            id: 0,
+            start: at(day, start),
+            end: end.map(|end| at(day, end)),
+            duration: None,
+        }
+    }
+
+    fn total(events: Vec<Event>) -> Duration {
+        events.merge().update_duration().total_duration().1
+    }
+
+    #[test]
+    fn cutoff_is_the_month_start_raw_months_back() {
+        let config = RetentionConfig { raw_months: 12 };
+
+        assert_eq!(
+            config.cutoff(NaiveDate::from_ymd_opt(2024, 3, 15).unwrap()),
+            NaiveDate::from_ymd_opt(2023, 3, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn compacted_day_keeps_the_reported_total() {
+        let events = vec![
+            event(4, "13:00", Some("17:00")),
+            event(4, "09:00", Some("12:00")),
+            event(4, "12:10", Some("12:40")),
+        ];
+        let days = compact(&events, NaiveDate::from_ymd_opt(2024, 3, 5).unwrap());
+        let compacted = days[&NaiveDate::from_ymd_opt(2024, 3, 4).unwrap()].clone();
+
+        assert_eq!(compacted.len(), 1);
+        assert_eq!(compacted[0].start, at(4, "09:00"));
+        assert_eq!(total(compacted), total(events));
+    }
+
+    #[test]
+    fn days_on_or_after_the_cutoff_open_or_single_are_kept() {
+        let events = vec![
+            event(1, "09:00", Some("17:00")),
+            event(2, "09:00", Some("12:00")),
+            event(2, "13:00", None),
+            event(5, "09:00", Some("12:00")),
+            event(5, "13:00", Some("17:00")),
+        ];
+
+        assert!(compact(&events, NaiveDate::from_ymd_opt(2024, 3, 5).unwrap()).is_empty());
+    }
+}
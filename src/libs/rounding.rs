@@ -0,0 +1,228 @@
+use super::{config::ConfigModule, event::Event};
+use chrono::Duration;
+use dialoguer::{theme::ColorfulTheme, Input, Select};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundingDirection {
+    Up,
+    Down,
+    Nearest,
+}
+
+/// Whether every work interval is rounded or only the total of the day.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundingScope {
+    Interval,
+    Day,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RoundingConfig {
+    pub minutes: i64,
+    pub direction: RoundingDirection,
+    pub scope: RoundingScope,
+}
+
+impl RoundingConfig {
+    pub fn module() -> ConfigModule {
+        ConfigModule {
+            key: "rounding".to_string(),
+            name: "Time rounding".to_string(),
+        }
+    }
+
+    pub fn init(config: &Option<Self>) -> Result<Self, Box<dyn Error>> {
+        let config = config.clone().unwrap_or(Self {
+            minutes: 15,
+            direction: RoundingDirection::Nearest,
+            scope: RoundingScope::Interval,
+        });
+        let directions = [RoundingDirection::Up, RoundingDirection::Down, RoundingDirection::Nearest];
+        let scopes = [RoundingScope::Interval, RoundingScope::Day];
+        println!("Time rounding settings");
+        let minutes = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Enter the rounding increment in minutes, e.g. 15 or 30 (0 to disable)")
+            .default(config.minutes)
+            .interact_text()?;
+        let direction = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select the rounding direction")
+            .items(&["Up", "Down", "Nearest"])
+            .default(directions.iter().position(|direction| *direction == config.direction).unwrap_or(2))
+            .interact()?;
+        let scope = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select what is rounded")
+            .items(&["Every work interval", "The daily total"])
+            .default(scopes.iter().position(|scope| *scope == config.scope).unwrap_or(0))
+            .interact()?;
+        Ok(Self {
+            minutes,
+            direction: directions[direction],
+            scope: scopes[scope],
+        })
+    }
+
+    /// Rounds `duration` to the configured increment.
+    pub fn round(&self, duration: Duration) -> Duration {
+        let increment = self.minutes * 60;
+        if increment <= 0 {
+            return duration;
+        }
+        let seconds = duration.num_seconds();
+        let rounded = match self.direction {
+            RoundingDirection::Up => (seconds + increment - 1).div_euclid(increment),
+            RoundingDirection::Down => seconds.div_euclid(increment),
+            RoundingDirection::Nearest => (seconds + increment / 2).div_euclid(increment),
+        };
+
+        Duration::seconds(rounded * increment)
+    }
+
+    /// Rounds the merged intervals of a day, ordered by start, by moving their ends; start times and the stored events
+    /// stay as recorded. With the interval scope an end rounded up never passes the start of the next interval, and
+    /// intervals rounded down to nothing are left out. With the day scope only the last interval changes, by the
+    /// difference between the rounded and the raw total.
+    pub fn apply(&self, (mut events, total): (Vec<Event>, Duration)) -> (Vec<Event>, Duration) {
+        match self.scope {
+            RoundingScope::Interval => {
+                let next_starts: Vec<_> = events.iter().skip(1).map(|event| Some(event.start)).chain([None]).collect();
+                for (event, next_start) in events.iter_mut().zip(next_starts) {
+                    if let Some(duration) = event.duration {
+                        let end = event.start + self.round(duration);
+                        let end = next_start.map_or(end, |next_start| end.min(next_start.max(event.start)));
+                        event.end = Some(end);
+                        event.duration = Some(end - event.start);
+                    }
+                }
+                events.retain(|event| event.duration != Some(Duration::zero()));
+            }
+            RoundingScope::Day => {
+                let difference = self.round(total) - total;
+                if let Some(event) = events.last_mut() {
+                    if let Some(duration) = event.duration {
+                        let duration = (duration + difference).max(Duration::zero());
+                        event.end = Some(event.start + duration);
+                        event.duration = Some(duration);
+                    }
+                }
+            }
+        }
+        let total = events
+            .iter()
+            .filter_map(|event| event.duration)
+            .fold(Duration::zero(), |total, duration| total + duration);
+
+        (events, total)
+    }
+
+    pub fn describe(&self) -> String {
+        let direction = match self.direction {
+            RoundingDirection::Up => "up to a multiple of",
+            RoundingDirection::Down => "down to a multiple of",
+            RoundingDirection::Nearest => "to the nearest multiple of",
+        };
+        let scope = match self.scope {
+            RoundingScope::Interval => "every interval",
+            RoundingScope::Day => "the daily total",
+        };
+        format!("Durations are rounded {} {} minutes for {}", direction, self.minutes, scope)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    fn config(direction: RoundingDirection, scope: RoundingScope) -> RoundingConfig {
+        RoundingConfig { minutes: 15, direction, scope }
+    }
+
+    fn at(time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("2024-03-04 {}", time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn day(intervals: &[(&str, &str)]) -> (Vec<Event>, Duration) {
+        let events: Vec<Event> = intervals
+            .iter()
+            .map(|(start, end)| Event {
+                id: 0,
+                start: at(start),
+                end: Some(at(end)),
+                duration: Some(at(end) - at(start)),
+            })
+            .collect();
+        let total = events
+            .iter()
+            .filter_map(|event| event.duration)
+            .fold(Duration::zero(), |total, duration| total + duration);
+
+        (events, total)
+    }
+
+    fn ends(events: &[Event]) -> Vec<NaiveDateTime> {
+        events.iter().filter_map(|event| event.end).collect()
+    }
+
+    #[test]
+    fn round_up() {
+        let rounding = config(RoundingDirection::Up, RoundingScope::Interval);
+
+        assert_eq!(rounding.round(Duration::minutes(61)), Duration::minutes(75));
+        assert_eq!(rounding.round(Duration::minutes(60)), Duration::minutes(60));
+        assert_eq!(rounding.round(Duration::zero()), Duration::zero());
+    }
+
+    #[test]
+    fn round_down() {
+        let rounding = config(RoundingDirection::Down, RoundingScope::Interval);
+
+        assert_eq!(rounding.round(Duration::minutes(74)), Duration::minutes(60));
+        assert_eq!(rounding.round(Duration::minutes(14)), Duration::zero());
+    }
+
+    #[test]
+    fn round_nearest() {
+        let rounding = config(RoundingDirection::Nearest, RoundingScope::Interval);
+
+        assert_eq!(rounding.round(Duration::minutes(67)), Duration::minutes(60));
+        assert_eq!(rounding.round(Duration::seconds(67 * 60 + 30)), Duration::minutes(75));
+    }
+
+    #[test]
+    fn zero_increment_keeps_the_duration() {
+        let rounding = RoundingConfig {
+            minutes: 0,
+            ..config(RoundingDirection::Up, RoundingScope::Interval)
+        };
+
+        assert_eq!(rounding.round(Duration::minutes(7)), Duration::minutes(7));
+    }
+
+    #[test]
+    fn rounded_up_interval_stops_at_the_next_start() {
+        let (events, total) = config(RoundingDirection::Up, RoundingScope::Interval).apply(day(&[("09:00", "10:01"), ("10:10", "11:00")]));
+
+        assert_eq!(ends(&events), vec![at("10:10"), at("11:10")]);
+        assert_eq!(total, Duration::minutes(130));
+    }
+
+    #[test]
+    fn intervals_rounded_to_nothing_are_left_out() {
+        let (events, total) = config(RoundingDirection::Down, RoundingScope::Interval).apply(day(&[("09:00", "09:10"), ("10:00", "12:05")]));
+
+        assert_eq!(ends(&events), vec![at("12:00")]);
+        assert_eq!(total, Duration::minutes(120));
+    }
+
+    #[test]
+    fn day_scope_moves_only_the_last_end() {
+        let (events, total) = config(RoundingDirection::Up, RoundingScope::Day).apply(day(&[("09:00", "09:10"), ("10:00", "12:01")]));
+
+        assert_eq!(ends(&events), vec![at("09:10"), at("12:05")]);
+        assert_eq!(total, Duration::minutes(135));
+    }
+}
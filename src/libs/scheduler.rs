@@ -47,6 +47,20 @@ impl Scheduler {
         Ok(())
     }
 
+    /// Checks that every autostart task exists and runs the current executable.
+    pub fn verify() -> Result<bool> {
+        let current_exe_path = env::current_exe().unwrap();
+        let current_dir_path = current_exe_path.parent().unwrap().to_str().unwrap();
+        for name in ["kasl boot", "kasl start", "kasl end"] {
+            match Task::working_dir(r"\", name) {
+                Ok(working_dir) if working_dir == current_dir_path => {}
+                _ => return Ok(false),
+            }
+        }
+
+        Ok(true)
+    }
+
     pub fn delete() -> Result<()> {
         Task::delete_task(r"\", "kasl boot")?;
         Task::delete_task(r"\", "kasl start")?;
@@ -171,6 +185,18 @@ impl Task {
         Ok(self)
     }
 
+    pub fn working_dir(path: &str, name: &str) -> Result<String> {
+        unsafe {
+            let task_service = Self::get_task_service()?;
+            let folder = task_service.GetFolder(&BSTR::from(path))?;
+            let registered_task = folder.GetTask(&BSTR::from(name))?;
+            let exec_action: IExecAction = registered_task.Definition()?.Actions()?.get_Item(1)?.cast()?;
+            let mut working_dir = BSTR::new();
+            exec_action.WorkingDirectory(&mut working_dir)?;
+            Ok(working_dir.to_string())
+        }
+    }
+
     pub fn delete_task(path: &str, name: &str) -> Result<()> {
         unsafe {
             let task_service = Self::get_task_service()?;
@@ -1,4 +1,4 @@
-use super::data_storage::DataStorage;
+use super::{data_storage::DataStorage, interactive};
 use aes::Aes256;
 use base64::prelude::*;
 use block_modes::block_padding::Pkcs7;
@@ -13,6 +13,14 @@ use std::path::PathBuf;
 
 type Aes256Cbc = Cbc<Aes256, Pkcs7>;
 
+/// A stored credential as shown by `kasl secret list`, with the value already masked.
+#[derive(Debug)]
+pub struct SecretStatus {
+    pub service: String,
+    pub name: String,
+    pub value: String,
+}
+
 #[derive(Clone, Debug)]
 pub struct Secret {
     password: Option<String>,
@@ -55,11 +63,27 @@ impl Secret {
     }
 
     pub fn prompt(&self) -> Result<String, Box<dyn Error>> {
+        if interactive::is_disabled() {
+            let name = self.secret_file_path.file_name().unwrap_or_default().to_string_lossy();
+            return Err(interactive::required(&format!("The secret {}", name), "store it first with `kasl secret set`"));
+        }
         let password = Password::with_theme(&ColorfulTheme::default()).with_prompt(&self.prompt).interact().unwrap();
         self.set_password(&password).encrypt()?;
         Ok(password)
     }
 
+    /// Encrypts and stores `value` without prompting, e.g. for tokens obtained by `oauth`.
+    pub fn set(&self, value: &str) -> Result<(), Box<dyn Error>> {
+        self.set_password(value).encrypt()?;
+
+        Ok(())
+    }
+
+    /// Stored value, `None` when nothing is stored or it cannot be decrypted.
+    pub fn get(&self) -> Option<String> {
+        self.decrypt().ok()
+    }
+
     fn encrypt(&self) -> Result<Self, Box<dyn Error>> {
         let cipher = Aes256Cbc::new_from_slices(&self.key, &self.iv)?;
         let password = &self.password.clone().unwrap();
@@ -83,3 +107,13 @@ impl Secret {
         Ok(decrypted_password)
     }
 }
+
+/// Keeps only the first and last two characters of longer values, e.g. `ab****yz`.
+pub fn mask(value: &str) -> String {
+    let chars: Vec<char> = value.trim().chars().collect();
+    match chars.len() {
+        0 => "-".to_string(),
+        1..=8 => "*".repeat(chars.len()),
+        len => format!("{}****{}", chars[..2].iter().collect::<String>(), chars[len - 2..].iter().collect::<String>()),
+    }
+}
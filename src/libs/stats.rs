@@ -0,0 +1,294 @@
+use super::event::{Event, EventGroup};
+use chrono::{Datelike, Duration, DurationRound, Local, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+#[derive(Debug)]
+pub struct HourlyActivity {
+    pub buckets: [Duration; 24],
+    pub days: i64,
+}
+
+impl HourlyActivity {
+    /// Spreads every event interval over the hours of the day it covers.
+    pub fn new(events: &[Event], days: i64) -> Self {
+        let mut buckets = [Duration::zero(); 24];
+        let now = Local::now().naive_local();
+        for event in events {
+            let end = event.end.unwrap_or(now.min(event.start.date().and_hms_opt(23, 59, 59).unwrap()));
+            let mut cursor: NaiveDateTime = event.start;
+            while cursor < end {
+                let next_hour = cursor.duration_trunc(Duration::hours(1)).unwrap() + Duration::hours(1);
+                let chunk_end = next_hour.min(end);
+                buckets[cursor.hour() as usize] += chunk_end - cursor;
+                cursor = chunk_end;
+            }
+        }
+
+        Self { buckets, days: days.max(1) }
+    }
+
+    pub fn average(&self, hour: usize) -> Duration {
+        self.buckets[hour] / self.days as i32
+    }
+
+    pub fn save_csv(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut file = File::create(path)?;
+        writeln!(file, "hour,total_minutes,average_minutes")?;
+        for hour in 0..24 {
+            writeln!(file, "{:02},{},{}", hour, self.buckets[hour].num_minutes(), self.average(hour).num_minutes())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct DayStats {
+    pub date: NaiveDate,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub duration: Duration,
+    pub pauses: Vec<Duration>,
+}
+
+#[derive(Debug)]
+pub struct WorkStats {
+    pub days: Vec<DayStats>,
+}
+
+impl WorkStats {
+    /// Builds per-day statistics; pauses are the gaps left between merged events.
+    pub fn new(events: Vec<Event>) -> Self {
+        let mut days: Vec<DayStats> = events
+            .group_events()
+            .into_iter()
+            .filter_map(|(date, mut day_events)| {
+                day_events.sort_by_key(|event| event.start);
+                let merged = day_events.merge().update_duration();
+                let first = merged.first()?;
+                let last = merged.last()?;
+                let pauses = merged
+                    .windows(2)
+                    .filter_map(|pair| pair[0].end.map(|end| pair[1].start - end))
+                    .filter(|pause| *pause > Duration::zero())
+                    .collect();
+                Some(DayStats {
+                    date,
+                    start: first.start.time(),
+                    end: last.end.unwrap_or(last.start).time(),
+                    duration: merged.iter().filter_map(|event| event.duration).fold(Duration::zero(), |total, d| total + d),
+                    pauses,
+                })
+            })
+            .collect();
+        days.sort_by_key(|day| day.date);
+
+        Self { days }
+    }
+
+    fn average_time(times: impl Iterator<Item = NaiveTime>) -> Option<NaiveTime> {
+        let seconds: Vec<u32> = times.map(|time| time.num_seconds_from_midnight()).collect();
+        match seconds.is_empty() {
+            true => None,
+            false => NaiveTime::from_num_seconds_from_midnight_opt(seconds.iter().sum::<u32>() / seconds.len() as u32, 0),
+        }
+    }
+
+    pub fn average_start(&self) -> Option<NaiveTime> {
+        Self::average_time(self.days.iter().map(|day| day.start))
+    }
+
+    pub fn average_end(&self) -> Option<NaiveTime> {
+        Self::average_time(self.days.iter().map(|day| day.end))
+    }
+
+    pub fn total_duration(&self) -> Duration {
+        self.days.iter().fold(Duration::zero(), |total, day| total + day.duration)
+    }
+
+    pub fn average_duration(&self) -> Duration {
+        match self.days.is_empty() {
+            true => Duration::zero(),
+            false => self.total_duration() / self.days.len() as i32,
+        }
+    }
+
+    pub fn average_pause_count(&self) -> f64 {
+        match self.days.is_empty() {
+            true => 0.0,
+            false => self.days.iter().map(|day| day.pauses.len()).sum::<usize>() as f64 / self.days.len() as f64,
+        }
+    }
+
+    pub fn pause_count(&self) -> usize {
+        self.days.iter().map(|day| day.pauses.len()).sum()
+    }
+
+    pub fn pause_length(&self) -> Duration {
+        self.days
+            .iter()
+            .flat_map(|day| day.pauses.iter())
+            .fold(Duration::zero(), |total, pause| total + *pause)
+    }
+
+    /// Share of the time between the first start and the last end of each day spent working.
+    pub fn productivity(&self) -> Option<f64> {
+        let span = self.days.iter().fold(Duration::zero(), |total, day| total + (day.end - day.start));
+        match span > Duration::zero() {
+            true => Some((self.total_duration().num_seconds() as f64 / span.num_seconds() as f64 * 100.0).min(100.0)),
+            false => None,
+        }
+    }
+
+    pub fn average_pause_length(&self) -> Duration {
+        let pauses: Vec<&Duration> = self.days.iter().flat_map(|day| day.pauses.iter()).collect();
+        match pauses.is_empty() {
+            true => Duration::zero(),
+            false => pauses.iter().fold(Duration::zero(), |total, pause| total + **pause) / pauses.len() as i32,
+        }
+    }
+
+    /// Weekday with the highest average working time.
+    pub fn most_productive_weekday(&self) -> Option<(Weekday, Duration)> {
+        let mut weekdays: HashMap<Weekday, Vec<Duration>> = HashMap::new();
+        for day in self.days.iter() {
+            weekdays.entry(day.date.weekday()).or_default().push(day.duration);
+        }
+        weekdays
+            .into_iter()
+            .map(|(weekday, durations)| {
+                let total = durations.iter().fold(Duration::zero(), |total, d| total + *d);
+                (weekday, total / durations.len() as i32)
+            })
+            .max_by_key(|(_, average)| *average)
+    }
+
+    /// Longest run of consecutive working days; weekends without work do not break a streak.
+    pub fn longest_streak(&self) -> usize {
+        let next_workday = |date: NaiveDate| {
+            let mut next = date + Duration::days(1);
+            while matches!(next.weekday(), Weekday::Sat | Weekday::Sun) {
+                next += Duration::days(1);
+            }
+            next
+        };
+        let mut longest = 0;
+        let mut current = 0;
+        let mut previous: Option<NaiveDate> = None;
+        for day in self.days.iter() {
+            current = match previous {
+                Some(date) if day.date == date + Duration::days(1) || day.date == next_workday(date) => current + 1,
+                _ => 1,
+            };
+            longest = longest.max(current);
+            previous = Some(day.date);
+        }
+
+        longest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn time(time: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(time, "%H:%M").unwrap()
+    }
+
+    fn event(start: &str, end: &str) -> Event {
+        Event {
+            id: 0,
+            start: at(start),
+            end: Some(at(end)),
+            duration: None,
+        }
+    }
+
+    /// Monday to Wednesday and the following Monday; Tuesday has a one-hour lunch and a short gap that is merged.
+    fn stats() -> WorkStats {
+        WorkStats::new(vec![
+            event("2024-03-04 09:00", "2024-03-04 17:00"),
+            event("2024-03-05 08:00", "2024-03-05 12:00"),
+            event("2024-03-05 12:10", "2024-03-05 12:30"),
+            event("2024-03-05 13:30", "2024-03-05 18:00"),
+            event("2024-03-06 10:00", "2024-03-06 14:00"),
+            event("2024-03-11 09:00", "2024-03-11 15:00"),
+        ])
+    }
+
+    #[test]
+    fn days_are_built_from_merged_events() {
+        let stats = stats();
+        let tuesday = &stats.days[1];
+
+        assert_eq!(stats.days.len(), 4);
+        assert_eq!((tuesday.start, tuesday.end), (time("08:00"), time("18:00")));
+        assert_eq!(tuesday.duration, Duration::minutes(540));
+        assert_eq!(tuesday.pauses, vec![Duration::hours(1)]);
+    }
+
+    #[test]
+    fn averages() {
+        let stats = stats();
+
+        assert_eq!(stats.total_duration(), Duration::hours(27));
+        assert_eq!(stats.average_duration(), Duration::minutes(405));
+        assert_eq!(stats.average_start(), Some(time("09:00")));
+        assert_eq!(stats.average_end(), Some(time("16:00")));
+        assert_eq!((stats.pause_count(), stats.pause_length()), (1, Duration::hours(1)));
+        assert_eq!(stats.average_pause_count(), 0.25);
+        assert_eq!(stats.average_pause_length(), Duration::hours(1));
+    }
+
+    #[test]
+    fn productivity_is_work_over_the_daily_span() {
+        assert_eq!(stats().productivity().map(|productivity| productivity.round()), Some(96.0));
+    }
+
+    #[test]
+    fn most_productive_weekday_has_the_highest_average() {
+        assert_eq!(stats().most_productive_weekday(), Some((Weekday::Tue, Duration::hours(9))));
+    }
+
+    #[test]
+    fn weekends_do_not_break_a_streak() {
+        let stats = WorkStats::new(vec![
+            event("2024-03-07 09:00", "2024-03-07 17:00"),
+            event("2024-03-08 09:00", "2024-03-08 17:00"),
+            event("2024-03-11 09:00", "2024-03-11 17:00"),
+            event("2024-03-13 09:00", "2024-03-13 17:00"),
+        ]);
+
+        assert_eq!(stats.longest_streak(), 3);
+    }
+
+    #[test]
+    fn empty_stats() {
+        let stats = WorkStats::new(vec![]);
+
+        assert_eq!(
+            (stats.average_duration(), stats.average_start(), stats.productivity()),
+            (Duration::zero(), None, None)
+        );
+        assert_eq!(stats.longest_streak(), 0);
+    }
+
+    #[test]
+    fn hourly_activity_splits_intervals_at_full_hours() {
+        let activity = HourlyActivity::new(&[event("2024-03-04 09:30", "2024-03-04 11:15")], 2);
+
+        assert_eq!(activity.buckets[9], Duration::minutes(30));
+        assert_eq!(activity.buckets[10], Duration::hours(1));
+        assert_eq!(activity.buckets[11], Duration::minutes(15));
+        assert_eq!(activity.average(10), Duration::minutes(30));
+    }
+}
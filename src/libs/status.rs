@@ -0,0 +1,85 @@
+use super::{
+    display::DisplayConfig,
+    event::{Event, EventGroup, FormatEvent},
+    stats::WorkStats,
+};
+use chrono::{Duration, Local, NaiveTime};
+
+#[derive(Debug)]
+pub struct Status {
+    pub active: bool,
+    pub start: Option<NaiveTime>,
+    pub duration: Duration,
+    pub pauses: Vec<Duration>,
+    pub since_break: Option<Duration>,
+    pub incomplete_tasks: usize,
+}
+
+impl Status {
+    /// Builds today's snapshot; the workday is active while the last event is still open.
+    pub fn new(events: Vec<Event>, incomplete_tasks: usize) -> Self {
+        let active = events.last().is_some_and(|event| event.end.is_none());
+        let since_break = match active {
+            true => events.clone().merge().last().map(|event| Local::now().naive_local() - event.start),
+            false => None,
+        };
+        let day = WorkStats::new(events).days.pop();
+
+        Self {
+            active,
+            start: day.as_ref().map(|day| day.start),
+            duration: day.as_ref().map_or(Duration::zero(), |day| day.duration),
+            pauses: day.map_or(vec![], |day| day.pauses),
+            since_break,
+            incomplete_tasks,
+        }
+    }
+
+    pub fn pause_duration(&self) -> Duration {
+        self.pauses.iter().fold(Duration::zero(), |total, pause| total + *pause)
+    }
+
+    /// Clock-out time that reaches `target` when work goes on from now without further pauses;
+    /// `None` before the workday starts and once the target is reached. Every pause pushes it back.
+    pub fn projected_end(&self, target: Duration) -> Option<NaiveTime> {
+        self.start?;
+        let remaining = target - self.duration;
+
+        (remaining > Duration::zero()).then(|| Local::now().time() + remaining)
+    }
+
+    /// The projection as shown by `kasl status`, e.g. `17:15 (+00:45 pauses)`.
+    pub fn forecast(&self, target: Duration) -> String {
+        match (self.start, self.projected_end(target)) {
+            (None, _) => "-".to_string(),
+            (Some(_), None) => "target reached".to_string(),
+            (Some(_), Some(end)) if self.pauses.is_empty() => end.format("%H:%M").to_string(),
+            (Some(_), Some(end)) => format!(
+                "{} (+{} pauses)",
+                end.format("%H:%M"),
+                FormatEvent::format_duration(Some(self.pause_duration()))
+            ),
+        }
+    }
+
+    /// Share of the time since the workday start that was spent working.
+    pub fn productivity(&self) -> Option<f64> {
+        let elapsed = Local::now().time() - self.start?;
+        match elapsed > Duration::zero() {
+            true => Some((self.duration.num_seconds() as f64 / elapsed.num_seconds() as f64 * 100.0).min(100.0)),
+            false => None,
+        }
+    }
+
+    pub fn state(&self) -> &str {
+        match (self.active, self.start) {
+            (true, _) => "working",
+            (false, Some(_)) => "away",
+            (false, None) => "not started",
+        }
+    }
+
+    pub fn icon(&self, display: &DisplayConfig) -> &str {
+        display.icon(self.active, self.start.is_some())
+    }
+}
@@ -0,0 +1,411 @@
+use super::{
+    config::Config,
+    data_storage::DataStorage,
+    date,
+    event::{Event, EventGroup, EventGroupDuration, FormatEvent},
+    project::Project,
+    rate::RateConfig,
+    report,
+    stats::WorkStats,
+    task::{Task, TaskFilter},
+};
+use crate::db::{events::Events, tasks::Tasks};
+use chrono::{Datelike, Duration, Local, Months, NaiveDate, Weekday};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs;
+
+const LAST_SUMMARY_FILE: &str = ".last_morning_summary";
+const LOOKBACK_DAYS: i64 = 7;
+
+pub struct MorningSummary {}
+
+impl MorningSummary {
+    /// Once a day, on the first command, prints yesterday's carry-over when enabled in the report config,
+    /// otherwise only warns about unsent reports when SiServer is configured.
+    pub fn show() {
+        let Ok(config) = Config::read() else {
+            return;
+        };
+        let enabled = config.report.is_some_and(|report| report.morning_summary);
+        if !enabled && config.si.is_none() {
+            return;
+        }
+        let today = Local::now().date_naive();
+        let Ok(last_summary_file) = DataStorage::new().get_path(LAST_SUMMARY_FILE) else {
+            return;
+        };
+        if fs::read_to_string(&last_summary_file).is_ok_and(|date| date.trim() == today.format("%Y-%m-%d").to_string()) {
+            return;
+        }
+        let summary = match enabled {
+            true => Self::summary(today).map(Some),
+            false => Self::pending_reports(today),
+        };
+        if let Ok(Some(summary)) = summary {
+            println!("\n{}\n", summary);
+        }
+        let _ = fs::write(&last_summary_file, today.format("%Y-%m-%d").to_string());
+    }
+
+    fn pending_reports(today: NaiveDate) -> Result<Option<String>, Box<dyn Error>> {
+        let pending_dates = report::pending_dates(today)?;
+        match pending_dates.is_empty() {
+            true => Ok(None),
+            false => Ok(Some(format!(
+                "Reports not submitted: {} (run `kasl report --pending` to list them)",
+                pending_dates.len()
+            ))),
+        }
+    }
+
+    fn summary(today: NaiveDate) -> Result<String, Box<dyn Error>> {
+        let events = Events::new()?.fetch_range(today - Duration::days(LOOKBACK_DAYS), today - Duration::days(1))?;
+        let incomplete_tasks = Tasks::new()?.fetch(TaskFilter::Incomplete)?;
+        let mut summary = "Good morning!".to_string();
+        if let Some(last_date) = events.iter().map(|event| event.start.date()).max() {
+            let (_, duration) = events
+                .into_iter()
+                .filter(|event| event.start.date() == last_date)
+                .collect::<Vec<_>>()
+                .merge()
+                .update_duration()
+                .total_duration();
+            summary += &format!(
+                "\nLast workday ({}): {} worked",
+                last_date.format("%B %-d"),
+                FormatEvent::format_duration(Some(duration))
+            );
+        }
+        if let Some(pending_reports) = Self::pending_reports(today)? {
+            summary += &format!("\n{}", pending_reports);
+        }
+        if !incomplete_tasks.is_empty() {
+            summary += &format!("\nIncomplete tasks carried over: {}", incomplete_tasks.len());
+            for task in incomplete_tasks.iter() {
+                summary += &format!("\n  - {} ({}%)", task.name, task.completeness.unwrap_or(0));
+            }
+        }
+
+        Ok(summary)
+    }
+}
+
+/// Working time attributed to one project within a period.
+#[derive(Debug)]
+pub struct ProjectTotal {
+    pub name: String,
+    pub client: Option<String>,
+    pub duration: Duration,
+    pub amount: Option<String>,
+}
+
+/// Events and net working time per day, plus a running total.
+pub type PeriodDurations = (HashMap<NaiveDate, (Vec<Event>, Duration)>, Duration);
+
+/// A calendar span that `kasl sum` aggregates working hours over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Month(NaiveDate),
+    Week(NaiveDate),
+    Quarter(NaiveDate),
+    Year(NaiveDate),
+    /// An explicit span of days, both ends included.
+    Range(NaiveDate, NaiveDate),
+}
+
+/// Working time of one month within a longer period.
+#[derive(Debug)]
+pub struct MonthTotal {
+    pub month: NaiveDate,
+    pub days: usize,
+    pub duration: Duration,
+}
+
+impl Period {
+    pub fn month(date: NaiveDate) -> Self {
+        Self::Month(date.with_day(1).unwrap_or(date))
+    }
+
+    /// ISO week containing `date`.
+    pub fn week(date: NaiveDate) -> Self {
+        Self::Week(date - Duration::days(date.weekday().num_days_from_monday() as i64))
+    }
+
+    /// ISO week `week` of `year`, if that week exists.
+    pub fn iso_week(year: i32, week: u32) -> Option<Self> {
+        NaiveDate::from_isoywd_opt(year, week, Weekday::Mon).map(Self::Week)
+    }
+
+    /// Quarter `quarter` (1-4) of `year`.
+    pub fn quarter_of(year: i32, quarter: u32) -> Option<Self> {
+        match quarter {
+            1..=4 => NaiveDate::from_ymd_opt(year, (quarter - 1) * 3 + 1, 1).map(Self::Quarter),
+            _ => None,
+        }
+    }
+
+    pub fn year_of(year: i32) -> Option<Self> {
+        NaiveDate::from_ymd_opt(year, 1, 1).map(Self::Year)
+    }
+
+    /// Parses a `FROM..TO` range of dates in any form `date::parse` accepts, e.g. `last monday..yesterday`.
+    pub fn range(spec: &str, today: NaiveDate) -> Option<Self> {
+        let (from, to) = spec.split_once("..")?;
+        let from = date::parse(from, today).ok()?;
+        let to = date::parse(to, today).ok()?;
+        (from <= to).then_some(Self::Range(from, to))
+    }
+
+    pub fn first_day(&self) -> NaiveDate {
+        match *self {
+            Self::Month(first_day) | Self::Week(first_day) | Self::Quarter(first_day) | Self::Year(first_day) | Self::Range(first_day, _) => first_day,
+        }
+    }
+
+    pub fn last_day(&self) -> NaiveDate {
+        match *self {
+            Self::Month(first_day) => (first_day + Months::new(1)).pred_opt().unwrap_or(first_day),
+            Self::Week(first_day) => first_day + Duration::days(6),
+            Self::Quarter(first_day) => (first_day + Months::new(3)).pred_opt().unwrap_or(first_day),
+            Self::Year(first_day) => (first_day + Months::new(12)).pred_opt().unwrap_or(first_day),
+            Self::Range(_, last_day) => last_day,
+        }
+    }
+
+    /// The period right before this one; for a range, the span of the same length that ends the day before it starts.
+    pub fn previous(&self) -> Self {
+        match *self {
+            Self::Month(first_day) => Self::Month(first_day - Months::new(1)),
+            Self::Week(first_day) => Self::Week(first_day - Duration::weeks(1)),
+            Self::Quarter(first_day) => Self::Quarter(first_day - Months::new(3)),
+            Self::Year(first_day) => Self::Year(first_day - Months::new(12)),
+            Self::Range(first_day, last_day) => Self::Range(first_day - (last_day - first_day) - Duration::days(1), first_day - Duration::days(1)),
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match *self {
+            Self::Month(first_day) => first_day.format("%B, %Y").to_string(),
+            Self::Week(first_day) => format!(
+                "week {}, {} ({} - {})",
+                first_day.iso_week().week(),
+                first_day.iso_week().year(),
+                first_day.format("%B %-d"),
+                self.last_day().format("%B %-d")
+            ),
+            Self::Quarter(first_day) => format!("Q{}, {}", first_day.month0() / 3 + 1, first_day.year()),
+            Self::Year(first_day) => first_day.year().to_string(),
+            Self::Range(first_day, last_day) => format!("{} - {}", first_day.format("%B %-d, %Y"), last_day.format("%B %-d, %Y")),
+        }
+    }
+
+    /// Net working time per day of the period; only finished days, i.e. those before `today`, are counted.
+    pub fn durations(&self, today: NaiveDate) -> Result<PeriodDurations, Box<dyn Error>> {
+        match self.finished_days(today) {
+            Some((from, to)) => Ok(Events::new()?.fetch_range(from, to)?.group_events().calc()),
+            None => Ok((HashMap::new(), Duration::zero())),
+        }
+    }
+
+    /// First and last finished day of the period, if any day of it has passed.
+    pub fn finished_days(&self, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+        let to = self.last_day().min(today - Duration::days(1));
+        (to >= self.first_day()).then_some((self.first_day(), to))
+    }
+}
+
+/// Groups daily working time by month, oldest month first.
+pub fn month_totals((days, _): &PeriodDurations) -> Vec<MonthTotal> {
+    let mut months: BTreeMap<NaiveDate, MonthTotal> = BTreeMap::new();
+    for (date, (_, duration)) in days.iter() {
+        let month = date.with_day(1).unwrap_or(*date);
+        let month_total = months.entry(month).or_insert(MonthTotal {
+            month,
+            days: 0,
+            duration: Duration::zero(),
+        });
+        month_total.days += 1;
+        month_total.duration += *duration;
+    }
+
+    months.into_values().collect()
+}
+
+/// Hourly rates of the tasks recorded on `date`: their project's rate, `None` when it has none.
+pub fn task_rates(date: NaiveDate, tasks: &[Task], projects: &[Project]) -> Vec<Option<f64>> {
+    let date_prefix = date.format("%Y-%m-%d").to_string();
+
+    tasks
+        .iter()
+        .filter(|task| task.timestamp.as_deref().is_some_and(|timestamp| timestamp.starts_with(&date_prefix)))
+        .map(|task| {
+            let project_id = task.project_id?;
+            projects.iter().find(|project| project.id == Some(project_id))?.hourly_rate
+        })
+        .collect()
+}
+
+/// Splits the working time of every day evenly between the tasks recorded that day and sums it per project.
+/// Time of days without tasks and of tasks without a project goes to a `No project` row at the end.
+/// Amounts use the project rate, falling back to the configured one.
+pub fn project_totals((days, _): &PeriodDurations, tasks: &[Task], projects: &[Project], rate: Option<&RateConfig>) -> Vec<ProjectTotal> {
+    let mut durations: HashMap<Option<i32>, Duration> = HashMap::new();
+    for (date, (_, duration)) in days.iter() {
+        let date_prefix = date.format("%Y-%m-%d").to_string();
+        let day_tasks: Vec<&Task> = tasks
+            .iter()
+            .filter(|task| task.timestamp.as_deref().is_some_and(|timestamp| timestamp.starts_with(&date_prefix)))
+            .collect();
+        if day_tasks.is_empty() {
+            *durations.entry(None).or_insert(Duration::zero()) += *duration;
+            continue;
+        }
+        let share = *duration / day_tasks.len() as i32;
+        for task in day_tasks {
+            *durations.entry(task.project_id).or_insert(Duration::zero()) += share;
+        }
+    }
+
+    let amount = |hourly_rate: Option<f64>, duration: Duration| {
+        let hours = duration.num_minutes() as f64 / 60.0;
+        match (hourly_rate, rate) {
+            (Some(hourly_rate), Some(rate)) => Some(rate.format_amount(hours * hourly_rate)),
+            (Some(hourly_rate), None) => Some(format!("{:.2}", hours * hourly_rate)),
+            (None, Some(rate)) => Some(rate.format_amount(hours * rate.hourly)),
+            (None, None) => None,
+        }
+    };
+    let mut project_totals: Vec<ProjectTotal> = projects
+        .iter()
+        .filter_map(|project| {
+            let duration = durations.remove(&project.id)?;
+            Some(ProjectTotal {
+                name: project.name.clone(),
+                client: project.client.clone(),
+                duration,
+                amount: amount(project.hourly_rate, duration),
+            })
+        })
+        .collect();
+    project_totals.sort_by_key(|project_total| std::cmp::Reverse(project_total.duration));
+    let rest = durations.into_values().fold(Duration::zero(), |total, duration| total + duration);
+    if rest > Duration::zero() {
+        project_totals.push(ProjectTotal {
+            name: "No project".to_string(),
+            client: None,
+            duration: rest,
+            amount: amount(None, rest),
+        });
+    }
+
+    project_totals
+}
+
+/// Working statistics and the number of recorded tasks for the finished days of a period.
+#[derive(Debug)]
+pub struct PeriodReport {
+    pub period: Period,
+    pub stats: WorkStats,
+    pub tasks: usize,
+}
+
+impl PeriodReport {
+    pub fn new(period: Period, today: NaiveDate) -> Result<Self, Box<dyn Error>> {
+        let (events, tasks) = match period.finished_days(today) {
+            Some((from, to)) => (Events::new()?.fetch_range(from, to)?, Tasks::new()?.fetch(TaskFilter::Range(from, to))?.len()),
+            None => (vec![], 0),
+        };
+
+        Ok(Self {
+            period,
+            stats: WorkStats::new(events),
+            tasks,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn bounds(period: Period) -> (NaiveDate, NaiveDate) {
+        (period.first_day(), period.last_day())
+    }
+
+    #[test]
+    fn week_53_spans_the_turn_of_the_year() {
+        let week = Period::iso_week(2020, 53).unwrap();
+
+        assert_eq!(bounds(week), (date(2020, 12, 28), date(2021, 1, 3)));
+        assert_eq!(week.title(), "week 53, 2020 (December 28 - January 3)");
+        assert_eq!(week.previous(), Period::iso_week(2020, 52).unwrap());
+        assert_eq!(Period::iso_week(2021, 53), None);
+    }
+
+    #[test]
+    fn january_1_may_fall_in_the_last_week_of_the_previous_iso_year() {
+        assert_eq!(Period::week(date(2021, 1, 1)), Period::iso_week(2020, 53).unwrap());
+        assert_eq!(Period::week(date(2021, 1, 1)).previous(), Period::week(date(2020, 12, 24)));
+        // And December 31 in the first week of the next one.
+        assert_eq!(Period::week(date(2019, 12, 31)), Period::iso_week(2020, 1).unwrap());
+        assert!(Period::week(date(2019, 12, 31)).title().starts_with("week 1, 2020"));
+    }
+
+    #[test]
+    fn months_end_on_their_last_day() {
+        let february = Period::month(date(2024, 3, 31)).previous();
+
+        assert_eq!(bounds(february), (date(2024, 2, 1), date(2024, 2, 29)));
+        assert_eq!(Period::month(date(2024, 1, 15)).previous(), Period::month(date(2023, 12, 1)));
+    }
+
+    #[test]
+    fn quarters_cover_three_months() {
+        assert_eq!(bounds(Period::quarter_of(2024, 1).unwrap()), (date(2024, 1, 1), date(2024, 3, 31)));
+        assert_eq!(bounds(Period::quarter_of(2024, 4).unwrap()), (date(2024, 10, 1), date(2024, 12, 31)));
+        assert_eq!(Period::quarter_of(2024, 1).unwrap().previous(), Period::quarter_of(2023, 4).unwrap());
+        assert_eq!(Period::quarter_of(2024, 3).unwrap().title(), "Q3, 2024");
+        assert_eq!(Period::quarter_of(2024, 0), None);
+        assert_eq!(Period::quarter_of(2024, 5), None);
+    }
+
+    #[test]
+    fn years_cover_the_calendar_year() {
+        let year = Period::year_of(2024).unwrap();
+
+        assert_eq!(bounds(year), (date(2024, 1, 1), date(2024, 12, 31)));
+        assert_eq!(year.previous(), Period::year_of(2023).unwrap());
+    }
+
+    #[test]
+    fn previous_range_has_the_same_length_and_ends_before_it() {
+        let today = date(2024, 3, 14);
+        let range = Period::range("2024-03-04..2024-03-10", today).unwrap();
+
+        assert_eq!(bounds(range.previous()), (date(2024, 2, 26), date(2024, 3, 3)));
+        assert_eq!(
+            bounds(Period::range("today..today", today).unwrap().previous()),
+            (date(2024, 3, 13), date(2024, 3, 13))
+        );
+        assert_eq!(
+            bounds(Period::range("last monday..yesterday", today).unwrap()),
+            (date(2024, 3, 11), date(2024, 3, 13))
+        );
+        assert_eq!(Period::range("2024-03-10..2024-03-04", today), None);
+        assert_eq!(Period::range("2024-03-04", today), None);
+    }
+
+    #[test]
+    fn only_days_before_today_are_finished() {
+        let week = Period::week(date(2024, 3, 6));
+
+        assert_eq!(week.finished_days(date(2024, 3, 6)), Some((date(2024, 3, 4), date(2024, 3, 5))));
+        assert_eq!(week.finished_days(date(2024, 3, 4)), None);
+        assert_eq!(week.finished_days(date(2024, 4, 1)), Some((date(2024, 3, 4), date(2024, 3, 10))));
+    }
+}
@@ -1,6 +1,9 @@
+use super::{diag, error::KaslError};
 use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
     pub id: Option<i32>,
     pub task_id: Option<i32>,
@@ -9,6 +12,24 @@ pub struct Task {
     pub comment: String,
     pub completeness: Option<i32>,
     pub excluded_from_search: Option<bool>,
+    #[serde(default)]
+    pub deleted_at: Option<String>,
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub external_id: Option<String>,
+    #[serde(default)]
+    pub external_url: Option<String>,
+    #[serde(default)]
+    pub project_id: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskNote {
+    pub id: i32,
+    pub task_id: i32,
+    pub timestamp: String,
+    pub text: String,
 }
 
 impl Task {
@@ -21,16 +42,106 @@ impl Task {
             comment: comment.to_string(),
             completeness,
             excluded_from_search: None,
+            deleted_at: None,
+            source: None,
+            external_id: None,
+            external_url: None,
+            project_id: None,
+        }
+    }
+
+    /// Identifier shared by all daily records of the same task.
+    pub fn chain_id(&self) -> Option<i32> {
+        self.task_id.filter(|id| *id != 0).or(self.id)
+    }
+
+    /// Copy for sharing outside the team: the name is replaced with a pseudonym, the comment and external links are dropped.
+    pub fn redacted(&self) -> Self {
+        Self {
+            name: diag::pseudonym("Task", &self.name),
+            comment: String::new(),
+            external_id: None,
+            external_url: None,
+            ..self.clone()
         }
     }
+
+    /// Marks the task as imported from an external system, e.g. a Jira issue key or a GitLab commit SHA.
+    pub fn with_external(mut self, source: &str, external_id: &str, external_url: Option<String>) -> Self {
+        self.source = Some(source.to_string());
+        self.external_id = Some(external_id.to_string());
+        self.external_url = external_url;
+        self
+    }
+}
+
+/// A task captured in one line, e.g. `fix login bug #urgent @backend 50%`: `#` marks tags, `@` the project
+/// and `N%` the completeness; the remaining words form the name.
+#[derive(Debug)]
+pub struct QuickTask {
+    pub name: String,
+    pub tags: Vec<String>,
+    pub project: Option<String>,
+    pub completeness: Option<i32>,
+}
+
+impl QuickTask {
+    pub fn parse(text: &str) -> Result<Self, Box<dyn Error>> {
+        let mut quick_task = Self {
+            name: String::new(),
+            tags: vec![],
+            project: None,
+            completeness: None,
+        };
+        let mut words = vec![];
+        for word in text.split_whitespace() {
+            if let Some(tag) = word.strip_prefix('#').filter(|tag| !tag.is_empty()) {
+                quick_task.tags.push(tag.to_string());
+            } else if let Some(project) = word.strip_prefix('@').filter(|project| !project.is_empty()) {
+                if quick_task.project.replace(project.to_string()).is_some() {
+                    return Err(KaslError::validation(format!(
+                        "`{}` names a second project, a task belongs to one project",
+                        word
+                    )));
+                }
+            } else if let Some(completeness) = word.strip_suffix('%').and_then(|percent| percent.parse::<i32>().ok()) {
+                if !(0..=100).contains(&completeness) {
+                    return Err(KaslError::validation(format!("Completeness {}% is outside 0-100%", completeness)));
+                }
+                quick_task.completeness = Some(completeness);
+            } else {
+                words.push(word);
+            }
+        }
+        if words.is_empty() {
+            return Err(KaslError::validation(
+                "The task name is missing, e.g. `kasl t \"fix login bug #urgent @backend 50%\"`",
+            ));
+        }
+        quick_task.name = words.join(" ");
+
+        Ok(quick_task)
+    }
+
+    /// Tags are kept in the task comment as `#tag`, since tasks have no field of their own for them.
+    pub fn comment(&self) -> String {
+        self.tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>().join(" ")
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum TaskFilter {
     All,
+    Deleted,
     Date(NaiveDate),
+    /// Tasks recorded between two dates, both included.
+    Range(NaiveDate, NaiveDate),
     Incomplete,
     ByIds(Vec<i32>),
+    ByRowIds(Vec<i32>),
+    /// The latest record imported from `source` with the given external ID.
+    ByExternalId(String, String),
+    BySource(String),
 }
 
 pub trait FormatTasks {
@@ -83,3 +194,48 @@ impl FormatTasks for Vec<Task> {
             .join("\n")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quick_task_separates_tags_project_and_completeness_from_the_name() {
+        let quick_task = QuickTask::parse("fix #urgent login @backend bug 50% #auth").unwrap();
+
+        assert_eq!(quick_task.name, "fix login bug");
+        assert_eq!(quick_task.tags, vec!["urgent", "auth"]);
+        assert_eq!(quick_task.project.as_deref(), Some("backend"));
+        assert_eq!(quick_task.completeness, Some(50));
+        assert_eq!(quick_task.comment(), "#urgent #auth");
+    }
+
+    #[test]
+    fn quick_task_without_markers_is_only_a_name() {
+        let quick_task = QuickTask::parse("  write   release notes ").unwrap();
+
+        assert_eq!(quick_task.name, "write release notes");
+        assert!(quick_task.tags.is_empty() && quick_task.project.is_none() && quick_task.completeness.is_none());
+    }
+
+    #[test]
+    fn quick_task_completeness_must_be_a_percentage() {
+        assert_eq!(QuickTask::parse("deploy 0%").unwrap().completeness, Some(0));
+        assert_eq!(QuickTask::parse("deploy 100%").unwrap().completeness, Some(100));
+        assert!(QuickTask::parse("deploy 101%").is_err());
+        assert!(QuickTask::parse("deploy -5%").is_err());
+        // Words that are not a number followed by `%`, and bare markers, stay part of the name.
+        assert_eq!(QuickTask::parse("raise coverage to ninety% # @").unwrap().name, "raise coverage to ninety% # @");
+    }
+
+    #[test]
+    fn quick_task_belongs_to_one_project() {
+        assert!(QuickTask::parse("review @backend @frontend").is_err());
+    }
+
+    #[test]
+    fn quick_task_needs_a_name() {
+        assert!(QuickTask::parse("#urgent @backend 50%").is_err());
+        assert!(QuickTask::parse("").is_err());
+    }
+}
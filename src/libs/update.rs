@@ -1,3 +1,4 @@
+use crate::api::http::SendWithRetry;
 use crate::libs::data_storage::DataStorage;
 use chrono::{DateTime, Duration, Utc};
 use flate2::read::GzDecoder;
@@ -75,7 +76,8 @@ impl Update {
             println!("No update required, you are using the latest version!");
             return Ok(());
         }
-        let resp = self.client.get(&self.download_url.clone().unwrap()).send().await?;
+        let download_url = self.download_url.as_deref().ok_or("The latest release has no download for this platform")?;
+        let resp = self.client.get(download_url).send_with_retry().await?;
         let tar_gz_path = format!("{}.tar.gz", &self.name);
         let mut out = File::create(&tar_gz_path)?;
         let content = resp.bytes().await?;
@@ -96,7 +98,7 @@ impl Update {
             .client
             .get(&self.releases_url)
             .header("User-Agent", &self.name)
-            .send()
+            .send_with_retry()
             .await?
             .json::<Release>()
             .await?;
@@ -169,3 +171,9 @@ impl Update {
         format!("{}-v{}-{}-{}", &self.name, &self.latest_version.clone().unwrap(), arch, os)
     }
 }
+
+impl Default for Update {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,214 @@
+use super::event::Event;
+use chrono::{Local, NaiveDateTime};
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub enum EventIssue {
+    /// The event has no end although its day is already over.
+    OpenEnded(Event),
+    /// The event ends before it starts.
+    Inverted(Event),
+    /// The event ends on a later day than it starts.
+    SpansMidnight(Event),
+    /// The event starts before the previous one ends.
+    Overlapping(Event, Event),
+}
+
+impl fmt::Display for EventIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let format = |date_time: &NaiveDateTime| date_time.format("%Y-%m-%d %H:%M").to_string();
+        let format_end = |event: &Event| event.end.as_ref().map_or("-".to_string(), format);
+        match self {
+            Self::OpenEnded(event) => write!(f, "Event #{} started at {} was never closed", event.id, format(&event.start)),
+            Self::Inverted(event) => write!(
+                f,
+                "Event #{} ends at {} before it starts at {}",
+                event.id,
+                format_end(event),
+                format(&event.start)
+            ),
+            Self::SpansMidnight(event) => write!(f, "Event #{} spans midnight: {} - {}", event.id, format(&event.start), format_end(event)),
+            Self::Overlapping(previous, event) => write!(
+                f,
+                "Event #{} ({} - {}) overlaps event #{} ({} - {})",
+                event.id,
+                format(&event.start),
+                format_end(event),
+                previous.id,
+                format(&previous.start),
+                format_end(previous)
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum EventFix {
+    Update(Event),
+    Delete(i32),
+}
+
+/// Finds inconsistent events; `events` must be ordered by start.
+pub fn validate(events: &[Event]) -> Vec<EventIssue> {
+    let today = Local::now().date_naive();
+    let mut issues = vec![];
+    let mut previous: Option<&Event> = None;
+    for event in events {
+        match event.end {
+            None if event.start.date() < today => issues.push(EventIssue::OpenEnded(event.clone())),
+            Some(end) if end < event.start => issues.push(EventIssue::Inverted(event.clone())),
+            Some(end) if end.date() > event.start.date() => issues.push(EventIssue::SpansMidnight(event.clone())),
+            _ => {}
+        }
+        if let Some(previous_event) = previous {
+            if previous_event.end.is_some_and(|end| event.start < end) {
+                issues.push(EventIssue::Overlapping(previous_event.clone(), event.clone()));
+            }
+        }
+        if !matches!(previous, Some(previous_event) if event.end <= previous_event.end) {
+            previous = Some(event);
+        }
+    }
+
+    issues
+}
+
+/// Normalization strategy:
+/// - an open-ended or inverted event is closed at its own start, so it no longer adds working time;
+/// - an event spanning midnight is cut at the end of its first day;
+/// - an event fully contained in a previous closed one is deleted, a partially overlapping one starts when the
+///   previous ends; any other overlap, e.g. with an event that is still open, is left for a manual fix.
+///
+/// Several issues of the same event are combined into a single fix.
+pub fn fix(issues: &[EventIssue]) -> Vec<EventFix> {
+    let mut fixes: Vec<(i32, Option<Event>)> = vec![];
+    for issue in issues {
+        let event = match issue {
+            EventIssue::OpenEnded(event) | EventIssue::Inverted(event) | EventIssue::SpansMidnight(event) | EventIssue::Overlapping(_, event) => event,
+        };
+        let index = fixes.iter().position(|(id, _)| *id == event.id);
+        let current = match index {
+            Some(index) => match fixes[index].1.clone() {
+                Some(current) => current,
+                None => continue,
+            },
+            None => event.clone(),
+        };
+        let fixed = match issue {
+            EventIssue::OpenEnded(_) | EventIssue::Inverted(_) => Some(Event {
+                end: Some(current.start),
+                ..current
+            }),
+            EventIssue::SpansMidnight(_) => Some(Event {
+                end: current.start.date().and_hms_opt(23, 59, 59),
+                ..current
+            }),
+            EventIssue::Overlapping(previous, _) => match (previous.end, current.end) {
+                (Some(previous_end), Some(end)) if end > previous_end => Some(Event {
+                    start: previous_end,
+                    ..current
+                }),
+                (Some(_), Some(_)) if current.start >= previous.start => None,
+                _ => continue,
+            },
+        };
+        match index {
+            Some(index) => fixes[index].1 = fixed,
+            None => fixes.push((event.id, fixed)),
+        }
+    }
+
+    fixes
+        .into_iter()
+        .map(|(id, event)| match event {
+            Some(event) => EventFix::Update(event),
+            None => EventFix::Delete(id),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Timelike;
+
+    fn at(day: u32, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("2024-03-{:02} {}", day, time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn event(id: i32, start: NaiveDateTime, end: Option<NaiveDateTime>) -> Event {
+        Event {
+            id,
+            start,
+            end,
+            duration: None,
+        }
+    }
+
+    fn updated(fixes: &[EventFix]) -> Vec<(i32, NaiveDateTime, Option<NaiveDateTime>)> {
+        fixes
+            .iter()
+            .filter_map(|fix| match fix {
+                EventFix::Update(event) => Some((event.id, event.start, event.end)),
+                EventFix::Delete(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn open_ended_event_is_closed_at_its_start() {
+        let issues = validate(&[event(1, at(4, "09:00"), None)]);
+
+        assert!(matches!(issues[..], [EventIssue::OpenEnded(_)]));
+        assert_eq!(updated(&fix(&issues)), vec![(1, at(4, "09:00"), Some(at(4, "09:00")))]);
+    }
+
+    #[test]
+    fn inverted_event_is_closed_at_its_start() {
+        let issues = validate(&[event(1, at(4, "12:00"), Some(at(4, "09:00")))]);
+
+        assert!(matches!(issues[..], [EventIssue::Inverted(_)]));
+        assert_eq!(updated(&fix(&issues)), vec![(1, at(4, "12:00"), Some(at(4, "12:00")))]);
+    }
+
+    #[test]
+    fn event_spanning_midnight_is_cut_at_the_end_of_its_day() {
+        let issues = validate(&[event(1, at(4, "22:00"), Some(at(5, "01:00")))]);
+
+        assert!(matches!(issues[..], [EventIssue::SpansMidnight(_)]));
+        assert_eq!(updated(&fix(&issues)), vec![(1, at(4, "22:00"), at(4, "23:59").with_second(59))]);
+    }
+
+    #[test]
+    fn partially_overlapping_event_starts_when_the_previous_ends() {
+        let issues = validate(&[event(1, at(4, "09:00"), Some(at(4, "12:00"))), event(2, at(4, "11:00"), Some(at(4, "13:00")))]);
+
+        assert!(matches!(issues[..], [EventIssue::Overlapping(_, _)]));
+        assert_eq!(updated(&fix(&issues)), vec![(2, at(4, "12:00"), Some(at(4, "13:00")))]);
+    }
+
+    #[test]
+    fn contained_event_is_deleted() {
+        let issues = validate(&[event(1, at(4, "09:00"), Some(at(4, "17:00"))), event(2, at(4, "10:00"), Some(at(4, "11:00")))]);
+
+        assert!(matches!(fix(&issues)[..], [EventFix::Delete(2)]));
+    }
+
+    #[test]
+    fn overlap_with_an_open_event_is_left_alone() {
+        let today = Local::now().date_naive();
+        let previous = event(1, today.and_hms_opt(0, 0, 0).unwrap(), today.and_hms_opt(0, 30, 0));
+        let issues = validate(&[previous, event(2, today.and_hms_opt(0, 10, 0).unwrap(), None)]);
+
+        assert!(matches!(issues[..], [EventIssue::Overlapping(_, _)]));
+        assert!(fix(&issues).is_empty());
+    }
+
+    #[test]
+    fn issues_of_one_event_are_combined() {
+        let issues = validate(&[event(1, at(4, "09:00"), Some(at(4, "17:00"))), event(2, at(4, "16:00"), None)]);
+
+        assert_eq!(issues.len(), 2);
+        assert!(matches!(fix(&issues)[..], [EventFix::Delete(2)]));
+    }
+}
@@ -1,75 +1,608 @@
-use super::{event::FormatEvent, task::Task};
-use chrono::NaiveDate;
-use prettytable::{format, row, Table};
-use std::{collections::HashMap, error::Error};
+use super::error::KaslError;
+use super::{
+    event::{FormatDurations, FormatEvent},
+    invoice::{Invoice, NO_PROJECT},
+    journal::JournalEntry,
+    productivity::BreakDebt,
+    project::Project,
+    reminder::Reminder,
+    report::ReportSubmission,
+    secret::SecretStatus,
+    stats::{HourlyActivity, WorkStats},
+    status::Status,
+    summary::{MonthTotal, PeriodReport, ProjectTotal},
+    task::{Task, TaskNote},
+};
+use crate::api::ApiCheck;
+use chrono::{Datelike, Duration, NaiveDate};
+use clap::Args;
+use console::Term;
+use prettytable::{format, row, Cell, Row, Table};
+use std::{cmp::Ordering, collections::HashMap, error::Error};
+
+/// Narrowest width a column is truncated to when a table does not fit the terminal.
+const MIN_COLUMN_WIDTH: usize = 8;
+
+/// Sorting, column selection and row limit for list views; footer rows such as totals are kept as they are.
+#[derive(Debug, Default, Clone, Args)]
+pub struct TableOptions {
+    #[arg(
+        long,
+        value_name = "COLUMN",
+        help = "Sort rows by a column, prefix it with - to sort descending, e.g. -completeness"
+    )]
+    pub sort: Option<String>,
+    #[arg(
+        long,
+        value_name = "COLUMNS",
+        value_delimiter = ',',
+        help = "Show only these columns in this order, e.g. id,name"
+    )]
+    pub columns: Vec<String>,
+    #[arg(long, value_name = "N", help = "Show at most N rows")]
+    pub limit: Option<usize>,
+}
+
+impl TableOptions {
+    /// Index of the column titled `name`, matched case-insensitively with `_` standing for spaces.
+    fn column(titles: &[&str], name: &str) -> Result<usize, Box<dyn Error>> {
+        let name = name.trim().replace('_', " ").to_uppercase();
+        Ok(titles.iter().position(|title| *title == name).ok_or_else(|| {
+            KaslError::validation(format!(
+                "Unknown column `{}`, expected one of: {}",
+                name.to_lowercase().replace(' ', "_"),
+                titles.iter().map(|title| title.to_lowercase().replace(' ', "_")).collect::<Vec<_>>().join(", ")
+            ))
+        })?)
+    }
+}
+
+/// Numbers compare by value, everything else case-insensitively; durations are zero-padded, so they sort as text.
+fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    match text.chars().count() > width {
+        true => format!("{}…", text.chars().take(width.saturating_sub(1)).collect::<String>()),
+        false => text.to_string(),
+    }
+}
 
 pub struct View {}
 
 impl View {
-    pub fn tasks(tasks: &Vec<Task>) -> Result<(), Box<dyn Error>> {
+    /// Builds a table from plain rows, applying `options` to `rows` and the column choice to `footer` as well.
+    /// When stdout is a terminal, the widest columns are truncated until the table fits its width.
+    fn render(titles: &[&str], mut rows: Vec<Vec<String>>, footer: Vec<Vec<String>>, options: &TableOptions) -> Result<Table, Box<dyn Error>> {
+        if let Some(sort) = &options.sort {
+            let (descending, name) = match sort.strip_prefix('-') {
+                Some(name) => (true, name),
+                None => (false, sort.as_str()),
+            };
+            let column = TableOptions::column(titles, name)?;
+            rows.sort_by(|a, b| {
+                let ordering = compare_cells(&a[column], &b[column]);
+                match descending {
+                    true => ordering.reverse(),
+                    false => ordering,
+                }
+            });
+        }
+        if let Some(limit) = options.limit {
+            rows.truncate(limit);
+        }
+        let columns: Vec<usize> = match options.columns.is_empty() {
+            true => (0..titles.len()).collect(),
+            false => options
+                .columns
+                .iter()
+                .map(|name| TableOptions::column(titles, name))
+                .collect::<Result<_, _>>()?,
+        };
+        let has_footer = !footer.is_empty();
+        let project = |row: &Vec<String>| -> Vec<String> { columns.iter().map(|&column| row.get(column).cloned().unwrap_or_default()).collect() };
+        let titles: Vec<String> = columns.iter().map(|&column| titles[column].to_string()).collect();
+        let mut rows: Vec<Vec<String>> = rows.iter().chain(footer.iter()).map(project).collect();
+
+        if let Some((_, terminal_width)) = Term::stdout().size_checked() {
+            let mut widths: Vec<usize> = titles.iter().map(|title| title.chars().count()).collect();
+            for row in rows.iter() {
+                for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                    *width = (*width).max(cell.chars().count());
+                }
+            }
+            // Each column takes its content plus a space on both sides and one separator.
+            let table_width = |widths: &[usize]| widths.iter().map(|width| width + 3).sum::<usize>() + 1;
+            while table_width(&widths) > terminal_width as usize {
+                let Some((widest, width)) = widths.iter().copied().enumerate().max_by_key(|(_, width)| *width) else {
+                    break;
+                };
+                if width <= MIN_COLUMN_WIDTH {
+                    break;
+                }
+                widths[widest] = (width - (table_width(&widths) - terminal_width as usize)).max(MIN_COLUMN_WIDTH);
+            }
+            for row in rows.iter_mut() {
+                for (cell, width) in row.iter_mut().zip(widths.iter()) {
+                    *cell = truncate(cell, *width);
+                }
+            }
+        }
+
         let mut table = Table::new();
         table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
-        table.set_titles(row!["ID", "TASK ID", "NAME", "COMMENT", "COMPLETENESS"]);
+        table.set_titles(Row::new(titles.iter().map(|title| Cell::new(title)).collect()));
+        let body_len = rows.len() - footer.len();
+        for (index, row) in rows.iter().enumerate() {
+            if has_footer && index == body_len {
+                table.add_empty_row();
+            }
+            table.add_row(Row::new(row.iter().map(|cell| Cell::new(cell)).collect()));
+        }
+
+        Ok(table)
+    }
+
+    pub fn tasks(tasks: &[Task]) -> Result<(), Box<dyn Error>> {
+        Self::tasks_with(tasks, &TableOptions::default())
+    }
+
+    pub fn tasks_with(tasks: &[Task], options: &TableOptions) -> Result<(), Box<dyn Error>> {
+        Self::tasks_table(tasks, options)?.printstd();
+
+        Ok(())
+    }
+
+    pub fn tasks_table(tasks: &[Task], options: &TableOptions) -> Result<Table, Box<dyn Error>> {
+        let rows = tasks
+            .iter()
+            .map(|task| {
+                vec![
+                    task.id.unwrap_or(0).to_string(),
+                    task.task_id.unwrap_or(0).to_string(),
+                    task.name.clone(),
+                    task.comment.clone(),
+                    task.completeness.unwrap_or(100).to_string(),
+                ]
+            })
+            .collect();
+
+        Self::render(&["ID", "TASK ID", "NAME", "COMMENT", "COMPLETENESS"], rows, vec![], options)
+    }
+
+    pub fn events(events: &(Vec<FormatEvent>, String), options: &TableOptions) -> Result<(), Box<dyn Error>> {
+        Self::events_table(events, options)?.printstd();
+
+        Ok(())
+    }
+
+    pub fn events_table((events, total_duration): &(Vec<FormatEvent>, String), options: &TableOptions) -> Result<Table, Box<dyn Error>> {
+        let rows = events
+            .iter()
+            .map(|event| vec![event.id.to_string(), event.start.clone(), event.end.clone(), event.duration.clone()])
+            .collect();
+        let footer = vec![vec!["TOTAL".to_string(), "".to_string(), "".to_string(), total_duration.clone()]];
+
+        Self::render(&["ID", "START", "END", "DURATION"], rows, footer, options)
+    }
+
+    pub fn events_raw(events: &[FormatEvent], options: &TableOptions) -> Result<(), Box<dyn Error>> {
+        let rows = events
+            .iter()
+            .map(|event| vec![event.id.to_string(), event.start.clone(), event.end.clone()])
+            .collect();
+        Self::render(&["ID", "START", "END"], rows, vec![], options)?.printstd();
+
+        Ok(())
+    }
+
+    pub fn sum_table(
+        (events, total_duration, average_duration): &FormatDurations,
+        earnings: &Option<(HashMap<NaiveDate, String>, String)>,
+        options: &TableOptions,
+    ) -> Result<Table, Box<dyn Error>> {
+        let mut dates: Vec<&NaiveDate> = events.keys().collect();
+        dates.sort();
+
+        let mut rows = vec![];
+        for date in dates {
+            if let Some(day_events) = events.get(date) {
+                let mut row = vec![date.format("%-d").to_string(), day_events.1.clone()];
+                if let Some((daily_amounts, _)) = earnings {
+                    row.push(daily_amounts.get(date).cloned().unwrap_or_default());
+                }
+                rows.push(row);
+            }
+        }
+        let mut footer = vec![vec!["AVERAGE".to_string(), average_duration.clone()]];
+        footer.push(match earnings {
+            Some((_, total_amount)) => vec!["TOTAL".to_string(), total_duration.clone(), total_amount.clone()],
+            None => vec!["TOTAL".to_string(), total_duration.clone()],
+        });
+        let titles: &[&str] = match earnings {
+            Some(_) => &["DATE", "DURATION", "EARNINGS"],
+            None => &["DATE", "DURATION"],
+        };
+
+        Self::render(titles, rows, footer, options)
+    }
+
+    pub fn months_table(month_totals: &[MonthTotal]) -> Table {
+        let mut table: Table = Table::new();
+        table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+        table.set_titles(row!["MONTH", "DAYS", "DURATION", "AVERAGE"]);
+
+        let average = |duration: Duration, days: usize| match days {
+            0 => Duration::zero(),
+            _ => Duration::seconds(duration.num_seconds() / days as i64),
+        };
+        for month_total in month_totals.iter() {
+            table.add_row(row![
+                month_total.month.format("%B"),
+                month_total.days,
+                FormatEvent::format_duration(Some(month_total.duration)),
+                FormatEvent::format_duration(Some(average(month_total.duration, month_total.days)))
+            ]);
+        }
+        let days: usize = month_totals.iter().map(|month_total| month_total.days).sum();
+        let duration = month_totals.iter().fold(Duration::zero(), |total, month_total| total + month_total.duration);
+        table.add_empty_row();
+        table.add_row(row![
+            "TOTAL",
+            days,
+            FormatEvent::format_duration(Some(duration)),
+            FormatEvent::format_duration(Some(average(duration, days)))
+        ]);
+
+        table
+    }
+
+    /// Side-by-side totals of two periods with the change from `before` to `after`.
+    pub fn comparison_table(before: &PeriodReport, after: &PeriodReport) -> Table {
+        let mut table: Table = Table::new();
+        table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+        table.set_titles(row!["METRIC", before.period.title(), after.period.title(), "DELTA"]);
+
+        let duration_row = |metric: &str, before: Duration, after: Duration| {
+            row![
+                metric,
+                FormatEvent::format_duration(Some(before)),
+                FormatEvent::format_duration(Some(after)),
+                FormatEvent::format_delta(after - before)
+            ]
+        };
+        let count_row = |metric: &str, before: usize, after: usize| row![metric, before, after, format!("{:+}", after as i64 - before as i64)];
+        let percent = |value: Option<f64>| value.map_or("-".to_string(), |value| format!("{:.0}%", value));
+        let productivity_delta = match (before.stats.productivity(), after.stats.productivity()) {
+            (Some(before), Some(after)) => format!("{:+.0}%", after - before),
+            _ => "-".to_string(),
+        };
+
+        table.add_row(count_row("Working days", before.stats.days.len(), after.stats.days.len()));
+        table.add_row(duration_row("Total", before.stats.total_duration(), after.stats.total_duration()));
+        table.add_row(duration_row("Average per day", before.stats.average_duration(), after.stats.average_duration()));
+        table.add_row(row![
+            "Productivity",
+            percent(before.stats.productivity()),
+            percent(after.stats.productivity()),
+            productivity_delta
+        ]);
+        table.add_row(count_row("Pauses", before.stats.pause_count(), after.stats.pause_count()));
+        table.add_row(duration_row("Pause time", before.stats.pause_length(), after.stats.pause_length()));
+        table.add_row(count_row("Tasks", before.tasks, after.tasks));
+
+        table
+    }
+
+    pub fn invoice(invoice: &Invoice) -> Result<(), Box<dyn Error>> {
+        let mut table: Table = Table::new();
+        table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+        table.set_titles(row!["DATE", "DURATION", "AMOUNT"]);
+
+        for day in invoice.days.iter() {
+            table.add_row(row![
+                day.date.format("%-d"),
+                FormatEvent::format_duration(Some(day.duration)),
+                invoice.rate.format_amount(day.amount)
+            ]);
+        }
+        table.add_empty_row();
+        table.add_row(row!["RATE", "", invoice.rate.format_amount(invoice.rate.hourly)]);
+        table.add_row(row![
+            "TOTAL",
+            FormatEvent::format_duration(Some(invoice.total_duration())),
+            invoice.rate.format_amount(invoice.total_amount())
+        ]);
+        table.printstd();
+
+        if invoice.projects.iter().any(|project| project.name != NO_PROJECT) {
+            let mut projects_table: Table = Table::new();
+            projects_table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+            projects_table.set_titles(row!["PROJECT", "CLIENT", "DURATION", "AMOUNT"]);
+            for project in invoice.projects.iter() {
+                projects_table.add_row(row![
+                    project.name,
+                    project.client.as_deref().unwrap_or("-"),
+                    FormatEvent::format_duration(Some(project.duration)),
+                    invoice.rate.format_amount(project.amount)
+                ]);
+            }
+            println!("\nProjects:");
+            projects_table.printstd();
+        }
+
+        Ok(())
+    }
+
+    /// Renders a calendar heatmap: one column per week, one row per weekday.
+    pub fn heatmap(durations: &HashMap<NaiveDate, Duration>, from: NaiveDate, to: NaiveDate) -> Result<(), Box<dyn Error>> {
+        const SHADES: [&str; 5] = ["··", "░░", "▒▒", "▓▓", "██"];
+        let shade = |duration: Option<&Duration>| match duration.map_or(0, |duration| duration.num_hours()) {
+            0 if duration.is_none() => SHADES[0],
+            0..=1 => SHADES[1],
+            2..=3 => SHADES[2],
+            4..=5 => SHADES[3],
+            _ => SHADES[4],
+        };
+        let start = from - Duration::days(from.weekday().num_days_from_monday() as i64);
+        let weeks = (to - start).num_days() / 7 + 1;
+
+        let mut months = vec![' '; (weeks * 2) as usize];
+        let mut last_month = None;
+        for week in 0..weeks {
+            let date = (start + Duration::weeks(week)).max(from);
+            if last_month != Some(date.month()) && (week * 2 + 3) as usize <= months.len() {
+                let label: Vec<char> = date.format("%b").to_string().chars().collect();
+                months.splice((week * 2) as usize..(week * 2) as usize + label.len(), label);
+                last_month = Some(date.month());
+            }
+        }
+        println!("    {}", months.iter().collect::<String>());
+
+        for weekday in 0..7 {
+            let label = match weekday {
+                0 => "Mon",
+                2 => "Wed",
+                4 => "Fri",
+                _ => "",
+            };
+            let cells: String = (0..weeks)
+                .map(|week| {
+                    let date = start + Duration::days(week * 7 + weekday);
+                    match date < from || date > to {
+                        true => "  ",
+                        false => shade(durations.get(&date)),
+                    }
+                })
+                .collect();
+            println!("{:<4}{}", label, cells);
+        }
+        println!("\n    Less {} More (no data, <2h, <4h, <6h, 6h+)", SHADES.join(" "));
+
+        Ok(())
+    }
+
+    pub fn hourly_activity(hourly_activity: &HourlyActivity) -> Result<(), Box<dyn Error>> {
+        const BAR_WIDTH: i64 = 30;
+        let mut table: Table = Table::new();
+        table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+        table.set_titles(row!["HOUR", "ACTIVITY", "AVERAGE"]);
+
+        for hour in 0..24 {
+            let average = hourly_activity.average(hour);
+            let bar = "█".repeat((average.num_minutes() * BAR_WIDTH / 60) as usize);
+            table.add_row(row![format!("{:02}:00", hour), bar, FormatEvent::format_duration(Some(average))]);
+        }
+        table.printstd();
 
-        for (index, task) in tasks.iter().enumerate() {
+        Ok(())
+    }
+
+    pub fn stats(stats: &WorkStats, previous: &WorkStats) -> Result<(), Box<dyn Error>> {
+        let format_time = |time: Option<chrono::NaiveTime>| time.map_or("--:--".to_string(), |time| time.format("%H:%M").to_string());
+        let trend = stats.average_duration() - previous.average_duration();
+        let trend_sign = if trend < Duration::zero() { "-" } else { "+" };
+
+        let mut table: Table = Table::new();
+        table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+        table.set_titles(row!["METRIC", "VALUE"]);
+        table.add_row(row!["Working days", stats.days.len()]);
+        table.add_row(row!["Total", FormatEvent::format_duration(Some(stats.total_duration()))]);
+        table.add_row(row!["Average per day", FormatEvent::format_duration(Some(stats.average_duration()))]);
+        table.add_row(row!["Average start", format_time(stats.average_start())]);
+        table.add_row(row!["Average end", format_time(stats.average_end())]);
+        table.add_row(row!["Average pauses per day", format!("{:.1}", stats.average_pause_count())]);
+        table.add_row(row!["Average pause length", FormatEvent::format_duration(Some(stats.average_pause_length()))]);
+        table.add_row(row![
+            "Most productive weekday",
+            stats.most_productive_weekday().map_or("-".to_string(), |(weekday, average)| format!(
+                "{} ({})",
+                weekday,
+                FormatEvent::format_duration(Some(average))
+            ))
+        ]);
+        table.add_row(row!["Longest streak (days)", stats.longest_streak()]);
+        table.add_row(row![
+            "Trend vs previous period",
+            format!("{}{} per day", trend_sign, FormatEvent::format_duration(Some(trend.abs())))
+        ]);
+        table.printstd();
+
+        Ok(())
+    }
+
+    pub fn status(status: &Status, target: Duration, break_debt: Option<&BreakDebt>) -> Result<(), Box<dyn Error>> {
+        let mut table: Table = Table::new();
+        table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+        table.set_titles(row!["STATUS", "VALUE"]);
+        table.add_row(row!["State", status.state()]);
+        table.add_row(row![
+            "Workday start",
+            status.start.map_or("--:--".to_string(), |start| start.format("%H:%M").to_string())
+        ]);
+        table.add_row(row!["Working time", FormatEvent::format_duration(Some(status.duration))]);
+        table.add_row(row![
+            "Pauses",
+            format!("{} ({})", status.pauses.len(), FormatEvent::format_duration(Some(status.pause_duration())))
+        ]);
+        table.add_row(row![
+            "Productivity",
+            status.productivity().map_or("-".to_string(), |productivity| format!("{:.0}%", productivity))
+        ]);
+        table.add_row(row![
+            format!("Projected end ({})", FormatEvent::format_duration(Some(target))),
+            status.forecast(target)
+        ]);
+        if let Some(break_debt) = break_debt {
             table.add_row(row![
-                index + 1,
-                task.task_id.unwrap_or(0),
-                task.name,
-                task.comment,
-                task.completeness.unwrap_or(100)
+                "Break due",
+                format!(
+                    "{} ({} required for {} worked, {} taken)",
+                    FormatEvent::format_duration(Some(break_debt.missing())),
+                    FormatEvent::format_duration(Some(break_debt.required)),
+                    FormatEvent::format_duration(Some(break_debt.worked)),
+                    FormatEvent::format_duration(Some(break_debt.taken))
+                )
             ]);
         }
+        table.add_row(row!["Incomplete tasks", status.incomplete_tasks]);
         table.printstd();
 
         Ok(())
     }
 
-    pub fn events((events, total_duration): &(Vec<FormatEvent>, String)) -> Result<(), Box<dyn Error>> {
+    pub fn report_history(submissions: &[ReportSubmission]) -> Result<(), Box<dyn Error>> {
         let mut table: Table = Table::new();
         table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
-        table.set_titles(row!["ID", "START", "END", "DURATION"]);
+        table.set_titles(row!["SUBMITTED AT", "STATUS", "PAYLOAD"]);
 
-        for event in events.iter() {
-            table.add_row(row![event.id, event.start, event.end, event.duration]);
+        for submission in submissions.iter() {
+            let payload = serde_json::from_str::<serde_json::Value>(&submission.payload)
+                .and_then(|payload| serde_json::to_string_pretty(&payload))
+                .unwrap_or(submission.payload.clone());
+            table.add_row(row![submission.submitted_at.format("%Y-%m-%d %H:%M:%S"), submission.status, payload]);
         }
-        table.add_empty_row();
-        table.add_row(row!["TOTAL", "", "", total_duration]);
         table.printstd();
 
         Ok(())
     }
 
-    pub fn events_raw(events: &Vec<FormatEvent>) -> Result<(), Box<dyn Error>> {
+    pub fn task_notes(notes: &[TaskNote]) -> Result<(), Box<dyn Error>> {
         let mut table: Table = Table::new();
         table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
-        table.set_titles(row!["ID", "START", "END"]);
+        table.set_titles(row!["TIME", "NOTE"]);
 
-        for event in events.iter() {
-            table.add_row(row![event.id, event.start, event.end]);
+        for note in notes.iter() {
+            table.add_row(row![note.timestamp, note.text]);
         }
         table.printstd();
 
         Ok(())
     }
 
-    pub fn sum((events, total_duration, average_duration): &(HashMap<NaiveDate, (Vec<FormatEvent>, String)>, String, String)) -> Result<(), Box<dyn Error>> {
+    pub fn projects(projects: &[Project]) -> Result<(), Box<dyn Error>> {
         let mut table: Table = Table::new();
         table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
-        table.set_titles(row!["DATE", "DURATION"]);
-        let mut dates: Vec<&NaiveDate> = events.keys().collect();
-        dates.sort();
+        table.set_titles(row!["ID", "NAME", "CLIENT", "TAGS", "RATE"]);
 
-        for date in dates {
-            if let Some(day_events) = events.get(date) {
-                table.add_row(row![date.format("%-d"), day_events.1]);
-            }
+        for project in projects.iter() {
+            table.add_row(row![
+                project.id.unwrap_or_default(),
+                project.name,
+                project.client.as_deref().unwrap_or("-"),
+                project.tags.join(", "),
+                project.hourly_rate.map_or("-".to_string(), |rate| format!("{:.2}", rate))
+            ]);
+        }
+        table.printstd();
+
+        Ok(())
+    }
+
+    pub fn reminders(reminders: &[Reminder]) -> Result<(), Box<dyn Error>> {
+        let mut table: Table = Table::new();
+        table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+        table.set_titles(row!["ID", "EVERY", "MESSAGE", "COMMAND"]);
+
+        for reminder in reminders.iter() {
+            table.add_row(row![
+                reminder.id.unwrap_or_default(),
+                format!("{} min", reminder.minutes),
+                reminder.message,
+                reminder.command.as_deref().unwrap_or("-")
+            ]);
         }
-        table.add_empty_row();
-        table.add_row(row!["AVERAGE", average_duration]);
-        table.add_row(row!["TOTAL", total_duration]);
         table.printstd();
 
         Ok(())
     }
+
+    pub fn project_totals_table(project_totals: &[ProjectTotal]) -> Table {
+        let mut table: Table = Table::new();
+        table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+        table.set_titles(row!["PROJECT", "CLIENT", "DURATION", "EARNINGS"]);
+
+        for project_total in project_totals.iter() {
+            table.add_row(row![
+                project_total.name,
+                project_total.client.as_deref().unwrap_or("-"),
+                FormatEvent::format_duration(Some(project_total.duration)),
+                project_total.amount.as_deref().unwrap_or("-")
+            ]);
+        }
+
+        table
+    }
+
+    pub fn secrets(secrets: &[SecretStatus]) -> Result<(), Box<dyn Error>> {
+        let mut table: Table = Table::new();
+        table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+        table.set_titles(row!["SERVICE", "SECRET", "VALUE"]);
+
+        for secret in secrets.iter() {
+            table.add_row(row![secret.service, secret.name, secret.value]);
+        }
+        table.printstd();
+
+        Ok(())
+    }
+
+    pub fn api_checks(checks: &[ApiCheck]) -> Result<(), Box<dyn Error>> {
+        let mut table: Table = Table::new();
+        table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+        table.set_titles(row!["SERVICE", "STATUS", "LATENCY", "DETAILS"]);
+
+        for check in checks.iter() {
+            let (status, details) = match &check.result {
+                Ok(details) => ("ok", details),
+                Err(reason) => ("failed", reason),
+            };
+            table.add_row(row![check.service, status, format!("{} ms", check.latency_ms), details]);
+        }
+        table.printstd();
+
+        Ok(())
+    }
+
+    pub fn journal(entries: &[JournalEntry]) -> Result<(), Box<dyn Error>> {
+        Self::journal_table(entries).printstd();
+
+        Ok(())
+    }
+
+    pub fn journal_table(entries: &[JournalEntry]) -> Table {
+        let mut table: Table = Table::new();
+        table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+        table.set_titles(row!["TIME", "NOTE"]);
+
+        for entry in entries.iter() {
+            table.add_row(row![entry.timestamp, entry.text]);
+        }
+
+        table
+    }
 }
@@ -1,14 +1,17 @@
 use crate::commands::Cli;
-use libs::update::Update;
-use std::error::Error;
+#[cfg(test)]
+use kasl::testing;
+use kasl::{api, db, libs};
+use libs::{error, update::Update};
+use std::process;
 
-mod api;
 mod commands;
-mod db;
-mod libs;
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
+async fn main() {
     Update::show_msg().await;
-    Cli::menu().await
+    if let Err(e) = Cli::menu().await {
+        eprintln!("Error: {}", e);
+        process::exit(error::exit_code(e.as_ref()));
+    }
 }
@@ -0,0 +1,173 @@
+//! Fixtures for hermetic tests: an in-memory database and builders for workdays, pauses and tasks
+//! that write through the regular tables.
+use crate::{
+    db::{db::Db, events::Events, migrations, tasks::Tasks},
+    libs::task::Task,
+};
+use chrono::{NaiveDate, NaiveTime};
+use rusqlite::Connection;
+use std::{error::Error, rc::Rc};
+
+/// Opens a migrated in-memory database and shares it on this thread, so `Db::transaction` and every
+/// table created with `new()` use it instead of the data directory.
+pub fn memory_db() -> Result<Rc<Connection>, Box<dyn Error>> {
+    let mut conn = Connection::open_in_memory()?;
+    migrations::migrate(&mut conn)?;
+
+    Ok(Db::share(conn).conn)
+}
+
+/// A workday from `start` to `end` with pauses in between, stored as the intervals `kasl start` and `kasl end` record.
+#[derive(Debug, Clone)]
+pub struct Workday {
+    date: NaiveDate,
+    start: NaiveTime,
+    end: Option<NaiveTime>,
+    pauses: Vec<(NaiveTime, NaiveTime)>,
+}
+
+impl Workday {
+    /// An open workday; without `until` its last interval stays open, as while still working.
+    pub fn new(date: NaiveDate, start: NaiveTime) -> Self {
+        Self {
+            date,
+            start,
+            end: None,
+            pauses: vec![],
+        }
+    }
+
+    pub fn until(mut self, end: NaiveTime) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    pub fn pause(mut self, from: NaiveTime, to: NaiveTime) -> Self {
+        self.pauses.push((from, to));
+        self
+    }
+
+    /// Stores the working intervals left between the pauses.
+    pub fn insert(&self, conn: &Rc<Connection>) -> Result<(), Box<dyn Error>> {
+        let mut pauses = self.pauses.clone();
+        pauses.sort();
+        let mut events = Events::with_connection(conn.clone());
+        let mut start = self.start;
+        for (from, to) in pauses {
+            events.insert_interval(self.date.and_time(start), Some(self.date.and_time(from)))?;
+            start = to;
+        }
+
+        events.insert_interval(self.date.and_time(start), self.end.map(|end| self.date.and_time(end)))
+    }
+}
+
+/// A task recorded on a given day.
+#[derive(Debug, Clone)]
+pub struct TaskFixture {
+    task: Task,
+}
+
+impl TaskFixture {
+    pub fn new(name: &str, date: NaiveDate) -> Self {
+        Self {
+            task: Task {
+                timestamp: Some(format!("{} 12:00:00", date.format("%Y-%m-%d"))),
+                ..Task::new(name, "", Some(100))
+            },
+        }
+    }
+
+    pub fn completeness(mut self, completeness: i32) -> Self {
+        self.task.completeness = Some(completeness);
+        self
+    }
+
+    pub fn comment(mut self, comment: &str) -> Self {
+        self.task.comment = comment.to_string();
+        self
+    }
+
+    pub fn project(mut self, project_id: i32) -> Self {
+        self.task.project_id = Some(project_id);
+        self
+    }
+
+    /// Stores the task with its own date rather than the current time.
+    pub fn insert(&self, conn: &Rc<Connection>) -> Result<(), Box<dyn Error>> {
+        Tasks::with_connection(conn.clone()).restore(std::slice::from_ref(&self.task))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::events::SelectRequest,
+        libs::{event::EventGroup, task::TaskFilter},
+    };
+    use chrono::Duration;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, 4).unwrap()
+    }
+
+    fn time(hour: u32, min: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, min, 0).unwrap()
+    }
+
+    #[test]
+    fn workday_is_stored_as_intervals_between_its_pauses() {
+        let conn = memory_db().unwrap();
+        Workday::new(date(), time(9, 0))
+            .pause(time(15, 0), time(15, 30))
+            .pause(time(12, 0), time(13, 0))
+            .until(time(17, 0))
+            .insert(&conn)
+            .unwrap();
+
+        let events = Events::new().unwrap().fetch(SelectRequest::Daily, date()).unwrap();
+        let intervals: Vec<_> = events.iter().map(|event| (event.start.time(), event.end.map(|end| end.time()))).collect();
+
+        assert_eq!(
+            intervals,
+            vec![
+                (time(9, 0), Some(time(12, 0))),
+                (time(13, 0), Some(time(15, 0))),
+                (time(15, 30), Some(time(17, 0)))
+            ]
+        );
+        assert_eq!(events.merge().update_duration().total_duration().1, Duration::minutes(390));
+    }
+
+    #[test]
+    fn workday_without_an_end_stays_open() {
+        let conn = memory_db().unwrap();
+        Workday::new(date(), time(9, 0)).insert(&conn).unwrap();
+
+        let events = Events::with_connection(conn).fetch_all().unwrap();
+
+        assert_eq!(events.len(), 1);
+        assert!(events[0].end.is_none());
+    }
+
+    #[test]
+    fn task_fixture_is_stored_on_its_date_in_the_memory_db() {
+        let conn = memory_db().unwrap();
+        TaskFixture::new("Review", date())
+            .completeness(60)
+            .comment("halfway")
+            .project(2)
+            .insert(&conn)
+            .unwrap();
+
+        let tasks = Tasks::new().unwrap().fetch(TaskFilter::Date(date())).unwrap();
+
+        assert_eq!(tasks.len(), 1);
+        assert_eq!((tasks[0].name.as_str(), tasks[0].comment.as_str()), ("Review", "halfway"));
+        assert_eq!((tasks[0].completeness, tasks[0].project_id), (Some(60), Some(2)));
+        assert!(Tasks::new().unwrap().fetch(TaskFilter::Date(date().succ_opt().unwrap())).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,50 @@
+//! Period summaries computed from workdays and tasks stored through the `kasl::testing` fixtures.
+use chrono::{Duration, NaiveDate, NaiveTime};
+use kasl::{
+    libs::summary::{project_totals, Period, PeriodReport},
+    testing::{memory_db, TaskFixture, Workday},
+};
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+}
+
+fn time(hour: u32, min: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, min, 0).unwrap()
+}
+
+#[test]
+fn week_counts_the_finished_days_net_of_pauses() {
+    let conn = memory_db().unwrap();
+    Workday::new(date(4), time(9, 0))
+        .pause(time(12, 0), time(13, 0))
+        .until(time(17, 0))
+        .insert(&conn)
+        .unwrap();
+    Workday::new(date(5), time(10, 0)).until(time(16, 30)).insert(&conn).unwrap();
+    Workday::new(date(6), time(9, 0)).insert(&conn).unwrap();
+    TaskFixture::new("Review", date(4)).insert(&conn).unwrap();
+    TaskFixture::new("Deploy", date(5)).project(1).insert(&conn).unwrap();
+
+    let period = Period::week(date(6));
+    let durations = period.durations(date(6)).unwrap();
+    let report = PeriodReport::new(period, date(6)).unwrap();
+
+    assert_eq!(durations.0.len(), 2);
+    assert_eq!(durations.0[&date(4)].1, Duration::hours(7));
+    assert_eq!(durations.0[&date(5)].1, Duration::minutes(390));
+    assert_eq!(report.tasks, 2);
+}
+
+#[test]
+fn time_without_a_known_project_is_totalled_on_its_own_row() {
+    let conn = memory_db().unwrap();
+    Workday::new(date(4), time(9, 0)).until(time(17, 0)).insert(&conn).unwrap();
+    TaskFixture::new("Review", date(4)).project(7).insert(&conn).unwrap();
+
+    let durations = Period::week(date(4)).durations(date(5)).unwrap();
+    let totals = project_totals(&durations, &[], &[], None);
+
+    assert_eq!(totals.len(), 1);
+    assert_eq!((totals[0].name.as_str(), totals[0].duration), ("No project", Duration::hours(8)));
+}